@@ -0,0 +1,282 @@
+//! Deployment script for `stylus-dorg-task`.
+//! This example deploys the program with `cargo stylus` and configures it using ethers-rs.
+//!
+//! - Deploy and activate the program, unless `STYLUS_CONTRACT_ADDRESS` points at one already.
+//! - Verify activation through the `ArbWasm` precompile.
+//! - Initialize the program and apply the optional admin, market and owner config.
+//! - Print the resulting addresses and market ids.
+//!
+
+use dotenv::dotenv;
+use ethers::{
+    middleware::SignerMiddleware,
+    prelude::abigen,
+    providers::{Http, Middleware, Provider},
+    signers::{LocalWallet, Signer},
+    types::{Address, U256},
+};
+use eyre::eyre;
+use std::io::{BufRead, BufReader};
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Your private key file path.
+const PRIVATE_KEY_PATH: &str = "PRIVATE_KEY_PATH";
+
+/// Stylus RPC endpoint url.
+const RPC_URL: &str = "RPC_URL";
+
+/// Deployed program addresses.
+const STYLUS_CONTRACT_ADDRESS: &str = "STYLUS_CONTRACT_ADDRESS";
+const BASE_TOKEN_ADDRESS: &str = "BASE_TOKEN_ADDRESS";
+const QUOTE_TOKEN_ADDRESS: &str = "QUOTE_TOKEN_ADDRESS";
+
+/// Optional config applied after initialization.
+const EXCHANGE_RATE: &str = "EXCHANGE_RATE";
+const ADMIN_ADDRESSES: &str = "ADMIN_ADDRESSES";
+const ADMIN_THRESHOLD: &str = "ADMIN_THRESHOLD";
+const OWNER_ADDRESS: &str = "OWNER_ADDRESS";
+
+/// `ArbWasm` precompile, which tracks program activation.
+const ARB_WASM_ADDRESS: &str = "0x0000000000000000000000000000000000000071";
+
+abigen!(
+    ArbWasm,
+    r#"[
+        function stylusVersion() external view returns (uint16)
+        function programVersion(address program) external view returns (uint16)
+    ]"#
+);
+
+abigen!(
+    Contract,
+    r#"[
+        function initialize() external
+        function createMarket(address base_token, address quote_token, uint256 exchange_rate) external returns (uint256)
+        function addAdmin(address admin) external
+        function setAdminThreshold(uint64 threshold) external
+        function transferOwnership(address new_owner) external
+        function fetchInitializationStatus() external view returns (bool)
+        function fetchCurrentMarketIndex() external view returns (uint256)
+        function fetchMarketId(address base_token, address quote_token) external view returns (uint256)
+        function fetchOwner() external view returns (address)
+        function fetchPendingOwner() external view returns (address)
+        function fetchAdmins() external view returns (address[] memory)
+        function fetchAdminThreshold() external view returns (uint64)
+    ]"#
+);
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables
+    dotenv().ok();
+
+    let private_key_path = std::env::var(PRIVATE_KEY_PATH)
+        .map_err(|_| eyre!("No {} env var set", PRIVATE_KEY_PATH))?;
+    let private_key = read_secret_from_file(&private_key_path)?;
+    let rpc_url = std::env::var(RPC_URL).map_err(|_| eyre!("No {} env var set", RPC_URL))?;
+
+    // Set up rpc client.
+    let wallet = LocalWallet::from_str(&private_key)?;
+    let provider = Provider::<Http>::try_from(rpc_url.as_str())?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let client = Arc::new(SignerMiddleware::new(
+        provider,
+        wallet.clone().with_chain_id(chain_id),
+    ));
+
+    // Deploy and activate the program, or reuse an existing deployment.
+    let contract_address: Address = match std::env::var(STYLUS_CONTRACT_ADDRESS) {
+        Ok(address) => {
+            println!("Using Deployed Program At {address}");
+            address.parse()?
+        }
+        Err(_) => deploy(&private_key_path, &rpc_url)?,
+    };
+
+    // Verify activation.
+    let arb_wasm = ArbWasm::new(ARB_WASM_ADDRESS.parse::<Address>()?, client.clone());
+    let stylus_version = arb_wasm.stylus_version().call().await?;
+    let program_version = arb_wasm
+        .program_version(contract_address)
+        .call()
+        .await
+        .map_err(|_| eyre!("Program at {contract_address:?} is not activated"))?;
+    if program_version != stylus_version {
+        return Err(eyre!(
+            "Program activated with Stylus version {program_version}, expected {stylus_version}"
+        ));
+    }
+    println!("Program Activated With Stylus Version: {program_version}");
+
+    let contract = Contract::new(contract_address, client.clone());
+
+    // Initialize contract
+    if !contract.fetch_initialization_status().call().await? {
+        let pending_initialization_tx = contract.initialize();
+        if let Some(receipt) = pending_initialization_tx.send().await?.await? {
+            println!(
+                "Initialized Contract Successfully With Signature: {:?}",
+                receipt.transaction_hash
+            );
+        };
+    } else {
+        println!("Contract Already Initialized");
+    };
+
+    // Create the initial market
+    if let (Ok(base_token), Ok(quote_token), Ok(exchange_rate)) = (
+        std::env::var(BASE_TOKEN_ADDRESS),
+        std::env::var(QUOTE_TOKEN_ADDRESS),
+        std::env::var(EXCHANGE_RATE),
+    ) {
+        let base_token: Address = base_token.parse()?;
+        let quote_token: Address = quote_token.parse()?;
+        let exchange_rate = U256::from_dec_str(&exchange_rate)?;
+
+        let market_index = contract.fetch_current_market_index().call().await?;
+        let pending_create_market_tx =
+            contract.create_market(base_token, quote_token, exchange_rate);
+        if let Some(receipt) = pending_create_market_tx.send().await?.await? {
+            println!(
+                "Market Created Successfully With Signature: {:?}",
+                receipt.transaction_hash
+            );
+        };
+
+        let market_id = contract
+            .fetch_market_id(base_token, quote_token)
+            .call()
+            .await?;
+        println!("Market Index: {market_index}");
+        println!("Market Id: {market_id:#x}");
+    }
+
+    // Add admins
+    if let Ok(admins) = std::env::var(ADMIN_ADDRESSES) {
+        let current_admins = contract.fetch_admins().call().await?;
+        for admin in admins.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+            let admin: Address = admin.parse()?;
+            if current_admins.contains(&admin) {
+                continue;
+            }
+
+            let pending_add_admin_tx = contract.add_admin(admin);
+            if let Some(receipt) = pending_add_admin_tx.send().await?.await? {
+                println!(
+                    "Added Admin {admin:?} With Signature: {:?}",
+                    receipt.transaction_hash
+                );
+            };
+        }
+    }
+
+    // Set the admin threshold. Owner actions need admin confirmations afterwards, so the owner
+    // transfer below runs first.
+    let admin_threshold = std::env::var(ADMIN_THRESHOLD)
+        .ok()
+        .map(|threshold| threshold.parse::<u64>())
+        .transpose()?;
+
+    // Start the ownership transfer, accepted by the new owner with `acceptOwnership`.
+    if let Ok(owner) = std::env::var(OWNER_ADDRESS) {
+        let owner: Address = owner.parse()?;
+        if contract.fetch_owner().call().await? != owner {
+            let pending_transfer_tx = contract.transfer_ownership(owner);
+            if let Some(receipt) = pending_transfer_tx.send().await?.await? {
+                println!(
+                    "Started Ownership Transfer To {owner:?} With Signature: {:?}",
+                    receipt.transaction_hash
+                );
+            };
+        }
+    }
+
+    if let Some(threshold) = admin_threshold {
+        if contract.fetch_admin_threshold().call().await? != threshold {
+            let pending_threshold_tx = contract.set_admin_threshold(threshold);
+            if let Some(receipt) = pending_threshold_tx.send().await?.await? {
+                println!(
+                    "Set Admin Threshold To {threshold} With Signature: {:?}",
+                    receipt.transaction_hash
+                );
+            };
+        }
+    }
+
+    // Print the deployment
+    println!();
+    println!("Program Address: {contract_address:?}");
+    println!("Owner: {:?}", contract.fetch_owner().call().await?);
+    println!(
+        "Pending Owner: {:?}",
+        contract.fetch_pending_owner().call().await?
+    );
+    println!("Admins: {:?}", contract.fetch_admins().call().await?);
+    println!(
+        "Admin Threshold: {}",
+        contract.fetch_admin_threshold().call().await?
+    );
+
+    Ok(())
+}
+
+/// Deploy and activate the program with `cargo stylus deploy`, returning its address.
+fn deploy(private_key_path: &str, rpc_url: &str) -> eyre::Result<Address> {
+    let output = Command::new("cargo")
+        .args(["stylus", "deploy"])
+        .arg(format!("--private-key-path={private_key_path}"))
+        .arg(format!("--endpoint={rpc_url}"))
+        .output()
+        .map_err(|e| eyre!("Could not run cargo stylus: {e}"))?;
+
+    let log = strip_ansi(&format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ));
+    print!("{log}");
+    if !output.status.success() {
+        return Err(eyre!("cargo stylus deploy failed"));
+    }
+
+    // The CLI reports the address as eg. `Deploying program to address 0x...`.
+    log.lines()
+        .filter(|line| line.contains("address"))
+        .flat_map(str::split_whitespace)
+        .filter_map(|word| {
+            word.trim_end_matches(|c: char| !c.is_ascii_hexdigit())
+                .parse()
+                .ok()
+        })
+        .last()
+        .ok_or_else(|| eyre!("No program address in cargo stylus output"))
+}
+
+/// Remove terminal color codes from CLI output.
+fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip until the final byte of the escape sequence.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+fn read_secret_from_file(fpath: &str) -> eyre::Result<String> {
+    let f = std::fs::File::open(fpath)?;
+    let mut buf_reader = BufReader::new(f);
+    let mut secret = String::new();
+    buf_reader.read_line(&mut secret)?;
+    Ok(secret.trim().to_string())
+}
@@ -0,0 +1,253 @@
+//! Gas benchmark for the core operations of a deployed `stylus-dorg-task` contract.
+//! This example uses ethers-rs and should run against a devnode.
+//!
+//! - Measures gas used by market creation, liquidity deposits and withdrawals, both swap
+//!   directions, and market closing.
+//! - Prints a table and compares it with a baseline JSON, written on the first run.
+//!
+
+use dotenv::dotenv;
+use ethers::{
+    abi::Detokenize,
+    contract::builders::ContractCall,
+    middleware::SignerMiddleware,
+    prelude::abigen,
+    providers::{Http, Middleware, Provider},
+    signers::{LocalWallet, Signer},
+    types::{Address, U256},
+};
+use eyre::eyre;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Your private key file path.
+const PRIVATE_KEY_PATH: &str = "PRIVATE_KEY_PATH";
+
+/// Stylus RPC endpoint url.
+const RPC_URL: &str = "RPC_URL";
+
+/// Deployed program addresses.
+const STYLUS_CONTRACT_ADDRESS: &str = "STYLUS_CONTRACT_ADDRESS";
+const BASE_TOKEN_ADDRESS: &str = "BASE_TOKEN_ADDRESS";
+const QUOTE_TOKEN_ADDRESS: &str = "QUOTE_TOKEN_ADDRESS";
+
+/// Baseline settings.
+const GAS_BASELINE_PATH: &str = "GAS_BASELINE_PATH";
+const GAS_TOLERANCE_BPS: &str = "GAS_TOLERANCE_BPS";
+const UPDATE_GAS_BASELINE: &str = "UPDATE_GAS_BASELINE";
+
+/// Default baseline file and allowed regression (5%).
+const DEFAULT_GAS_BASELINE_PATH: &str = "gas-baseline.json";
+const DEFAULT_GAS_TOLERANCE_BPS: u64 = 500;
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+abigen!(
+    Erc20,
+    r#"[
+        function approve(address,uint256) external returns (bool)
+    ]"#
+);
+
+abigen!(
+    Contract,
+    r#"[
+        function initialize() external
+        function fetchInitializationStatus() external view returns (bool)
+        function createMarket(address base_token, address quote_token, uint256 exchange_rate) external returns (uint256)
+        function depositLiquidity(uint256 market_index, uint256 base_amount, uint256 quote_amount) external
+        function withdrawLiquidity(uint256 market_index, uint256 base_amount, uint256 quote_amount) external
+        function swapBaseTokenForQuoteToken(address base_token, address quote_token, uint256 base_amount) external
+        function swapQuoteTokenForBaseToken(address base_token, address quote_token, uint256 quote_amount) external
+        function closeMarket(uint256 market_index) external
+        function fetchCurrentMarketIndex() external view returns (uint256)
+        function fetchPairHistory(address base_token, address quote_token) external view returns (uint256[] memory)
+        function fetchMarketStatus(uint256 market_index) external view returns (uint8)
+    ]"#
+);
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables
+    dotenv().ok();
+
+    let private_key_path = std::env::var(PRIVATE_KEY_PATH)
+        .map_err(|_| eyre!("No {} env var set", PRIVATE_KEY_PATH))?;
+    let private_key = read_secret_from_file(&private_key_path)?;
+    let rpc_url = std::env::var(RPC_URL).map_err(|_| eyre!("No {} env var set", RPC_URL))?;
+    let contract_address: Address = std::env::var(STYLUS_CONTRACT_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", STYLUS_CONTRACT_ADDRESS))?
+        .parse()?;
+    let base_token_address: Address = std::env::var(BASE_TOKEN_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", BASE_TOKEN_ADDRESS))?
+        .parse()?;
+    let quote_token_address: Address = std::env::var(QUOTE_TOKEN_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", QUOTE_TOKEN_ADDRESS))?
+        .parse()?;
+    let baseline_path =
+        std::env::var(GAS_BASELINE_PATH).unwrap_or_else(|_| DEFAULT_GAS_BASELINE_PATH.to_string());
+    let tolerance_bps: u64 = std::env::var(GAS_TOLERANCE_BPS)
+        .map(|tolerance| tolerance.parse())
+        .unwrap_or(Ok(DEFAULT_GAS_TOLERANCE_BPS))?;
+    let update_baseline = std::env::var(UPDATE_GAS_BASELINE).is_ok();
+
+    // Set up rpc client.
+    let wallet = LocalWallet::from_str(&private_key)?;
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let client = Arc::new(SignerMiddleware::new(
+        provider,
+        wallet.with_chain_id(chain_id),
+    ));
+
+    let contract = Contract::new(contract_address, client.clone());
+    let base_token = Erc20::new(base_token_address, client.clone());
+    let quote_token = Erc20::new(quote_token_address, client);
+
+    // Benchmark amounts
+    let exchange_rate = U256::from(3);
+    let base_liquidity = U256::exp10(20);
+    let quote_liquidity = U256::exp10(20) * 3;
+    let base_swap = U256::exp10(18);
+    let quote_swap = U256::exp10(18) * 3;
+
+    // Initialize contract
+    if !contract.fetch_initialization_status().call().await? {
+        contract.initialize().send().await?.await?;
+    }
+
+    // Approve contract to transfer both tokens
+    base_token
+        .approve(contract_address, U256::MAX)
+        .send()
+        .await?
+        .await?;
+    quote_token
+        .approve(contract_address, U256::MAX)
+        .send()
+        .await?
+        .await?;
+
+    // Close a market left open by a previous run so the pair can be listed again.
+    if let Some(previous_index) = contract
+        .fetch_pair_history(base_token_address, quote_token_address)
+        .call()
+        .await?
+        .last()
+    {
+        if contract.fetch_market_status(*previous_index).call().await? != 4 {
+            contract.close_market(*previous_index).send().await?.await?;
+        }
+    }
+
+    // Measure each operation in order.
+    let mut gas = BTreeMap::new();
+    gas.insert(
+        "create_market",
+        gas_used(contract.create_market(base_token_address, quote_token_address, exchange_rate))
+            .await?,
+    );
+    let market_index = contract.fetch_current_market_index().call().await? - 1;
+    gas.insert(
+        "deposit_liquidity",
+        gas_used(contract.deposit_liquidity(market_index, base_liquidity, quote_liquidity)).await?,
+    );
+    gas.insert(
+        "swap_base_token_for_quote_token",
+        gas_used(contract.swap_base_token_for_quote_token(
+            base_token_address,
+            quote_token_address,
+            base_swap,
+        ))
+        .await?,
+    );
+    gas.insert(
+        "swap_quote_token_for_base_token",
+        gas_used(contract.swap_quote_token_for_base_token(
+            base_token_address,
+            quote_token_address,
+            quote_swap,
+        ))
+        .await?,
+    );
+    gas.insert(
+        "withdraw_liquidity",
+        gas_used(contract.withdraw_liquidity(market_index, base_swap, quote_swap)).await?,
+    );
+    gas.insert(
+        "close_market",
+        gas_used(contract.close_market(market_index)).await?,
+    );
+
+    // Compare with the baseline.
+    let baseline: Option<BTreeMap<String, u64>> = std::fs::read_to_string(&baseline_path)
+        .ok()
+        .map(|baseline| serde_json::from_str(&baseline))
+        .transpose()?;
+
+    println!(
+        "{:<36} {:>12} {:>12} {:>9}",
+        "operation", "gas", "baseline", "change"
+    );
+    let mut regressions = Vec::new();
+    for (operation, used) in &gas {
+        match baseline
+            .as_ref()
+            .and_then(|baseline| baseline.get(*operation))
+        {
+            Some(&expected) => {
+                let change_bps = (*used as i128 - expected as i128) * 10_000 / expected as i128;
+                println!(
+                    "{:<36} {:>12} {:>12} {:>8.2}%",
+                    operation,
+                    used,
+                    expected,
+                    change_bps as f64 / 100.0
+                );
+                if change_bps > tolerance_bps as i128 {
+                    regressions.push(*operation);
+                }
+            }
+            None => println!("{:<36} {:>12} {:>12} {:>9}", operation, used, "-", "-"),
+        }
+    }
+
+    // Write the baseline on the first run or when asked to.
+    if baseline.is_none() || update_baseline {
+        std::fs::write(&baseline_path, serde_json::to_string_pretty(&gas)? + "\n")?;
+        println!("Wrote gas baseline to {baseline_path}");
+    }
+
+    if !regressions.is_empty() && !update_baseline {
+        return Err(eyre!(
+            "gas regressions above {tolerance_bps} bps: {}",
+            regressions.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Send a transaction and return the gas it used.
+async fn gas_used<D: Detokenize>(call: ContractCall<Client, D>) -> eyre::Result<u64> {
+    let receipt = call
+        .send()
+        .await?
+        .await?
+        .ok_or_else(|| eyre!("transaction dropped"))?;
+
+    receipt
+        .gas_used
+        .map(|gas| gas.as_u64())
+        .ok_or_else(|| eyre!("receipt has no gas used"))
+}
+
+fn read_secret_from_file(fpath: &str) -> eyre::Result<String> {
+    let f = std::fs::File::open(fpath)?;
+    let mut buf_reader = BufReader::new(f);
+    let mut secret = String::new();
+    buf_reader.read_line(&mut secret)?;
+    Ok(secret.trim().to_string())
+}
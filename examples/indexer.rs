@@ -0,0 +1,343 @@
+//! Example event indexer for a deployed `stylus-dorg-task` contract.
+//! Logs are fetched with ethers-rs, decoded with alloy `sol!` events, and written as SQLite
+//! statements to stdout, eg. `cargo run --example indexer | sqlite3 markets.db`.
+//!
+//! - Indexes market creation and closing, both swap directions, and liquidity changes.
+//! - Keeps per-market aggregates: swap count, volumes, and tracked liquidity.
+//! - Follows the chain head once caught up.
+//!
+//! Market indexes and creators come from `MarketCreated`. Swaps are attributed to the market
+//! listed for their pair, so index from the deployment block.
+//!
+
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::{sol, SolEvent};
+use dotenv::dotenv;
+use ethers::{
+    providers::{Http, Middleware, Provider},
+    types::{Filter, Log, H160},
+};
+use eyre::eyre;
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+
+/// Stylus RPC endpoint url.
+const RPC_URL: &str = "RPC_URL";
+
+/// Deployed program address.
+const STYLUS_CONTRACT_ADDRESS: &str = "STYLUS_CONTRACT_ADDRESS";
+
+/// Indexer settings.
+const START_BLOCK: &str = "START_BLOCK";
+const POLL_INTERVAL_SECS: &str = "POLL_INTERVAL_SECS";
+
+/// Blocks fetched per `eth_getLogs` request.
+const BLOCK_RANGE: u64 = 10_000;
+
+/// Tables written by the indexer.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS markets (
+    market_index INTEGER PRIMARY KEY,
+    base_token TEXT NOT NULL,
+    quote_token TEXT NOT NULL,
+    exchange_rate TEXT NOT NULL,
+    creator TEXT NOT NULL,
+    created_block INTEGER NOT NULL,
+    closed_block INTEGER
+);
+CREATE TABLE IF NOT EXISTS swaps (
+    tx_hash TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    block_number INTEGER NOT NULL,
+    market_index INTEGER NOT NULL REFERENCES markets (market_index),
+    direction TEXT NOT NULL CHECK (direction IN ('base_for_quote', 'quote_for_base')),
+    amount_in TEXT NOT NULL,
+    amount_out TEXT NOT NULL,
+    PRIMARY KEY (tx_hash, log_index)
+);
+CREATE TABLE IF NOT EXISTS liquidity (
+    tx_hash TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    block_number INTEGER NOT NULL,
+    market_index INTEGER NOT NULL REFERENCES markets (market_index),
+    kind TEXT NOT NULL CHECK (kind IN ('deposit', 'withdraw', 'close')),
+    provider TEXT NOT NULL,
+    base_amount TEXT NOT NULL,
+    quote_amount TEXT NOT NULL,
+    PRIMARY KEY (tx_hash, log_index)
+);
+CREATE TABLE IF NOT EXISTS market_aggregates (
+    market_index INTEGER PRIMARY KEY REFERENCES markets (market_index),
+    swap_count INTEGER NOT NULL,
+    base_volume TEXT NOT NULL,
+    quote_volume TEXT NOT NULL,
+    base_liquidity TEXT NOT NULL,
+    quote_liquidity TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS indexer_state (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    last_block INTEGER NOT NULL
+);
+";
+
+// Define Solidity events.
+sol! {
+    event MarketCreated(address indexed base_token, address indexed quote_token, uint256 exchange_rate, uint256 indexed market_index, address creator);
+    event SwappedBaseTokenForQuoteToken(address indexed base_token, address indexed quote_token, uint256 amount_in, uint256 amount_out);
+    event SwappedQuoteTokenForBaseToken(address indexed base_token, address indexed quote_token, uint256 amount_in, uint256 amount_out);
+    event LiquidityDeposited(uint256 indexed market_index, address indexed provider, uint256 base_amount, uint256 quote_amount);
+    event LiquidityWithdrawn(uint256 indexed market_index, address indexed provider, uint256 base_amount, uint256 quote_amount);
+    event MarketClosed(uint256 indexed market_index, address indexed creator, uint256 base_amount, uint256 quote_amount);
+}
+
+/// Running totals of a market. Amounts are uint256, so they are kept here and stored as text.
+#[derive(Default)]
+struct Aggregate {
+    swap_count: u64,
+    base_volume: U256,
+    quote_volume: U256,
+    base_liquidity: U256,
+    quote_liquidity: U256,
+}
+
+/// Indexer state rebuilt from the logs.
+#[derive(Default)]
+struct Indexer {
+    /// Market currently listed for a (base token, quote token) pair.
+    pairs: HashMap<(Address, Address), U256>,
+    aggregates: HashMap<U256, Aggregate>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables
+    dotenv().ok();
+
+    let rpc_url = std::env::var(RPC_URL).map_err(|_| eyre!("No {} env var set", RPC_URL))?;
+    let contract: H160 = std::env::var(STYLUS_CONTRACT_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", STYLUS_CONTRACT_ADDRESS))?
+        .parse()?;
+    let mut from_block: u64 = std::env::var(START_BLOCK)
+        .map(|block| block.parse())
+        .unwrap_or(Ok(0))?;
+    let poll_interval = Duration::from_secs(
+        std::env::var(POLL_INTERVAL_SECS)
+            .map(|secs| secs.parse())
+            .unwrap_or(Ok(5))?,
+    );
+
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let mut out = std::io::stdout().lock();
+    writeln!(out, "{SCHEMA}")?;
+
+    let mut indexer = Indexer::default();
+
+    loop {
+        // Wait for new blocks once caught up.
+        let head = provider.get_block_number().await?.as_u64();
+        if from_block > head {
+            tokio::time::sleep(poll_interval).await;
+            continue;
+        }
+
+        // Index a range of blocks in one transaction.
+        let to_block = head.min(from_block + BLOCK_RANGE - 1);
+        let filter = Filter::new()
+            .address(contract)
+            .from_block(from_block)
+            .to_block(to_block);
+        let logs = provider.get_logs(&filter).await?;
+
+        writeln!(out, "BEGIN;")?;
+        for log in &logs {
+            for statement in indexer.index(log)? {
+                writeln!(out, "{statement}")?;
+            }
+        }
+        writeln!(
+            out,
+            "INSERT OR REPLACE INTO indexer_state (id, last_block) VALUES (0, {to_block});"
+        )?;
+        writeln!(out, "COMMIT;")?;
+        out.flush()?;
+
+        eprintln!(
+            "Indexed blocks {from_block}..={to_block}, {} logs",
+            logs.len()
+        );
+        from_block = to_block + 1;
+    }
+}
+
+impl Indexer {
+    /// Decode a log and return the statements that record it.
+    fn index(&mut self, log: &Log) -> eyre::Result<Vec<String>> {
+        let topics: Vec<B256> = log.topics.iter().map(|topic| B256::from(topic.0)).collect();
+        let Some(signature) = topics.first().copied() else {
+            return Ok(Vec::new());
+        };
+        let data = log.data.to_vec();
+        let block = log
+            .block_number
+            .map(|block| block.as_u64())
+            .unwrap_or_default();
+        let tx_hash = log.transaction_hash.unwrap_or_default();
+        let log_index = log.log_index.unwrap_or_default();
+        let event = format!("'{tx_hash:?}', {log_index}, {block}");
+
+        let mut statements = Vec::new();
+        match signature {
+            MarketCreated::SIGNATURE_HASH => {
+                let created = MarketCreated::decode_raw_log(topics, &data, true)?;
+                let market_index = created.market_index;
+                self.pairs
+                    .insert((created.base_token, created.quote_token), market_index);
+                self.aggregates.insert(market_index, Aggregate::default());
+
+                statements.push(format!(
+                    "INSERT OR IGNORE INTO markets (market_index, base_token, quote_token, exchange_rate, creator, created_block) \
+                     VALUES ({market_index}, '{}', '{}', '{}', '{}', {block});",
+                    created.base_token, created.quote_token, created.exchange_rate, created.creator
+                ));
+                statements.push(self.aggregate_statement(market_index));
+            }
+            SwappedBaseTokenForQuoteToken::SIGNATURE_HASH => {
+                let swap = SwappedBaseTokenForQuoteToken::decode_raw_log(topics, &data, true)?;
+                let market_index = self.market_of(swap.base_token, swap.quote_token)?;
+                let aggregate = self.aggregates.entry(market_index).or_default();
+                aggregate.swap_count += 1;
+                aggregate.base_volume += swap.amount_in;
+                aggregate.quote_volume += swap.amount_out;
+                aggregate.base_liquidity += swap.amount_in;
+                aggregate.quote_liquidity -= swap.amount_out;
+
+                statements.push(format!(
+                    "INSERT OR IGNORE INTO swaps (tx_hash, log_index, block_number, market_index, direction, amount_in, amount_out) \
+                     VALUES ({event}, {market_index}, 'base_for_quote', '{}', '{}');",
+                    swap.amount_in, swap.amount_out
+                ));
+                statements.push(self.aggregate_statement(market_index));
+            }
+            SwappedQuoteTokenForBaseToken::SIGNATURE_HASH => {
+                let swap = SwappedQuoteTokenForBaseToken::decode_raw_log(topics, &data, true)?;
+                let market_index = self.market_of(swap.base_token, swap.quote_token)?;
+                let aggregate = self.aggregates.entry(market_index).or_default();
+                aggregate.swap_count += 1;
+                aggregate.quote_volume += swap.amount_in;
+                aggregate.base_volume += swap.amount_out;
+                aggregate.quote_liquidity += swap.amount_in;
+                aggregate.base_liquidity -= swap.amount_out;
+
+                statements.push(format!(
+                    "INSERT OR IGNORE INTO swaps (tx_hash, log_index, block_number, market_index, direction, amount_in, amount_out) \
+                     VALUES ({event}, {market_index}, 'quote_for_base', '{}', '{}');",
+                    swap.amount_in, swap.amount_out
+                ));
+                statements.push(self.aggregate_statement(market_index));
+            }
+            LiquidityDeposited::SIGNATURE_HASH => {
+                let deposit = LiquidityDeposited::decode_raw_log(topics, &data, true)?;
+                let aggregate = self.aggregates.entry(deposit.market_index).or_default();
+                aggregate.base_liquidity += deposit.base_amount;
+                aggregate.quote_liquidity += deposit.quote_amount;
+
+                statements.push(liquidity_statement(
+                    &event,
+                    deposit.market_index,
+                    "deposit",
+                    deposit.provider,
+                    deposit.base_amount,
+                    deposit.quote_amount,
+                ));
+                statements.push(self.aggregate_statement(deposit.market_index));
+            }
+            LiquidityWithdrawn::SIGNATURE_HASH => {
+                let withdrawal = LiquidityWithdrawn::decode_raw_log(topics, &data, true)?;
+                let aggregate = self.aggregates.entry(withdrawal.market_index).or_default();
+                aggregate.base_liquidity -= withdrawal.base_amount;
+                aggregate.quote_liquidity -= withdrawal.quote_amount;
+
+                statements.push(liquidity_statement(
+                    &event,
+                    withdrawal.market_index,
+                    "withdraw",
+                    withdrawal.provider,
+                    withdrawal.base_amount,
+                    withdrawal.quote_amount,
+                ));
+                statements.push(self.aggregate_statement(withdrawal.market_index));
+            }
+            MarketClosed::SIGNATURE_HASH => {
+                let closed = MarketClosed::decode_raw_log(topics, &data, true)?;
+                self.pairs
+                    .retain(|_, market_index| *market_index != closed.market_index);
+                let aggregate = self.aggregates.entry(closed.market_index).or_default();
+                aggregate.base_liquidity = U256::ZERO;
+                aggregate.quote_liquidity = U256::ZERO;
+
+                statements.push(format!(
+                    "UPDATE markets SET closed_block = {block} WHERE market_index = {};",
+                    closed.market_index
+                ));
+                statements.push(liquidity_statement(
+                    &event,
+                    closed.market_index,
+                    "close",
+                    closed.creator,
+                    closed.base_amount,
+                    closed.quote_amount,
+                ));
+                statements.push(self.aggregate_statement(closed.market_index));
+            }
+            _ => {}
+        }
+
+        Ok(statements)
+    }
+
+    /// Get the market currently listed for a token pair.
+    fn market_of(&self, base_token: Address, quote_token: Address) -> eyre::Result<U256> {
+        self.pairs
+            .get(&(base_token, quote_token))
+            .copied()
+            .ok_or_else(|| eyre!("swap on unknown pair {base_token}/{quote_token}, index from the deployment block"))
+    }
+
+    /// Statement storing the aggregate of a market.
+    fn aggregate_statement(&self, market_index: U256) -> String {
+        let aggregate = self.aggregates.get(&market_index);
+        let aggregate = aggregate.unwrap_or(&Aggregate {
+            swap_count: 0,
+            base_volume: U256::ZERO,
+            quote_volume: U256::ZERO,
+            base_liquidity: U256::ZERO,
+            quote_liquidity: U256::ZERO,
+        });
+
+        format!(
+            "INSERT OR REPLACE INTO market_aggregates (market_index, swap_count, base_volume, quote_volume, base_liquidity, quote_liquidity) \
+             VALUES ({market_index}, {}, '{}', '{}', '{}', '{}');",
+            aggregate.swap_count,
+            aggregate.base_volume,
+            aggregate.quote_volume,
+            aggregate.base_liquidity,
+            aggregate.quote_liquidity
+        )
+    }
+}
+
+/// Statement recording a liquidity change.
+fn liquidity_statement(
+    event: &str,
+    market_index: U256,
+    kind: &str,
+    provider: Address,
+    base_amount: U256,
+    quote_amount: U256,
+) -> String {
+    format!(
+        "INSERT OR IGNORE INTO liquidity (tx_hash, log_index, block_number, market_index, kind, provider, base_amount, quote_amount) \
+         VALUES ({event}, {market_index}, '{kind}', '{provider}', '{base_amount}', '{quote_amount}');"
+    )
+}
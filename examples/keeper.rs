@@ -0,0 +1,249 @@
+//! Example keeper for the conditional orders of a deployed `stylus-dorg-task` contract.
+//! This example uses ethers-rs and should run against a devnode or testnet.
+//!
+//! - Pages through the open orders of every market with `fetchOpenOrders`.
+//! - Checks triggers with `fetchConditionalOrderTriggered` and the fill's minimum out with
+//!   `quoteExactIn`.
+//! - Values each keeper tip in ETH with `TOKEN_PRICES` and submits fills, or cancels expired
+//!   orders, only when the tip covers the estimated gas cost.
+//!
+//! `TOKEN_PRICES` lists `token=price` pairs separated by commas, the price being the wei one
+//! whole token (10^18 units) is worth. Tips in unpriced tokens are valued at 0, so their
+//! orders only execute when gas is free. `MIN_PROFIT_WEI` (default 0) is the margin a tip must
+//! clear above the gas cost, `POLL_INTERVAL_SECS` (default 12) the delay between scans.
+//!
+
+use dotenv::dotenv;
+use ethers::{
+    contract::builders::ContractCall,
+    middleware::SignerMiddleware,
+    prelude::abigen,
+    providers::{Http, Middleware, Provider},
+    signers::{LocalWallet, Signer},
+    types::{Address, BlockNumber, U256},
+};
+use eyre::eyre;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Your private key file path.
+const PRIVATE_KEY_PATH: &str = "PRIVATE_KEY_PATH";
+
+/// Stylus RPC endpoint url.
+const RPC_URL: &str = "RPC_URL";
+
+/// Deployed program address.
+const STYLUS_CONTRACT_ADDRESS: &str = "STYLUS_CONTRACT_ADDRESS";
+
+/// Keeper settings.
+const TOKEN_PRICES: &str = "TOKEN_PRICES";
+const MIN_PROFIT_WEI: &str = "MIN_PROFIT_WEI";
+const POLL_INTERVAL_SECS: &str = "POLL_INTERVAL_SECS";
+
+/// Open orders fetched per `fetchOpenOrders` call.
+const PAGE_SIZE: u64 = 50;
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+abigen!(
+    Contract,
+    r#"[
+        function fetchCurrentMarketIndex() external view returns (uint256)
+        function fetchMarketById(uint256 market_index) external view returns (address, address, uint256, uint8, bytes32, bytes32)
+        function fetchOpenOrders(uint256 market_index, uint64 offset, uint64 limit) external view returns (uint256[] memory)
+        function fetchConditionalOrder(uint256 order_id) external view returns (address, uint256, uint8, uint8, uint256, uint256, uint256, uint64, uint256)
+        function fetchConditionalOrderTriggered(uint256 order_id) external view returns (bool)
+        function fetchConditionalOrderTip(uint256 order_id) external view returns (uint256)
+        function quoteExactIn(uint256 market_index, uint8 direction, uint256 amount_in) external view returns (uint256)
+        function fillConditionalOrder(uint256 order_id) external returns (uint256)
+        function cancelExpiredOrders(uint256[] memory order_ids) external returns (uint256)
+    ]"#
+);
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables
+    dotenv().ok();
+
+    let private_key_path = std::env::var(PRIVATE_KEY_PATH)
+        .map_err(|_| eyre!("No {} env var set", PRIVATE_KEY_PATH))?;
+    let private_key = read_secret_from_file(&private_key_path)?;
+    let rpc_url = std::env::var(RPC_URL).map_err(|_| eyre!("No {} env var set", RPC_URL))?;
+    let contract_address: Address = std::env::var(STYLUS_CONTRACT_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", STYLUS_CONTRACT_ADDRESS))?
+        .parse()?;
+    let prices = parse_prices(&std::env::var(TOKEN_PRICES).unwrap_or_default())?;
+    let min_profit: U256 = std::env::var(MIN_PROFIT_WEI)
+        .map(|profit| U256::from_dec_str(&profit))
+        .unwrap_or(Ok(U256::zero()))?;
+    let poll_interval = Duration::from_secs(
+        std::env::var(POLL_INTERVAL_SECS)
+            .map(|secs| secs.parse())
+            .unwrap_or(Ok(12))?,
+    );
+
+    // Set up rpc client.
+    let wallet = LocalWallet::from_str(&private_key)?;
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let client = Arc::new(SignerMiddleware::new(
+        provider,
+        wallet.with_chain_id(chain_id),
+    ));
+
+    let contract = Contract::new(contract_address, client.clone());
+
+    loop {
+        let now = client
+            .get_block(BlockNumber::Latest)
+            .await?
+            .ok_or_else(|| eyre!("no latest block"))?
+            .timestamp;
+        let gas_price = client.get_gas_price().await?;
+
+        let market_count = contract.fetch_current_market_index().call().await?;
+        let mut market_index = U256::one();
+        while market_index < market_count {
+            let (base_token, quote_token, ..) =
+                contract.fetch_market_by_id(market_index).call().await?;
+
+            // Sort the market's open orders into expired and fillable ones.
+            let mut expired = Vec::new();
+            let mut expired_tips = U256::zero();
+            for order_id in open_orders(&contract, market_index).await? {
+                let (_, _, direction, _, _, amount_in, min_amount_out, expires_at, _) =
+                    contract.fetch_conditional_order(order_id).call().await?;
+                let token_in = if direction == 0 {
+                    base_token
+                } else {
+                    quote_token
+                };
+                let tip = contract
+                    .fetch_conditional_order_tip(order_id)
+                    .call()
+                    .await?;
+                let tip_value = value_in_wei(&prices, token_in, tip);
+
+                if expires_at != 0 && U256::from(expires_at) <= now {
+                    expired.push(order_id);
+                    expired_tips += tip_value;
+                    continue;
+                }
+                if !contract
+                    .fetch_conditional_order_triggered(order_id)
+                    .call()
+                    .await?
+                {
+                    continue;
+                }
+
+                // Skip fills the market would pay less than the minimum for. Quotes apply
+                // the taker fee, so this errs on the side of skipping maker-fee fills.
+                let amount_out = contract
+                    .quote_exact_in(market_index, direction, amount_in - tip)
+                    .call()
+                    .await;
+                if !matches!(amount_out, Ok(amount_out) if amount_out >= min_amount_out) {
+                    continue;
+                }
+
+                // Fill the order if the tip pays for the gas.
+                let fill = contract.fill_conditional_order(order_id);
+                if profitable(&fill, gas_price, tip_value, min_profit).await {
+                    match fill.send().await {
+                        Ok(pending) => {
+                            let receipt = pending.await?;
+                            println!("filled order {order_id}: {receipt:?}");
+                        }
+                        Err(err) => println!("fill of order {order_id} failed: {err}"),
+                    }
+                }
+            }
+
+            // Refund the expired orders in one call if their tips pay for the gas.
+            if !expired.is_empty() {
+                let cancel = contract.cancel_expired_orders(expired.clone());
+                if profitable(&cancel, gas_price, expired_tips, min_profit).await {
+                    match cancel.send().await {
+                        Ok(pending) => {
+                            pending.await?;
+                            println!("cancelled expired orders {expired:?}");
+                        }
+                        Err(err) => println!("cancelling orders {expired:?} failed: {err}"),
+                    }
+                }
+            }
+
+            market_index += U256::one();
+        }
+
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// Fetch the ids of every open order of a market, page by page.
+async fn open_orders(contract: &Contract<Client>, market_index: U256) -> eyre::Result<Vec<U256>> {
+    let mut order_ids = Vec::new();
+    loop {
+        let offset = order_ids.len() as u64;
+        let page = contract
+            .fetch_open_orders(market_index, offset, PAGE_SIZE)
+            .call()
+            .await?;
+        let last_page = (page.len() as u64) < PAGE_SIZE;
+        order_ids.extend(page);
+        if last_page {
+            return Ok(order_ids);
+        }
+    }
+}
+
+/// Whether a call's tip covers its estimated gas cost by at least `min_profit`.
+///
+/// Calls that fail to estimate would revert, so they are never profitable.
+async fn profitable(
+    call: &ContractCall<Client, U256>,
+    gas_price: U256,
+    tip_value: U256,
+    min_profit: U256,
+) -> bool {
+    let Ok(gas) = call.estimate_gas().await else {
+        return false;
+    };
+    let cost = gas * gas_price;
+    tip_value >= cost + min_profit
+}
+
+/// Value an amount of a token in wei at its `TOKEN_PRICES` price, 0 if it has none.
+fn value_in_wei(prices: &HashMap<Address, U256>, token: Address, amount: U256) -> U256 {
+    prices
+        .get(&token)
+        .map(|price| amount.full_mul(*price) / U256::exp10(18))
+        .and_then(|value| U256::try_from(value).ok())
+        .unwrap_or_default()
+}
+
+/// Parse `token=price` pairs separated by commas.
+fn parse_prices(prices: &str) -> eyre::Result<HashMap<Address, U256>> {
+    prices
+        .split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| {
+            let (token, price) = pair
+                .split_once('=')
+                .ok_or_else(|| eyre!("invalid {} entry {pair}", TOKEN_PRICES))?;
+            Ok((token.trim().parse()?, U256::from_dec_str(price.trim())?))
+        })
+        .collect()
+}
+
+fn read_secret_from_file(fpath: &str) -> eyre::Result<String> {
+    let f = std::fs::File::open(fpath)?;
+    let mut buf_reader = BufReader::new(f);
+    let mut secret = String::new();
+    buf_reader.read_line(&mut secret)?;
+    Ok(secret.trim().to_string())
+}
@@ -81,15 +81,15 @@ async fn main() -> eyre::Result<()> {
         Contract,
         r#"[
             function initialize() external
-            function createMarket(address base_token, address quote_token, uint256 exchange_rate, uint256 base_amount, uint256 quote_amount) external returns (uint256)
+            function createAndFund(address base_token, address quote_token, uint256 exchange_rate, uint256 base_amount, uint256 quote_amount) external returns (uint256)
             function swapBaseTokenForQuoteToken(address base_token, address quote_token, uint256 base_amount) external
             function swapQuoteTokenForBaseToken(address base_token, address quote_token, uint256 quote_amount) external
             function fetchInitializationStatus() external view returns (bool)
             function fetchCurrentMarketIndex() external view returns (uint256)
             function fetchExchangeRate(address base_token, address quote_token) external view returns (uint256)
             function fetchMarketId(address base_token, address quote_token) external view returns (uint256)
-            function fetchMarketByTokens(address base_token, address quote_token) external view returns (address, address, uint256)
-            function fetchMarketById(uint64 market_index) external view returns (address, address, uint256)
+            function fetchMarketByTokens(address base_token, address quote_token) external view returns (address, address, uint256, uint8)
+            function fetchMarketById(uint256 market_index) external view returns (address, address, uint256, uint8, bytes32, bytes32)
         ]"#
     );
 
@@ -98,7 +98,7 @@ async fn main() -> eyre::Result<()> {
     let base_amount = 100 * 10i128.pow(18);
     let quote_amount = 300 * 10i128.pow(18);
 
-    let base_exchange_amount = 1 * 10i128.pow(18);
+    let base_exchange_amount = 10i128.pow(18);
     let quote_exchange_amount = 3 * 10i128.pow(18);
 
     // Set up contracts
@@ -126,7 +126,7 @@ async fn main() -> eyre::Result<()> {
 
     // Get market from id
     let market = contract
-        .fetch_market_by_id(current_market_index.as_u64() - 1)
+        .fetch_market_by_id(current_market_index - 1)
         .call()
         .await;
     
@@ -158,8 +158,8 @@ async fn main() -> eyre::Result<()> {
             );
         };
 
-        // create market if it does not exist
-        let pending_create_market_tx = contract.create_market(
+        // create and fund market if it does not exist
+        let pending_create_market_tx = contract.create_and_fund(
             base_token_address,
             quote_token_address,
             U256::from(exchange_rate),
@@ -181,7 +181,7 @@ async fn main() -> eyre::Result<()> {
         );
 
         // Get newly created market
-        let (base_token, quote_token, rate) = contract
+        let (base_token, quote_token, rate, status) = contract
             .fetch_market_by_tokens(base_token_address, quote_token_address)
             .call()
             .await?;
@@ -190,6 +190,9 @@ async fn main() -> eyre::Result<()> {
         assert_eq!(base_token_address, base_token);
         assert_eq!(quote_token_address, quote_token);
         assert_eq!(U256::from(exchange_rate), rate);
+
+        // Assert market is active
+        assert_eq!(status, 1);
     } else {
         println!("Market already exist");
     };
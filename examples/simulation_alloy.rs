@@ -0,0 +1,370 @@
+//! Example on how to interact with a deployed `stylus-dorg-task` contract using alloy bindings.
+//! Calls are encoded with `alloy-sol-types` and reverts decoded with `stylus_dorg_task::revert`,
+//! transactions are signed and sent with an ethers-rs middleware.
+//!
+//! - User create new market and deposit liquidity.
+//! - User swap base token for quote token and back.
+//! - User hit the error paths: zero amounts, stale rates, and closed markets.
+//! - User withdraw liquidity and close the market.
+//!
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall};
+use dotenv::dotenv;
+use ethers::{
+    middleware::SignerMiddleware,
+    providers::{Http, Middleware, MiddlewareError, Provider},
+    signers::{LocalWallet, Signer},
+    types::{transaction::eip2718::TypedTransaction, TransactionRequest, H160},
+};
+use eyre::eyre;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+use std::sync::Arc;
+use stylus_dorg_task::{revert::Revert, ContractError};
+
+/// Your private key file path.
+const PRIVATE_KEY_PATH: &str = "PRIVATE_KEY_PATH";
+
+/// Stylus RPC endpoint url.
+const RPC_URL: &str = "RPC_URL";
+
+/// Deployed program addresses.
+const STYLUS_CONTRACT_ADDRESS: &str = "STYLUS_CONTRACT_ADDRESS";
+const BASE_TOKEN_ADDRESS: &str = "BASE_TOKEN_ADDRESS";
+const QUOTE_TOKEN_ADDRESS: &str = "QUOTE_TOKEN_ADDRESS";
+
+/// Market status of a closed market.
+const CLOSED: u8 = 4;
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+// Define Solidity ABIs.
+sol! {
+    #[derive(Debug)]
+    interface IErc20 {
+        function approve(address spender, uint256 amount) external returns (bool);
+    }
+
+    #[derive(Debug)]
+    interface IContract {
+        function initialize() external;
+        function fetchInitializationStatus() external view returns (bool);
+        function createMarket(address base_token, address quote_token, uint256 exchange_rate) external returns (uint256);
+        function depositLiquidity(uint256 market_index, uint256 base_amount, uint256 quote_amount) external;
+        function withdrawLiquidity(uint256 market_index, uint256 base_amount, uint256 quote_amount) external;
+        function swapBaseTokenForQuoteToken(address base_token, address quote_token, uint256 base_amount) external;
+        function swapBaseTokenForQuoteToken(address base_token, address quote_token, uint256 base_amount, uint256 expected_rate) external;
+        function swapQuoteTokenForBaseToken(address base_token, address quote_token, uint256 quote_amount) external;
+        function closeMarket(uint256 market_index) external;
+        function fetchCurrentMarketIndex() external view returns (uint256);
+        function fetchPairHistory(address base_token, address quote_token) external view returns (uint256[] memory);
+        function fetchMarketById(uint256 market_index) external view returns (address, address, uint256, uint8, bytes32, bytes32);
+        function fetchMarketReserves(uint256 market_index) external view returns (uint256, uint256);
+    }
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables
+    dotenv().ok();
+
+    let private_key_path = std::env::var(PRIVATE_KEY_PATH)
+        .map_err(|_| eyre!("No {} env var set", PRIVATE_KEY_PATH))?;
+    let private_key = read_secret_from_file(&private_key_path)?;
+    let rpc_url = std::env::var(RPC_URL).map_err(|_| eyre!("No {} env var set", RPC_URL))?;
+    let contract: Address = std::env::var(STYLUS_CONTRACT_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", STYLUS_CONTRACT_ADDRESS))?
+        .parse()?;
+    let base_token: Address = std::env::var(BASE_TOKEN_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", BASE_TOKEN_ADDRESS))?
+        .parse()?;
+    let quote_token: Address = std::env::var(QUOTE_TOKEN_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", QUOTE_TOKEN_ADDRESS))?
+        .parse()?;
+
+    // Set up wallet and rpc client.
+    let wallet = LocalWallet::from_str(&private_key)?;
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let client = Arc::new(SignerMiddleware::new(
+        provider,
+        wallet.with_chain_id(chain_id),
+    ));
+
+    // Test amount
+    let exchange_rate = U256::from(3);
+    let base_liquidity = U256::from(100) * U256::from(10).pow(U256::from(18));
+    let quote_liquidity = U256::from(300) * U256::from(10).pow(U256::from(18));
+    let base_exchange_amount = U256::from(10).pow(U256::from(18));
+    let quote_exchange_amount = U256::from(3) * U256::from(10).pow(U256::from(18));
+
+    // Initialize contract
+    let initialized = call(
+        &client,
+        contract,
+        IContract::fetchInitializationStatusCall {},
+    )
+    .await?
+    .map_err(|revert| eyre!("fetchInitializationStatus reverted: {revert}"))?
+    ._0;
+    if !initialized {
+        send(&client, contract, IContract::initializeCall {}).await?;
+        println!("Initialized Contract");
+    }
+
+    // Approve contract to transfer both tokens
+    for token in [base_token, quote_token] {
+        send(
+            &client,
+            token,
+            IErc20::approveCall {
+                spender: contract,
+                amount: U256::MAX,
+            },
+        )
+        .await?;
+    }
+    println!("Approved Base Token And Quote Token");
+
+    // Close a market left open by a previous run so the pair can be listed again
+    let history = call(
+        &client,
+        contract,
+        IContract::fetchPairHistoryCall {
+            base_token,
+            quote_token,
+        },
+    )
+    .await?
+    .map_err(|revert| eyre!("fetchPairHistory reverted: {revert}"))?
+    ._0;
+    if let Some(&previous_index) = history.last() {
+        let (_, _, _, status) = fetch_market(&client, contract, previous_index).await?;
+        if status != CLOSED {
+            send(
+                &client,
+                contract,
+                IContract::closeMarketCall {
+                    market_index: previous_index,
+                },
+            )
+            .await?;
+            println!("Closed Previous Market {previous_index}");
+        }
+    }
+
+    // Create market and deposit liquidity
+    send(
+        &client,
+        contract,
+        IContract::createMarketCall {
+            base_token,
+            quote_token,
+            exchange_rate,
+        },
+    )
+    .await?;
+    let market_index = call(&client, contract, IContract::fetchCurrentMarketIndexCall {})
+        .await?
+        .map_err(|revert| eyre!("fetchCurrentMarketIndex reverted: {revert}"))?
+        ._0
+        - U256::from(1);
+    println!("Market Created With Index {market_index}");
+
+    send(
+        &client,
+        contract,
+        IContract::depositLiquidityCall {
+            market_index,
+            base_amount: base_liquidity,
+            quote_amount: quote_liquidity,
+        },
+    )
+    .await?;
+    println!("Deposited Liquidity");
+
+    // Assert market was stored
+    let (stored_base, stored_quote, stored_rate, status) =
+        fetch_market(&client, contract, market_index).await?;
+    assert_eq!(stored_base, base_token);
+    assert_eq!(stored_quote, quote_token);
+    assert_eq!(stored_rate, exchange_rate);
+    assert_eq!(status, 1);
+
+    // Swap base token for quote token and back
+    send(
+        &client,
+        contract,
+        IContract::swapBaseTokenForQuoteToken_0Call {
+            base_token,
+            quote_token,
+            base_amount: base_exchange_amount,
+        },
+    )
+    .await?;
+    println!("Swapped Base Token For Quote Token");
+
+    send(
+        &client,
+        contract,
+        IContract::swapQuoteTokenForBaseTokenCall {
+            base_token,
+            quote_token,
+            quote_amount: quote_exchange_amount,
+        },
+    )
+    .await?;
+    println!("Swapped Quote Token For Base Token");
+
+    // Error path: zero amounts are rejected
+    let revert = call(
+        &client,
+        contract,
+        IContract::swapBaseTokenForQuoteToken_0Call {
+            base_token,
+            quote_token,
+            base_amount: U256::ZERO,
+        },
+    )
+    .await?
+    .expect_err("zero amount swap must revert");
+    assert!(matches!(
+        revert,
+        Revert::Contract(ContractError::AmountCanNotBeZero(_))
+    ));
+    println!("Zero Amount Swap Reverted With {revert}");
+
+    // Error path: swaps locked to a stale rate are rejected
+    let revert = call(
+        &client,
+        contract,
+        IContract::swapBaseTokenForQuoteToken_1Call {
+            base_token,
+            quote_token,
+            base_amount: base_exchange_amount,
+            expected_rate: exchange_rate + U256::from(1),
+        },
+    )
+    .await?
+    .expect_err("stale rate swap must revert");
+    match &revert {
+        Revert::Contract(ContractError::RateChanged(error)) => {
+            assert_eq!(error.actual, exchange_rate);
+        }
+        _ => return Err(eyre!("unexpected revert {revert}")),
+    }
+    println!("Stale Rate Swap Reverted With {revert}");
+
+    // Withdraw part of the liquidity
+    send(
+        &client,
+        contract,
+        IContract::withdrawLiquidityCall {
+            market_index,
+            base_amount: base_exchange_amount,
+            quote_amount: quote_exchange_amount,
+        },
+    )
+    .await?;
+    println!("Withdrew Liquidity");
+
+    // Close market, reclaiming the remaining reserves
+    send(
+        &client,
+        contract,
+        IContract::closeMarketCall { market_index },
+    )
+    .await?;
+    let reserves = call(
+        &client,
+        contract,
+        IContract::fetchMarketReservesCall { market_index },
+    )
+    .await?
+    .map_err(|revert| eyre!("fetchMarketReserves reverted: {revert}"))?;
+    assert_eq!((reserves._0, reserves._1), (U256::ZERO, U256::ZERO));
+    println!("Closed Market {market_index}");
+
+    // Error path: closed markets reject swaps
+    let revert = call(
+        &client,
+        contract,
+        IContract::swapBaseTokenForQuoteToken_0Call {
+            base_token,
+            quote_token,
+            base_amount: base_exchange_amount,
+        },
+    )
+    .await?
+    .expect_err("closed market swap must revert");
+    match &revert {
+        Revert::Contract(ContractError::MarketNotActive(_)) => {}
+        _ => return Err(eyre!("unexpected revert {revert}")),
+    }
+    println!("Closed Market Swap Reverted With {revert}");
+
+    Ok(())
+}
+
+/// Fetch market (base token, quote token, exchange rate, status) by index.
+async fn fetch_market(
+    client: &Arc<Client>,
+    contract: Address,
+    market_index: U256,
+) -> eyre::Result<(Address, Address, U256, u8)> {
+    let market = call(
+        client,
+        contract,
+        IContract::fetchMarketByIdCall { market_index },
+    )
+    .await?
+    .map_err(|revert| eyre!("fetchMarketById reverted: {revert}"))?;
+
+    Ok((market._0, market._1, market._2, market._3))
+}
+
+/// Simulate a call and decode its return value, or the error it reverted with.
+async fn call<C: SolCall>(
+    client: &Arc<Client>,
+    to: Address,
+    call: C,
+) -> eyre::Result<Result<C::Return, Revert>> {
+    let tx: TypedTransaction = request(to, &call).into();
+    match client.call(&tx, None).await {
+        Ok(output) => Ok(Ok(C::abi_decode_returns(&output, true)?)),
+        Err(err) => {
+            let data = err
+                .as_error_response()
+                .and_then(|response| response.as_revert_data())
+                .ok_or_else(|| eyre!("call failed without revert data: {err}"))?;
+            Ok(Err(Revert::decode(&data)))
+        }
+    }
+}
+
+/// Send a transaction and wait for its receipt.
+async fn send<C: SolCall>(client: &Arc<Client>, to: Address, call: C) -> eyre::Result<()> {
+    client
+        .send_transaction(request(to, &call), None)
+        .await?
+        .await?
+        .ok_or_else(|| eyre!("transaction dropped"))?;
+
+    Ok(())
+}
+
+/// Build a transaction request for a call.
+fn request<C: SolCall>(to: Address, call: &C) -> TransactionRequest {
+    TransactionRequest::new()
+        .to(H160::from(to.into_array()))
+        .data(call.abi_encode())
+}
+
+fn read_secret_from_file(fpath: &str) -> eyre::Result<String> {
+    let f = std::fs::File::open(fpath)?;
+    let mut buf_reader = BufReader::new(f);
+    let mut secret = String::new();
+    buf_reader.read_line(&mut secret)?;
+    Ok(secret.trim().to_string())
+}
@@ -0,0 +1,65 @@
+//!
+//! Chain info for time-dependent features.
+//!
+//! On Arbitrum `block.number` is an estimate of the L1 block number, which only
+//! moves every few L2 blocks. Block counts, eg. the commit-reveal delay, are read
+//! from the `ArbSys` precompile instead so they follow L2 blocks. `block.timestamp`
+//! already is the L2 timestamp, so TWAPs, expiries and rate decays read it directly.
+//!
+//! Build with the `local-chain` feature to read the standard `block.number` on
+//! chains without `ArbSys`, eg. a local test node.
+//!
+//! L1 contracts reach L2 through retryable tickets, which arrive with the sender
+//! offset by `L1_ALIAS_OFFSET`, so an L1 contract never shares an address with L2.
+//!
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U160},
+    block,
+    call::Call,
+    prelude::*,
+};
+
+/// Address of the `ArbSys` precompile.
+pub const ARB_SYS: Address = Address::with_last_byte(0x64);
+
+/// Offset added to the sender of L1-to-L2 messages, `0x1111000000000000000000000000000000001111`.
+pub const L1_ALIAS_OFFSET: U160 = U160::from_limbs([0x1111, 0, 0x1111_0000]);
+
+sol_interface! {
+    interface IArbSys {
+        function arbBlockNumber() external view returns (uint256);
+    }
+}
+
+/// Current L2 block number.
+///
+/// Falls back to `block.number` if `ArbSys` cannot be read.
+pub fn block_number() -> u64 {
+    if cfg!(feature = "local-chain") {
+        return block::number();
+    }
+
+    IArbSys::new(ARB_SYS)
+        .arb_block_number(Call::new())
+        .ok()
+        .and_then(|number| u64::try_from(number).ok())
+        .unwrap_or_else(block::number)
+}
+
+/// Current L2 timestamp in seconds.
+pub fn timestamp() -> u64 {
+    block::timestamp()
+}
+
+/// Address an L1 contract appears as on L2.
+pub fn apply_l1_alias(l1_address: Address) -> Address {
+    let aliased = U160::from_be_bytes(l1_address.0 .0).wrapping_add(L1_ALIAS_OFFSET);
+    Address::from(aliased.to_be_bytes())
+}
+
+/// L1 address behind an aliased L2 sender.
+pub fn undo_l1_alias(l2_address: Address) -> Address {
+    let unaliased = U160::from_be_bytes(l2_address.0 .0).wrapping_sub(L1_ALIAS_OFFSET);
+    Address::from(unaliased.to_be_bytes())
+}
@@ -0,0 +1,106 @@
+//!
+//! EIP-712 typed structured data hashing.
+//!
+//! Signature-based entry points hash their payloads against the domain below,
+//! so a signature is only valid for this contract on the chain it was made for.
+//!
+
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    call::RawCall,
+    crypto::keccak,
+    keccak_const::Keccak256,
+};
+
+use crate::{Voucher, CONTRACT_NAME};
+
+/// Version of the signing domain.
+///
+/// Kept apart from the package version so releases do not invalidate signatures.
+pub const DOMAIN_VERSION: &str = "1";
+
+/// `keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")`.
+pub const DOMAIN_TYPEHASH: [u8; 32] = Keccak256::new()
+    .update(b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")
+    .finalize();
+
+/// `keccak256("Voucher(uint256 marketIndex,address trader,uint256 exchangeRate,uint16 feeBps,uint256 maxAmountIn,uint256 nonce,uint64 deadline)")`.
+pub const VOUCHER_TYPEHASH: [u8; 32] = Keccak256::new()
+    .update(b"Voucher(uint256 marketIndex,address trader,uint256 exchangeRate,uint16 feeBps,uint256 maxAmountIn,uint256 nonce,uint64 deadline)")
+    .finalize();
+
+/// Address of the `ecrecover` precompile.
+const ECRECOVER: Address = Address::with_last_byte(1);
+
+/// Half the order of secp256k1, signatures with a higher `s` are malleable copies.
+const HALF_ORDER: U256 = U256::from_limbs([
+    0xdfe9_2f46_681b_20a0,
+    0x5d57_6e73_57a4_501d,
+    0xffff_ffff_ffff_ffff,
+    0x7fff_ffff_ffff_ffff,
+]);
+
+/// `keccak256(CONTRACT_NAME)`.
+const NAME_HASH: [u8; 32] = Keccak256::new().update(CONTRACT_NAME.as_bytes()).finalize();
+
+/// `keccak256(DOMAIN_VERSION)`.
+const VERSION_HASH: [u8; 32] = Keccak256::new()
+    .update(DOMAIN_VERSION.as_bytes())
+    .finalize();
+
+/// Compute the domain separator for a chain id and verifying contract.
+pub fn domain_separator(chain_id: u64, verifying_contract: Address) -> B256 {
+    let mut encoded = [0u8; 160];
+    encoded[..32].copy_from_slice(&DOMAIN_TYPEHASH);
+    encoded[32..64].copy_from_slice(&NAME_HASH);
+    encoded[64..96].copy_from_slice(&VERSION_HASH);
+    encoded[96..128].copy_from_slice(&U256::from(chain_id).to_be_bytes::<32>());
+    encoded[140..160].copy_from_slice(verifying_contract.as_slice());
+
+    keccak(encoded)
+}
+
+/// Compute the digest to sign for a struct hash under a domain separator.
+pub fn typed_data_hash(domain_separator: B256, struct_hash: B256) -> B256 {
+    let mut encoded = [0u8; 66];
+    encoded[..2].copy_from_slice(b"\x19\x01");
+    encoded[2..34].copy_from_slice(domain_separator.as_slice());
+    encoded[34..66].copy_from_slice(struct_hash.as_slice());
+
+    keccak(encoded)
+}
+
+/// Compute the struct hash of a discount voucher.
+pub fn voucher_hash(voucher: &Voucher) -> B256 {
+    let (market_index, trader, exchange_rate, fee_bps, max_amount_in, nonce, deadline) = *voucher;
+    let mut encoded = [0u8; 256];
+    encoded[..32].copy_from_slice(&VOUCHER_TYPEHASH);
+    encoded[32..64].copy_from_slice(&market_index.to_be_bytes::<32>());
+    encoded[76..96].copy_from_slice(trader.as_slice());
+    encoded[96..128].copy_from_slice(&exchange_rate.to_be_bytes::<32>());
+    encoded[128..160].copy_from_slice(&U256::from(fee_bps).to_be_bytes::<32>());
+    encoded[160..192].copy_from_slice(&max_amount_in.to_be_bytes::<32>());
+    encoded[192..224].copy_from_slice(&nonce.to_be_bytes::<32>());
+    encoded[224..256].copy_from_slice(&U256::from(deadline).to_be_bytes::<32>());
+
+    keccak(encoded)
+}
+
+/// Recover the signer of a digest from a 65-byte `r || s || v` signature.
+///
+/// Return None for malformed or malleable signatures and ones no address signed.
+pub fn recover(digest: B256, signature: &[u8]) -> Option<Address> {
+    let [r @ .., v] = <&[u8; 65]>::try_from(signature).ok()?;
+    if U256::from_be_slice(&r[32..]) > HALF_ORDER || !matches!(v, 27 | 28) {
+        return None;
+    }
+
+    let mut input = [0u8; 128];
+    input[..32].copy_from_slice(digest.as_slice());
+    input[63] = *v;
+    input[64..].copy_from_slice(r);
+
+    let output = RawCall::new_static().call(ECRECOVER, &input).ok()?;
+    let signer = Address::from_slice(output.get(12..32)?);
+    (signer != Address::ZERO).then_some(signer)
+}
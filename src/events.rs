@@ -0,0 +1,119 @@
+//!
+//! Events emitted by the contract.
+//!
+//! Configuration events carry the value they replace as `previous_*` next to the value set as
+//! `new_*`, so monitoring can alert on configuration drift from a single log.
+//!
+
+use alloy_sol_types::sol;
+
+sol! {
+    #![sol(all_derives)]
+
+    // Activity events
+    event Initialized(uint64 version);
+    event MarketCreated(address indexed base_token, address indexed quote_token, uint256 exchange_rate, uint256 indexed market_index, address creator);
+    event SwappedBaseTokenForQuoteToken(address indexed base_token, address indexed quote_token, uint256 amount_in, uint256 amount_out);
+    event SwappedQuoteTokenForBaseToken(address indexed base_token, address indexed quote_token, uint256 amount_in, uint256 amount_out);
+    event AdminActionProposed(uint256 indexed action_id, address indexed proposer, bytes data);
+    event AdminActionConfirmed(uint256 indexed action_id, address indexed admin);
+    event AdminActionExecuted(uint256 indexed action_id, address indexed executor);
+    event L1MessageExecuted(address indexed governor, bytes4 selector);
+    event LiquidityDeposited(uint256 indexed market_index, address indexed provider, uint256 base_amount, uint256 quote_amount);
+    event LiquidityWithdrawn(uint256 indexed market_index, address indexed provider, uint256 base_amount, uint256 quote_amount);
+    event WithdrawalRequested(uint256 indexed market_index, uint256 base_amount, uint256 quote_amount, uint64 executable_at);
+    event WithdrawalCancelled(uint256 indexed market_index, uint256 base_amount, uint256 quote_amount);
+    event VoucherRedeemed(uint256 indexed market_index, address indexed trader, uint256 nonce);
+    event SalePhaseChanged(uint256 indexed market_index, uint8 previous_phase, uint8 new_phase);
+    event SaleFinalized(uint256 indexed market_index, bool succeeded, uint256 raised);
+    event SaleRefunded(uint256 indexed market_index, address indexed buyer, uint256 quote_amount, uint256 base_amount);
+    event AirdropClaimed(uint256 indexed market_index, address indexed account, uint256 amount);
+    event AirdropSwept(uint256 indexed market_index, address indexed creator, uint256 amount);
+    event ConditionalOrderPlaced(uint256 indexed order_id, uint256 indexed market_index, address indexed maker, uint8 direction, uint8 condition, uint256 trigger_rate, uint256 amount_in, uint64 expires_at, uint256 min_fill);
+    event ConditionalOrderCancelled(uint256 indexed order_id, address indexed maker, uint256 amount_in);
+    event ConditionalOrderFilled(uint256 indexed order_id, address indexed keeper, uint256 amount_in, uint256 amount_out);
+    event OrderPartiallyFilled(uint256 indexed order_id, uint256 filled, uint256 remaining);
+    event OrdersSettled(uint256 indexed market_index, uint256 orders, uint256 base_in, uint256 quote_in, uint256 base_out, uint256 quote_out);
+    event KeeperTipped(address indexed keeper, address indexed token, uint256 amount);
+    event MarketClosed(uint256 indexed market_index, address indexed creator, uint256 base_amount, uint256 quote_amount);
+    event MarketReservesSynced(uint256 indexed market_index, uint256 base_reserve, uint256 quote_reserve);
+    event SwapCommitted(bytes32 indexed commitment, address indexed committer, uint256 deposit);
+    event SwapRevealed(bytes32 indexed commitment, address indexed committer, uint256 market_index);
+    event SwapCommitmentCancelled(bytes32 indexed commitment, address indexed committer, uint256 deposit);
+    event FeesCollected(address indexed collector, address indexed token, uint256 amount);
+    event FeePushFailed(address indexed collector, address indexed token, uint256 amount);
+    event TreasuryAction(uint8 indexed operation, address indexed token, uint256 amount, address recipient);
+    event MarketFlagged(uint256 indexed market_index, address indexed guardian, bytes32 reason_hash);
+    event MarketFlagResolved(uint256 indexed market_index, bool reinstated);
+    event ListingFeePaid(uint256 indexed market_index, address indexed payer, address token, uint256 amount);
+    event BasketCreated(uint256 indexed basket_id, address indexed base_token, address indexed creator, address[] quote_tokens, uint256[] rates, uint256 sold_cap);
+    event BasketPurchased(uint256 indexed basket_id, address indexed buyer, address indexed quote_token, uint256 quote_amount, uint256 base_amount);
+    event BasketLiquidityChanged(uint256 indexed basket_id, address indexed token, uint256 deposited, uint256 withdrawn);
+    event ShortfallCovered(uint256 indexed market_index, address indexed token, uint256 amount, address indexed guardian);
+    event Sunset(address indexed owner, uint64 sunset_at);
+    event SolvencyBreach(uint256 indexed market_index, address indexed token, uint256 liabilities, uint256 balance, address indexed reporter);
+    event CreatorFeesClaimed(address indexed creator, address indexed token, address indexed recipient, uint256 amount);
+    event EffectiveRateChanged(uint256 indexed market_index, uint256 rate, uint8 reason);
+    event SaltedMarketCreated(uint256 indexed market_index, address indexed creator, bytes32 salt, uint256 market_id);
+    event ReservesTransferred(uint256 indexed from_market, uint256 indexed to_market, address indexed token, uint256 amount);
+
+    // Configuration events
+    event OwnershipTransferStarted(address indexed previous_owner, address indexed new_owner);
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+    event MarketCreatorTransferStarted(uint256 indexed market_index, address indexed previous_creator, address indexed new_creator);
+    event MarketCreatorTransferred(uint256 indexed market_index, address indexed previous_creator, address indexed new_creator);
+    event AdminAdded(address indexed admin);
+    event AdminRemoved(address indexed admin);
+    event AdminThresholdChanged(uint64 previous_threshold, uint64 new_threshold);
+    event DeterministicMarketIdsSet(bool previous_enabled, bytes32 previous_salt, bool new_enabled, bytes32 new_salt);
+    event MarketStatusChanged(uint256 indexed market_index, uint8 previous_status, uint8 new_status);
+    event MarketExpirySet(uint256 indexed market_index, uint64 previous_expires_at, uint64 new_expires_at);
+    event RateUpdateScheduled(uint256 indexed market_index, uint256 previous_rate, uint64 previous_effective_at, uint256 new_rate, uint64 new_effective_at);
+    event MarketRateUpdated(uint256 indexed market_index, uint256 previous_rate, uint256 new_rate);
+    event RateDecaySet(uint256 indexed market_index, uint8 previous_mode, uint256 previous_start_rate, uint256 previous_floor_rate, uint64 previous_start_time, uint64 previous_duration, uint8 new_mode, uint256 new_start_rate, uint256 new_floor_rate, uint64 new_start_time, uint64 new_duration);
+    event MarketRateBoundsSet(uint256 indexed market_index, uint256 previous_min_rate, uint256 previous_max_rate, uint256 new_min_rate, uint256 new_max_rate);
+    event RateOperatorSet(uint256 indexed market_index, address previous_operator, address new_operator);
+    event WithdrawalDelaySet(uint256 indexed market_index, uint64 previous_delay, bool previous_auto_pause, uint64 new_delay, bool new_auto_pause);
+    event MinLockedLiquiditySet(uint256 previous_amount, uint256 new_amount);
+    event LockedLiquiditySet(uint256 indexed market_index, uint256 previous_amount, uint256 new_amount);
+    event PurchaseCapSet(uint256 indexed market_index, uint256 previous_cap, uint256 new_cap);
+    event PresaleSet(uint256 indexed market_index, uint64 previous_ends_at, uint256 previous_presale_rate, uint256 previous_public_rate, bytes32 previous_merkle_root, uint64 new_ends_at, uint256 new_presale_rate, uint256 new_public_rate, bytes32 new_merkle_root);
+    event PresaleAllowlistSet(uint256 indexed market_index, address indexed account, bool previous_allowed, bool new_allowed);
+    event SaleSet(uint256 indexed market_index, uint256 previous_soft_cap, uint256 previous_hard_cap, uint64 previous_ends_at, uint256 new_soft_cap, uint256 new_hard_cap, uint64 new_ends_at);
+    event AirdropSet(uint256 indexed market_index, bytes32 previous_root, uint256 previous_amount, uint64 previous_claim_deadline, bytes32 new_root, uint256 new_amount, uint64 new_claim_deadline);
+    event HookApprovalSet(address indexed hook, bool previous_approved, bool new_approved);
+    event HookGasLimitSet(uint64 previous_gas_limit, uint64 new_gas_limit);
+    event MarketComplianceRegistrySet(uint256 indexed market_index, address previous_registry, address new_registry);
+    event MarketEoaOnlySet(uint256 indexed market_index, bool previous_enabled, bool new_enabled);
+    event MarketDirectionModeSet(uint256 indexed market_index, uint8 previous_mode, uint8 new_mode);
+    event MarketContractAllowedSet(uint256 indexed market_index, address indexed account, bool previous_allowed, bool new_allowed);
+    event MarketRecipientRestrictedSet(uint256 indexed market_index, bool previous_enabled, bool new_enabled);
+    event MarketCustodianAllowedSet(uint256 indexed market_index, address indexed custodian, bool previous_allowed, bool new_allowed);
+    event MarketDecimalsRefreshed(uint256 indexed market_index, uint8 previous_base_decimals, uint8 previous_quote_decimals, uint8 new_base_decimals, uint8 new_quote_decimals);
+    event MarketHookSet(uint256 indexed market_index, address previous_hook, address new_hook);
+    event MarketRebasingSet(uint256 indexed market_index, bool previous_enabled, bool new_enabled);
+    event MarketVaultsSet(uint256 indexed market_index, bool previous_base_vault, bool previous_quote_vault, bool new_base_vault, bool new_quote_vault);
+    event MarketFeeSet(uint256 indexed market_index, uint16 previous_fee_bps, uint16 new_fee_bps);
+    event FeeTierSet(uint64 indexed tier_id, uint16 previous_fee_bps, uint16 new_fee_bps);
+    event MarketFeeTierSet(uint256 indexed market_index, uint64 previous_tier_id, uint64 new_tier_id);
+    event MarketRateLimitSet(uint256 indexed market_index, uint16 previous_max_change_bps, uint16 previous_max_window_change_bps, uint64 previous_window, uint16 new_max_change_bps, uint16 new_max_window_change_bps, uint64 new_window);
+    event MarketMakerFeeSet(uint256 indexed market_index, uint16 previous_fee_bps, uint16 new_fee_bps);
+    event MarketDepositToleranceSet(uint256 indexed market_index, bool previous_enabled, uint16 previous_tolerance_bps, bool new_enabled, uint16 new_tolerance_bps);
+    event KeeperTipBoundsSet(uint16 previous_min_bps, uint16 previous_max_bps, uint16 new_min_bps, uint16 new_max_bps);
+    event MarketKeeperTipSet(uint256 indexed market_index, uint8 previous_mode, uint256 previous_amount, uint8 new_mode, uint256 new_amount);
+    event MarketCommitRevealSet(uint256 indexed market_index, bool previous_enabled, bool new_enabled);
+    event ProtocolFeeSet(uint16 previous_fee_bps, uint16 new_fee_bps);
+    event MaxSwapAgeSet(uint64 previous_max_age, uint64 new_max_age);
+    event SolvencyConfigSet(uint16 previous_tolerance_bps, uint256 previous_bounty, uint16 new_tolerance_bps, uint256 new_bounty);
+    event TreasuryConfigSet(address previous_buyback_token, bool previous_burn, address previous_recipient, address new_buyback_token, bool new_burn, address new_recipient);
+    event FeeCollectorSet(address previous_collector, address new_collector);
+    event L1GovernorSet(address previous_governor, address new_governor);
+    event FeePushThresholdSet(address indexed token, uint256 previous_threshold, uint256 new_threshold);
+    event GuardianSet(address indexed guardian, bool previous_enabled, bool new_enabled);
+    event InsuranceShareSet(uint16 previous_share_bps, uint16 new_share_bps);
+    event CreatorFeeShareSet(uint16 previous_share_bps, uint16 new_share_bps);
+    event CreationModeSet(uint8 previous_mode, uint8 new_mode);
+    event CreatorAllowed(address indexed creator, bool previous_allowed, bool new_allowed);
+    event ListingFeeSet(address previous_token, uint256 previous_amount, address new_token, uint256 new_amount);
+    event ListingFeeWaiverSet(address indexed account, bool previous_waived, bool new_waived);
+}
@@ -4,36 +4,143 @@
 //! The following contract implements a fixed-cost token sales contract.
 //!
 //! - User can create a new market.
+//! - Market creator can deposit and withdraw liquidity.
 //! - Market stores the base token, quote token, and exchange rate.
+//! - Market creator can charge a capped fee on swaps.
+//! - Swap math rounds in favor of the reserves, quotes use the same math.
 //! - User can swap base token for quote token.
 //! - User can swap quote token for base token.
+//! - Swaps can spend the user's full token balance.
+//! - Swaps can be locked to the exchange rate the user was quoted.
+//! - Markets can require swaps to be committed before they are revealed.
+//! - Markets can be looked up by an id derived from their token pair.
+//! - Markets report an explicit status and can be paused or expire.
+//! - Market creator can close a market and reclaim its reserves.
+//! - Total value locked is tracked per token across all markets.
+//! - Swap counts and volumes are tracked per user and market.
+//! - Owner actions can require confirmations from M of N admins.
+//! - Function selectors are exposed on-chain and as constants.
+//! - Revert data decodes to `ContractError` off-chain.
+//! - Multisig, pausing, commit-reveal, the treasury and baskets can be compiled out for a smaller
+//!   program.
+//! - Protocol fees accrue to a treasury the owner can withdraw or spend on buyback-and-burn.
+//! - A share of protocol fees funds an insurance pool guardians can use to cover reserve shortfalls.
+//! - Guardians can flag mispriced or scam markets, halting swaps until the owner reviews them.
+//! - Market creation can be open, limited to allowlisted creators, or owner-only.
+//! - An optional listing fee in ETH or a fee token is charged on market creation.
+//! - Rate updates are scheduled in advance and stay within per-market bounds.
+//! - Markets can run a dutch auction, decaying their rate linearly or exponentially to a floor.
+//! - A pair can have several markets, token-addressed calls use the oldest open one.
+//! - `swap_best` routes a swap across a pair's markets, best effective rate first, `swap_split`
+//!   takes explicit per-market amounts.
+//! - Basket markets sell one base token against several quote tokens under a combined cap.
+//! - ERC-4626 vault share markets can be traded with the vault's underlying asset.
+//! - Rebasing markets track reserves as shares of the contract's balances, absorbing rebases.
+//! - ERC-1363 tokens can swap in a single `transferAndCall`.
+//! - Swaps can pay a callback contract and call it in the same transaction, eg. buy then stake.
+//! - Markets can register an approved hook contract to set fees or veto swaps and liquidity.
+//! - Protocol fees can be pushed to an external fee collector, with a pull fallback.
+//! - Markets can check traders and recipients against a compliance registry.
+//! - Markets can be limited to EOA traders, with an allowlist for specific contracts.
+//! - Configuration changes emit events carrying both the previous and the new values.
+//! - `fetch_health` reports failed external calls, auto-pauses, circuit breaker trips and the
+//!   last swap time in one call.
+//! - Market creators can delegate rate updates to a rate operator.
+//! - Block counts follow Arbitrum L2 blocks through `ArbSys`, with a `local-chain` fallback.
+//! - An L1 contract can own the program, calling through retryable tickets from its alias.
+//! - An L1 governor can send fee and pause actions through `execute_l1_message`.
+//! - Markets can delay creator withdrawals, optionally pausing trading until they execute.
+//! - A minimum of base token can be locked in markets, like an AMM's minimum liquidity.
+//! - Market creators can sign EIP-712 vouchers granting a trader a discounted rate or fee.
+//! - Markets can cap the base token each user buys, with `fetch_remaining_allocation` views.
+//! - Markets can open with an allowlisted or merkle-proved presale before the public phase.
+//! - Sales can raise up to a hard cap and refund buyers if they miss their soft cap.
+//! - Leftover inventory of a sale can be airdropped to its buyers through merkle claims.
+//! - Conditional orders escrow a swap until the market rate crosses a trigger, then keepers fill them.
+//! - Conditional orders may expire, then anyone can refund them for the keeper tip.
+//! - Keepers may fill conditional orders in parts, down to a maker-set minimum fill.
+//! - Triggered orders of a market settle together, netting opposite directions in the reserves.
+//! - Markets can charge order fills a maker fee apart from the swap fee.
+//! - Keepers earn a market-set tip, within owner bounds, for filling conditional orders.
+//! - Markets can restrict swap output to the trader or allowlisted custodians.
+//! - The protocol fee is capped at `MAX_PROTOCOL_FEE_BPS`, readable on-chain.
+//! - Cached token decimals can be refreshed after a token upgrade, with their age on-chain.
+//! - Markets record their token symbols at creation, `string` or `bytes32`, for simple frontends.
+//! - Markets can require two-sided deposits to match their rate within a tolerance.
+//! - Liquidity withdrawals and fee collection can pay a recipient apart from the caller.
+//! - Market creator rights transfer in two steps, nominate then accept.
+//! - Markets can charge an owner-managed fee tier, adjusted for every market using it at once.
+//! - An owner-set max swap age expires deadline-less swaps pending since their submission time.
+//! - `check_swap_preconditions` reports every reason a swap would fail as one bitmask.
+//! - Markets can limit how far rate updates move their rate, per update and per time window.
+//! - A terminal `sunset` disables market creation and swaps while every withdrawal path stays open.
+//! - `fetch_accounting` exports a market's tracked reserves, liabilities and actual balances for audits.
+//! - Anyone can `verify_solvency` of a market, flagging it for a bounty when balances fall short.
+//! - A configurable share of protocol fees accrues to market creators, who `claim_creator_fees`.
+//! - `fetch_markets_enriched` pages through markets with their tokens, rates, reserves, fees and volume.
+//! - `EffectiveRateChanged` publishes every change of the rate traders receive, for price feeds.
+//! - `create_market_with_salt` is idempotent per creator and salt, for retry-safe deployment scripts.
+//! - Creators rebalance inventory across their markets with `transfer_reserves`, without moving tokens.
+//! - Markets can swap one way only, eg. to sell a token without buying it back, see `DirectionMode`.
 //!
 //! The program is ABI-equivalent with Solidity, meaning you can call it from Solidity and Rust.
 //! To do this, run `cargo stylus export-abi`.
 //!
+//! Token calls come after every storage write and event of a call, and the SDK's reentrancy
+//! guard stays on, so tokens with transfer hooks can neither reenter nor observe stale state.
+//! The exception is pushing fees to the fee collector, which records what it pulled.
+//!
 //! Note: this code is a technical task and has not been audited.
 //!
 
 // Allow `cargo stylus export-abi` to generate a main function.
-#![cfg_attr(not(feature = "export-abi"), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+// The ABI export chains one iterator per public function.
+#![recursion_limit = "512"]
 extern crate alloc;
 
+pub mod chain;
+pub mod eip712;
+pub mod events;
+pub mod math;
+pub mod merkle;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod revert;
+pub mod selectors;
+
 /// Import items from the SDK. The prelude contains common traits and macros.
-use alloy_sol_types::sol;
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::{sol, sol_data, SolType};
+use events::*;
 use stylus_sdk::{
-    alloy_primitives::{Address, U256, U64},
-    call::Call,
+    abi::{Bytes, Router},
+    alloy_primitives::{Address, FixedBytes, B256, U16, U256, U64, U8},
+    block,
+    call::{self, Call},
     contract::address,
-    evm, function_selector, msg,
+    crypto, evm, function_selector, msg,
     prelude::*,
+    tx,
 };
 
+/// Contract name reported by `name()`.
+const CONTRACT_NAME: &str = "Stylus dOrg Fixed-Cost Token Sales";
+
+/// Semantic version of this build, stored at initialization.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 // Define some persistent storage using the Solidity ABI.
 // `Contract` will be the entry point.
+//
+// Storage is append-only so the contract can be upgraded behind a proxy:
+// - Never reorder, retype or remove a field; deprecate it instead.
+// - `Contract` ends with a `__gap` reserving slots for future fields. Add new fields
+//   right before it and shrink it by the slots they take, `CONTRACT_STORAGE_SLOTS` must hold.
+// - Structs only stored in mappings append new fields at the end, each entry owns its slots.
 sol_storage! {
     #[entrypoint]
     pub struct Contract {
-        // Initialization status
+        // Legacy initialization status, superseded by `initialized_version`.
         bool initialized;
         // Market Index
         uint64 market_index;
@@ -41,30 +148,870 @@ sol_storage! {
         mapping(uint64 => Market) markets;
         // Maps base token and quote token address to a market index.
         mapping(address => mapping(address => uint64)) indexes;
+        // Contract owner
+        address owner;
+        // Owner nominated by `transfer_ownership`, pending acceptance.
+        address pending_owner;
+        // Registered admin addresses.
+        address[] admins;
+        // Maps an address to its admin status.
+        mapping(address => bool) is_admin;
+        // Number of admin confirmations owner actions require, 0 disables the scheme.
+        uint64 admin_threshold;
+        // Number of proposed admin actions.
+        uint256 admin_action_count;
+        // Maps admin action id to AdminAction data.
+        mapping(uint256 => AdminAction) admin_actions;
+        // Set while an approved admin action is being executed.
+        bool executing_admin_action;
+        // Contract name stored at initialization.
+        string name;
+        // Semantic version stored at initialization.
+        string version;
+        // Chain id at initialization.
+        uint64 initialized_chain_id;
+        // Block number at initialization.
+        uint64 initialized_block;
+        // Block timestamp at initialization.
+        uint64 initialized_at;
+        // EIP-712 domain separator cached for `cached_chain_id`.
+        bytes32 cached_domain_separator;
+        // Chain id the cached domain separator was computed for.
+        uint64 cached_chain_id;
+        // Maps signer to the next unused signature nonce.
+        mapping(address => uint256) nonces;
+        // Latest initialized version, bumped by each reinitializer.
+        uint64 initialized_version;
+        // Whether new markets register a deterministic market id.
+        bool deterministic_market_ids;
+        // Salt mixed into deterministic market ids.
+        bytes32 market_id_salt;
+        // Maps deterministic market id to a market index.
+        mapping(uint256 => uint64) deterministic_indexes;
+        // Maps base token and quote token address to every market index listed for the pair.
+        mapping(address => mapping(address => uint64[])) pair_history;
+        // Maps swap commitment to SwapCommitment data.
+        mapping(bytes32 => SwapCommitment) swap_commitments;
+        // Maps token to its reserves summed across all markets.
+        mapping(address => uint256) token_tvl;
+        // Tokens listed by any market, in the order they were first listed.
+        address[] tvl_tokens;
+        // Maps token to whether it is in `tvl_tokens`.
+        mapping(address => bool) is_tvl_token;
+        // Maps user and market index to UserStats data.
+        mapping(address => mapping(uint64 => UserStats)) user_stats;
+        // Token the treasury buys back, 0 if buybacks are disabled.
+        address treasury_buyback_token;
+        // Whether bought back tokens are burned rather than forwarded.
+        bool treasury_burn;
+        // Protocol fee charged on swaps on top of the market fee, in basis points.
+        uint16 protocol_fee_bps;
+        // Address bought back tokens are forwarded to, 0 keeps them in the treasury.
+        address treasury_recipient;
+        // Maps token to the protocol fees held by the treasury.
+        mapping(address => uint256) treasury_balances;
+        // Share of protocol fees set aside for the insurance fund, in basis points.
+        uint16 insurance_share_bps;
+        // Maps token to the insurance fund balance.
+        mapping(address => uint256) insurance_balances;
+        // Maps address to guardian status, guardians may draw on the insurance fund.
+        mapping(address => bool) is_guardian;
+        // Maps market index to the reason hash of its pending flag.
+        mapping(uint64 => bytes32) market_flags;
+        // Who may create markets, see `CreationMode`.
+        uint8 creation_mode;
+        // Maps address to whether it may create markets in allowlisted mode.
+        mapping(address => bool) is_allowed_creator;
+        // Token the listing fee is paid in, 0 for ETH.
+        address listing_fee_token;
+        // Listing fee charged on market creation, 0 if disabled.
+        uint256 listing_fee;
+        // Maps address to whether it is exempt from the listing fee.
+        mapping(address => bool) listing_fee_waived;
+        // Maps market index to its rate decay, if any.
+        mapping(uint64 => RateDecay) rate_decays;
+        // Number of basket markets, basket ids start at 1.
+        uint64 basket_count;
+        // Maps basket id to Basket data.
+        mapping(uint64 => Basket) baskets;
+        // Maps market index to its shares of rebasing token balances.
+        mapping(uint64 => RebasingShares) rebasing_shares;
+        // Maps token to the shares issued to rebasing markets.
+        mapping(address => uint256) rebasing_total_shares;
+        // Maps token to the balance held for rebasing markets as of the last sync.
+        mapping(address => uint256) rebasing_pools;
+        // Maps hook contract to whether markets may register it.
+        mapping(address => bool) is_approved_hook;
+        // Maps market index to its hook contract, 0 if none.
+        mapping(uint64 => address) market_hooks;
+        // Gas forwarded to each hook call, 0 to forward all gas.
+        uint64 hook_gas_limit;
+        // Contract protocol fees are pushed to, 0 keeps them in the treasury.
+        address fee_collector;
+        // Maps token to the treasury balance that triggers a push to the fee collector.
+        mapping(address => uint256) fee_push_thresholds;
+        // Maps market index to the compliance registry its traders are checked against, 0 if none.
+        mapping(uint64 => address) compliance_registries;
+        // Maps market index and contract to whether it may trade on an EOA-only market.
+        mapping(uint64 => mapping(address => bool)) allowed_contracts;
+        // Health counters polled by `fetch_health`, sharing one slot.
+        uint64 failed_external_calls;
+        uint64 auto_pauses;
+        uint64 circuit_breaker_trips;
+        uint64 last_swap_at;
+        // Maps market index to the address that may update its rate besides the creator.
+        mapping(uint64 => address) rate_operators;
+        // L1 contract whose aliased address may send `execute_l1_message`, 0 if none.
+        address l1_governor;
+        // Maps market index to its withdrawal delay and pending creator withdrawal.
+        mapping(uint64 => WithdrawalQueue) withdrawal_queues;
+        // Base token locked in markets at creation, see `set_min_locked_liquidity`.
+        uint256 min_locked_liquidity;
+        // Maps market index to the base token that can not leave it while it is open.
+        mapping(uint64 => uint256) locked_liquidity;
+        // Maps market creator to its redeemed voucher nonces.
+        mapping(address => mapping(uint256 => bool)) used_voucher_nonces;
+        // Maps market index to the base token each user may buy from it, 0 if uncapped.
+        mapping(uint64 => uint256) purchase_caps;
+        // Maps market index to its presale.
+        mapping(uint64 => Presale) presales;
+        // Maps market index to its capped sale.
+        mapping(uint64 => Sale) sales;
+        // Maps market index to the airdrop of its leftover base token.
+        mapping(uint64 => Airdrop) airdrops;
+        // Number of conditional orders placed, the id of the latest one.
+        uint256 conditional_order_count;
+        // Maps order id to its conditional order.
+        mapping(uint256 => ConditionalOrder) conditional_orders;
+        // Maps market index to the market fee its order fills pay.
+        mapping(uint64 => MakerFee) maker_fees;
+        // Bounds of keeper tips in basis points of the amount an execution moves.
+        uint16 min_keeper_tip_bps;
+        uint16 max_keeper_tip_bps;
+        // Maps market index to the tip keepers earn executing its orders.
+        mapping(uint64 => KeeperTip) keeper_tips;
+        // Maps market index to whether swaps only pay the trader or an allowed custodian.
+        mapping(uint64 => bool) recipient_restricted;
+        // Maps market index to the custodians that may receive swap output on restricted markets.
+        mapping(uint64 => mapping(address => bool)) allowed_custodians;
+        // Maps market index to when its token decimals were last cached.
+        mapping(uint64 => uint64) decimals_cached_at;
+        // Maps market index to the symbols of its tokens, read at creation.
+        mapping(uint64 => bytes32) base_symbols;
+        mapping(uint64 => bytes32) quote_symbols;
+        // Maps market index to how far two-sided deposits may stray from its rate.
+        mapping(uint64 => DepositTolerance) deposit_tolerances;
+        // Maps market index to the creator nominated to take it over.
+        mapping(uint64 => address) pending_creators;
+        // Maps tier id to its fee tier template.
+        mapping(uint64 => FeeTier) fee_tiers;
+        // Maps market index to the fee tier it charges, 0 for its own fee.
+        mapping(uint64 => uint64) market_fee_tiers;
+        // Seconds a swap submitted without a deadline stays executable, 0 if unbounded.
+        uint64 max_swap_age;
+        // Maps market index to how far rate updates may move its rate.
+        mapping(uint64 => RateLimit) rate_limits;
+        // Whether the contract was decommissioned, see `sunset`.
+        bool sunset;
+        // Shortfall of a token's balance below its liabilities `verify_solvency` tolerates.
+        uint16 solvency_tolerance_bps;
+        // ETH paid from the treasury to callers of `verify_solvency` that flag a market.
+        uint256 solvency_bounty;
+        // Share of protocol fees accrued to the market creator, in basis points.
+        uint16 creator_fee_share_bps;
+        // Maps creator to token to the protocol fees it may claim.
+        mapping(address => mapping(address => uint256)) creator_fee_balances;
+        // Maps token to the protocol fees accrued to all creators.
+        mapping(address => uint256) creator_fee_totals;
+        // Maps market index to the volume swapped through it.
+        mapping(uint64 => MarketVolume) market_volumes;
+        // Maps market index to the effective rate last published in `EffectiveRateChanged`.
+        mapping(uint64 => uint256) effective_rates;
+        // Maps salted market id to the market index created with it.
+        mapping(uint256 => uint64) salted_indexes;
+        // Maps market index to the directions it swaps in, see `DirectionMode`.
+        mapping(uint64 => uint8) direction_modes;
+        // Maps salted market id to the exchange rate its market was created at.
+        mapping(uint256 => uint256) salted_rates;
+        // Maps token to whether `verify_solvency` paid for its shortfall, until `resolve_flag`.
+        mapping(address => bool) breached_tokens;
+        // Maps market index to the ids of its open conditional orders, see `fetch_open_orders`.
+        mapping(uint64 => uint256[]) open_orders;
+        // Reserved slots for future fields.
+        uint256[168] __gap;
     }
 
-    // Market consists of a base token, quote token, and market rate
+    // Market consists of a base token, quote token, and market rate.
+    // Fields are ordered so small values share slots, see `MARKET_STORAGE_SLOTS`:
+    // - slot 0: base_token, expires_at, fee_bps, status, commit_reveal (read on every swap)
+    // - slot 1: quote_token, base_decimals, quote_decimals, base_vault, quote_vault, rebasing,
+    //   eoa_only
+    // - slot 2: creator, pending_rate_at
+    // - slots 3 to 6: exchange_rate, market_id, base_reserve, quote_reserve
+    // - slots 7 to 9: min_rate, max_rate, pending_rate
     pub struct Market {
         address base_token;     // eg. ETH in ETH/USDT
+        uint64 expires_at;      // eg. unix timestamp after which swaps stop, 0 if never
+        uint16 fee_bps;         // eg. 30 for a 0.3% fee kept in the output reserve
+        uint8 status;           // eg. MarketStatus::Active
+        bool commit_reveal;     // eg. true if swaps must be committed before they are revealed
         address quote_token;    // eg. USDT in ETH/USDT
+        uint8 base_decimals;    // eg. 18 for ETH, cached at creation
+        uint8 quote_decimals;   // eg. 6 for USDT, cached at creation
+        bool base_vault;        // eg. true if base_token is an ERC-4626 vault swappable via its asset
+        bool quote_vault;       // eg. true if quote_token is an ERC-4626 vault swappable via its asset
+        bool rebasing;          // eg. true if reserves are shares of the contract's balance
+        bool eoa_only;          // eg. true if only EOAs and allowed contracts may trade
+        address creator;        // eg. the market creator
+        uint64 pending_rate_at; // eg. unix timestamp the pending rate takes effect at, 0 if none
         uint256 exchange_rate;  // eg. ETH/USDT exchange is 3500
+        uint256 market_id;      // eg. keccak256(base_token, quote_token, salt), 0 if not registered
+        uint256 base_reserve;   // eg. base token held for this market
+        uint256 quote_reserve;  // eg. quote token held for this market
+        uint256 min_rate;       // eg. 3000, rate updates may not go below it
+        uint256 max_rate;       // eg. 4000, rate updates may not go above it, 0 if unbounded
+        uint256 pending_rate;   // eg. 3600, the scheduled rate
+    }
+
+    // Swap commitment consists of the committer, its escrowed deposit, and commit block
+    pub struct SwapCommitment {
+        address committer;
+        uint64 committed_block;  // eg. the swap can be revealed after this block
+        uint256 deposit;         // eg. ETH refunded once the swap is revealed
+    }
+
+    // Basket consists of a base token sold at fixed rates against several quote tokens
+    pub struct Basket {
+        address base_token;                       // eg. PROJECT
+        address creator;
+        uint256 base_reserve;                     // eg. base token left to sell
+        uint256 sold;                             // eg. base token sold so far
+        uint256 sold_cap;                         // eg. base token that may be sold, 0 if unlimited
+        address[] quote_tokens;                   // eg. USDC, USDT and DAI
+        mapping(address => uint256) rates;        // Maps quote token to its rate, 0 if not accepted
+        mapping(address => uint256) quote_reserves;  // Maps quote token to its proceeds
+    }
+
+    // Rebasing shares consist of a market's claim on the contract's rebasing token balances
+    pub struct RebasingShares {
+        uint256 base_shares;
+        uint256 quote_shares;
+    }
+
+    // Rate decay consists of a dutch auction from a start rate down to a floor rate
+    pub struct RateDecay {
+        uint8 mode;           // eg. DecayMode::Linear
+        uint64 start_time;    // eg. unix timestamp the decay starts at
+        uint64 duration;      // eg. seconds until the floor rate is reached
+        uint256 start_rate;
+        uint256 floor_rate;
+    }
+
+    // User stats consist of a user's swap count and volumes in one market
+    pub struct UserStats {
+        uint64 swap_count;
+        uint256 base_in;    // eg. base token sold to the market
+        uint256 quote_in;   // eg. quote token sold to the market
+        uint256 base_out;   // eg. base token bought from the market
+        uint256 quote_out;  // eg. quote token bought from the market
+    }
+
+    // Withdrawal queue consists of a market's withdrawal delay and its pending creator withdrawal
+    pub struct WithdrawalQueue {
+        uint64 delay;          // eg. seconds a creator withdrawal waits, 0 if withdrawals are instant
+        bool auto_pause;       // eg. true if requesting a withdrawal pauses the market
+        bool paused;           // eg. true if the pending withdrawal paused the market
+        uint64 executable_at;  // eg. unix timestamp the pending withdrawal executes at, 0 if none
+        uint256 base_amount;
+        uint256 quote_amount;
+    }
+
+    // Presale consists of an allowlisted window at its own rate before the public phase
+    pub struct Presale {
+        uint64 ends_at;                     // eg. unix timestamp the public phase starts at, 0 if none
+        bool public_started;                // eg. true once the public rate was stored
+        uint256 presale_rate;
+        uint256 public_rate;                // eg. the rate of the public phase, 0 to keep the market rate
+        bytes32 merkle_root;                // eg. root of the address tree of presale traders, 0 if none
+        mapping(address => bool) allowed;   // Maps trader to presale access
+    }
+
+    // Sale consists of the caps and deadline of a market's fundraise and its contributions
+    pub struct Sale {
+        uint8 status;                               // eg. `SaleStatus`
+        uint64 ends_at;                             // eg. unix timestamp the sale can be finalized at
+        uint256 soft_cap;                           // eg. quote token to raise for the sale to succeed
+        uint256 hard_cap;                           // eg. most quote token the sale raises, 0 if unlimited
+        uint256 raised;                             // eg. quote token escrowed from buyers
+        mapping(address => uint256) contributions;  // Maps buyer to the quote token it paid
+        mapping(address => uint256) purchased;      // Maps buyer to the base token it bought
+    }
+
+    // Airdrop consists of leftover base token of a sale claimable by the accounts of a merkle tree
+    pub struct Airdrop {
+        bytes32 root;                           // eg. root of the (address, uint256) tree of claims
+        uint64 claim_deadline;                  // eg. unix timestamp claims close at
+        bool swept;                             // eg. true once the unclaimed base token was swept
+        uint256 amount;                         // eg. base token set aside for claims
+        uint256 claimed;
+        mapping(address => bool) claimed_by;    // Maps account to whether it claimed
+    }
+
+    // Conditional order consists of a swap escrowed by its maker until the market rate crosses a trigger
+    pub struct ConditionalOrder {
+        address maker;
+        uint64 market_index;
+        uint8 direction;            // eg. `SwapDirection`
+        uint8 condition;            // eg. `TriggerCondition`
+        uint256 trigger_rate;
+        uint256 amount_in;          // eg. input token escrowed, 0 once filled or cancelled
+        uint256 min_amount_out;
+        uint64 expires_at;          // eg. 0 if the order never expires
+        uint64 open_position;       // eg. 1-based position in the market's open orders
+        uint256 min_fill;           // eg. 0 if the order only fills whole
+    }
+
+    // Maker fee consists of the market fee order fills pay instead of the swap fee
+    pub struct MakerFee {
+        bool enabled;       // eg. false to charge order fills the swap fee
+        uint16 fee_bps;
+    }
+
+    // Keeper tip consists of how much of an order's input a keeper earns executing it
+    pub struct KeeperTip {
+        uint8 mode;         // eg. `KeeperTipMode`
+        uint256 amount;     // eg. flat amount of the input token, or basis points of it
+    }
+
+    // Fee tier consists of a market fee the owner manages for every market using it
+    pub struct FeeTier {
+        bool enabled;       // eg. false if the tier was never set
+        uint16 fee_bps;     // eg. 5 for stable pairs, 30 for standard ones
+    }
+
+    // Rate limit consists of how far a market's rate may move per update and per window
+    pub struct RateLimit {
+        uint16 max_change_bps;          // eg. 500 for at most 5% per update, 0 if unlimited
+        uint16 max_window_change_bps;   // eg. 1000 for at most 10% per window, 0 if unlimited
+        uint64 window;                  // eg. 3600 for hourly windows
+        uint64 window_start;            // eg. unix timestamp the current window started at
+        uint256 window_start_rate;      // eg. rate the current window started at
+    }
+
+    // Market volume consists of the tokens swapped through a market in both directions
+    pub struct MarketVolume {
+        uint256 base_volume;    // eg. base token sold to and bought from the market
+        uint256 quote_volume;   // eg. quote token sold to and bought from the market
+    }
+
+    // Deposit tolerance consists of how far two-sided deposits may stray from the market rate
+    pub struct DepositTolerance {
+        bool enabled;       // eg. false to accept deposits in any ratio
+        uint16 tolerance_bps;
+    }
+
+    // Admin action consists of the calldata of an owner action and its confirmations
+    pub struct AdminAction {
+        address proposer;
+        bytes data;                         // eg. transferOwnership(address) calldata
+        bool executed;
+        mapping(address => bool) confirmed;  // Maps admin to confirmation status
+    }
+}
+
+/// Lifecycle status of a market.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MarketStatus {
+    /// Market does not exist.
+    None = 0,
+    /// Market accepts swaps.
+    Active = 1,
+    /// Market was paused by its creator or the owner.
+    Paused = 2,
+    /// Market passed its expiry.
+    Expired = 3,
+    /// Market was closed permanently.
+    Closed = 4,
+    /// Market was flagged by a guardian and awaits review by the owner.
+    Flagged = 5,
+}
+
+impl From<u8> for MarketStatus {
+    fn from(status: u8) -> Self {
+        match status {
+            1 => MarketStatus::Active,
+            2 => MarketStatus::Paused,
+            3 => MarketStatus::Expired,
+            4 => MarketStatus::Closed,
+            5 => MarketStatus::Flagged,
+            _ => MarketStatus::None,
+        }
+    }
+}
+
+/// Direction of a swap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SwapDirection {
+    /// Swap base token for quote token.
+    BaseForQuote = 0,
+    /// Swap quote token for base token.
+    QuoteForBase = 1,
+}
+
+impl TryFrom<u8> for SwapDirection {
+    type Error = ContractError;
+
+    fn try_from(direction: u8) -> Result<Self, Self::Error> {
+        match direction {
+            0 => Ok(SwapDirection::BaseForQuote),
+            1 => Ok(SwapDirection::QuoteForBase),
+            _ => Err(ContractError::InvalidSwapDirection(InvalidSwapDirection {})),
+        }
+    }
+}
+
+/// Terms a swap fills at, see `fill_swap_at`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SwapTerms {
+    /// Swap at the market rate and fee.
+    Taker,
+    /// Order fill at the market rate and maker fee, see `set_market_fee_schedule`.
+    Maker,
+    /// Swap at the (rate, market fee) of a voucher.
+    Voucher(U256, u16),
+}
+
+/// Treasury operation reported by `TreasuryAction` events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TreasuryOperation {
+    /// Protocol fees were withdrawn to a recipient.
+    Withdraw = 0,
+    /// Protocol fees were swapped for the buyback token.
+    Buyback = 1,
+    /// Bought back tokens were burned.
+    Burn = 2,
+    /// Bought back tokens were forwarded to the treasury recipient.
+    Forward = 3,
+}
+
+/// Who may create markets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CreationMode {
+    /// Anyone may create markets.
+    Open = 0,
+    /// Only allowlisted creators and the owner may create markets.
+    Allowlisted = 1,
+    /// Only the owner may create markets.
+    OwnerOnly = 2,
+}
+
+impl TryFrom<u8> for CreationMode {
+    type Error = ContractError;
+
+    fn try_from(mode: u8) -> Result<Self, Self::Error> {
+        match mode {
+            0 => Ok(CreationMode::Open),
+            1 => Ok(CreationMode::Allowlisted),
+            2 => Ok(CreationMode::OwnerOnly),
+            _ => Err(ContractError::InvalidCreationMode(InvalidCreationMode {})),
+        }
     }
 }
 
+/// How a market's rate decays in a dutch auction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DecayMode {
+    /// Rate does not decay.
+    None = 0,
+    /// Rate decays linearly, see `math::linear_decay`.
+    Linear = 1,
+    /// Rate decays exponentially, see `math::exponential_decay`.
+    Exponential = 2,
+}
+
+impl TryFrom<u8> for DecayMode {
+    type Error = ContractError;
+
+    fn try_from(mode: u8) -> Result<Self, Self::Error> {
+        match mode {
+            0 => Ok(DecayMode::None),
+            1 => Ok(DecayMode::Linear),
+            2 => Ok(DecayMode::Exponential),
+            _ => Err(ContractError::InvalidDecayMode(InvalidDecayMode {})),
+        }
+    }
+}
+
+/// Directions a market swaps in, see `set_market_direction_mode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DirectionMode {
+    /// Market swaps both ways.
+    Both = 0,
+    /// Market only takes base token for quote token.
+    BaseForQuoteOnly = 1,
+    /// Market only takes quote token for base token, eg. an issuer selling its token.
+    QuoteForBaseOnly = 2,
+}
+
+impl TryFrom<u8> for DirectionMode {
+    type Error = ContractError;
+
+    fn try_from(mode: u8) -> Result<Self, Self::Error> {
+        match mode {
+            0 => Ok(DirectionMode::Both),
+            1 => Ok(DirectionMode::BaseForQuoteOnly),
+            2 => Ok(DirectionMode::QuoteForBaseOnly),
+            _ => Err(ContractError::InvalidDirectionMode(InvalidDirectionMode {})),
+        }
+    }
+}
+
+impl DirectionMode {
+    /// Whether the mode allows swaps in a direction.
+    pub fn allows(self, direction: SwapDirection) -> bool {
+        match self {
+            DirectionMode::Both => true,
+            DirectionMode::BaseForQuoteOnly => direction == SwapDirection::BaseForQuote,
+            DirectionMode::QuoteForBaseOnly => direction == SwapDirection::QuoteForBase,
+        }
+    }
+}
+
+/// When a conditional order becomes fillable, see `place_conditional_order`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TriggerCondition {
+    /// Market rate is at or above the trigger rate.
+    Above = 0,
+    /// Market rate is at or below the trigger rate.
+    Below = 1,
+}
+
+impl TriggerCondition {
+    /// Check whether a market rate reached a trigger rate.
+    pub fn is_reached(self, rate: U256, trigger_rate: U256) -> bool {
+        match self {
+            TriggerCondition::Above => rate >= trigger_rate,
+            TriggerCondition::Below => rate <= trigger_rate,
+        }
+    }
+}
+
+impl TryFrom<u8> for TriggerCondition {
+    type Error = ContractError;
+
+    fn try_from(condition: u8) -> Result<Self, Self::Error> {
+        match condition {
+            0 => Ok(TriggerCondition::Above),
+            1 => Ok(TriggerCondition::Below),
+            _ => Err(ContractError::InvalidTriggerCondition(
+                InvalidTriggerCondition {},
+            )),
+        }
+    }
+}
+
+/// How a market's keeper tip is sized, see `set_market_keeper_tip`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum KeeperTipMode {
+    /// Flat amount of the input token.
+    Flat = 0,
+    /// Basis points of the input amount.
+    Bps = 1,
+}
+
+impl TryFrom<u8> for KeeperTipMode {
+    type Error = ContractError;
+
+    fn try_from(mode: u8) -> Result<Self, Self::Error> {
+        match mode {
+            0 => Ok(KeeperTipMode::Flat),
+            1 => Ok(KeeperTipMode::Bps),
+            _ => Err(ContractError::InvalidKeeperTipMode(InvalidKeeperTipMode {})),
+        }
+    }
+}
+
+/// Precondition a swap fails, see `check_swap_preconditions`.
+///
+/// Each precondition is a bit of the returned mask, `1 << precondition`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SwapPrecondition {
+    /// The amount in is 0.
+    AmountZero = 0,
+    /// Market is not active.
+    MarketNotActive = 1,
+    /// Trader holds less of the input token than the amount in.
+    InsufficientBalance = 2,
+    /// Trader allows the contract less of the input token than the amount in.
+    InsufficientAllowance = 3,
+    /// Market's output reserve can not pay the amount out.
+    InsufficientReserve = 4,
+    /// Trader fails the market's compliance registry or presale allowlist.
+    AccessDenied = 5,
+    /// Purchase exceeds the trader's allocation.
+    PurchaseCapExceeded = 6,
+    /// Market's sale closed or the buy exceeds its hard cap.
+    SaleCapExceeded = 7,
+    /// Market does not swap in the direction, see `DirectionMode`.
+    DirectionDisabled = 8,
+}
+
+/// Cause of a change of a market's effective rate, see `EffectiveRateChanged`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RateChangeReason {
+    /// Market was created or its rate decay or presale was set.
+    Manual = 0,
+    /// A scheduled rate update took effect.
+    Schedule = 1,
+    /// A rate decay moved the rate.
+    Decay = 2,
+    /// The presale ended and the public phase started.
+    Phase = 3,
+}
+
+/// Sale phase of a market, see `set_presale`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SalePhase {
+    /// Market has no presale.
+    None = 0,
+    /// Only allowlisted traders may swap, at the presale rate.
+    Presale = 1,
+    /// The presale ended, everyone may swap.
+    Public = 2,
+}
+
+/// Status of a market's capped sale, see `set_sale`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SaleStatus {
+    /// Market has no sale.
+    None = 0,
+    /// Sale accepts buys until its deadline or hard cap.
+    Open = 1,
+    /// Sale raised its soft cap and the proceeds went to the creator.
+    Succeeded = 2,
+    /// Sale missed its soft cap and buyers may claim refunds.
+    Failed = 3,
+}
+
+impl From<u8> for SaleStatus {
+    fn from(status: u8) -> Self {
+        match status {
+            1 => SaleStatus::Open,
+            2 => SaleStatus::Succeeded,
+            3 => SaleStatus::Failed,
+            _ => SaleStatus::None,
+        }
+    }
+}
+
+/// Storage slots taken by `Contract`, kept constant by shrinking `__gap`.
+pub const CONTRACT_STORAGE_SLOTS: usize = 256;
+const _: () = assert!(<Contract as StorageType>::REQUIRED_SLOTS == CONTRACT_STORAGE_SLOTS);
+
+/// Quote tokens a basket market may accept.
+pub const MAX_BASKET_QUOTE_TOKENS: usize = 8;
+
+/// Selector of `onTransferReceived(address,address,uint256,bytes)`, returned to accept an
+/// ERC-1363 transfer.
+pub const ERC1363_RECEIVED: [u8; 4] = [0x88, 0xa7, 0xca, 0x5c];
+
+/// Storage slots taken by each `Market`.
+pub const MARKET_STORAGE_SLOTS: usize = 10;
+const _: () = assert!(<Market as StorageType>::REQUIRED_SLOTS == MARKET_STORAGE_SLOTS);
+
+/// Basis points in 100%.
+pub const BPS: u16 = 10_000;
+
+/// Highest fee a market creator can set, in basis points.
+pub const MAX_MARKET_FEE_BPS: u16 = 1_000;
+
+/// Highest protocol fee the owner can set, in basis points.
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 100;
+
+/// Highest keeper tip bound the owner can set, in basis points.
+pub const MAX_KEEPER_TIP_BPS: u16 = 1_000;
+
+/// Blocks after its commit block a swap commitment may be cancelled, about a day of L1 blocks.
+pub const SWAP_COMMITMENT_TIMEOUT: u64 = 7_200;
+
+/// Owner actions the L1 governor may send through `execute_l1_message`, covering fee and pause
+/// control.
+pub const L1_GOVERNOR_SELECTORS: [[u8; 4]; 9] = [
+    selectors::PAUSE_MARKET,
+    selectors::UNPAUSE_MARKET,
+    selectors::SET_PROTOCOL_FEE,
+    selectors::SET_TREASURY_CONFIG,
+    selectors::SET_FEE_COLLECTOR,
+    selectors::SET_FEE_PUSH_THRESHOLD,
+    selectors::SET_LISTING_FEE,
+    selectors::SET_LISTING_FEE_WAIVER,
+    selectors::SET_GUARDIAN,
+];
+
+/// Address bought back tokens are burned to, as many tokens reject transfers to 0.
+pub const BURN_ADDRESS: Address = Address::new([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xde, 0xad,
+]);
+
+/// Narrow an external uint256 market index to the uint64 key markets are stored under.
+///
+/// Return None if the index does not fit, as no such market can exist.
+pub fn narrow_market_index(market_index: U256) -> Option<U64> {
+    u64::try_from(market_index).ok().map(U64::from)
+}
+
+/// Exact-in swap priced by `quote_batch`.
+///
+/// (market index, direction, amount in)
+pub type QuoteRequest = (U256, u8, U256);
+
+/// Basket market returned by `fetch_basket`.
+///
+/// (base token, creator, base reserve, sold, sold cap, quote tokens)
+pub type BasketInfo = (Address, Address, U256, U256, U256, Vec<Address>);
+
+/// Leg of a `swap_split`.
+///
+/// (market index, amount in)
+pub type SplitLeg = (U256, U256);
+
+/// Rate decay returned by `fetch_rate_decay`.
+///
+/// (mode, start rate, floor rate, start time, duration)
+pub type RateDecayConfig = (u8, U256, U256, u64, u64);
+
+/// Accounting of one token of a market returned by `fetch_accounting`.
+///
+/// (token, market reserve, pending creator withdrawal, escrowed reserve, treasury balance,
+/// insurance balance, creator fees, total value locked across markets, contract balance)
+pub type TokenAccounting = (Address, U256, U256, U256, U256, U256, U256, U256, U256);
+
+/// Market returned by `fetch_markets_enriched`.
+///
+/// (market index, base token, quote token, base decimals, quote decimals, exchange rate,
+/// base reserve, quote reserve, status, direction mode, market fee, protocol fee, base volume,
+/// quote volume, creator)
+pub type MarketSummary = (
+    U256,
+    Address,
+    Address,
+    u8,
+    u8,
+    U256,
+    U256,
+    U256,
+    u8,
+    u8,
+    u16,
+    u16,
+    U256,
+    U256,
+    Address,
+);
+
+/// Creator withdrawal returned by `fetch_pending_withdrawal`.
+///
+/// (base amount, quote amount, executable at)
+pub type PendingWithdrawal = (U256, U256, u64);
+
+/// Discount voucher signed by a market creator, see `swap_with_voucher`.
+///
+/// (market index, trader, exchange rate, fee bps, max amount in, nonce, deadline)
+pub type Voucher = (U256, Address, U256, u16, U256, U256, u64);
+
+/// Sale phase returned by `fetch_phase`.
+///
+/// (phase, presale end, presale rate, public rate, merkle root)
+pub type PresaleConfig = (u8, u64, U256, U256, B256);
+
+/// Capped sale returned by `fetch_sale`.
+///
+/// (status, soft cap, hard cap, end, raised)
+pub type SaleInfo = (u8, U256, U256, u64, U256);
+
+/// Airdrop returned by `fetch_airdrop`.
+///
+/// (merkle root, amount, claimed, claim deadline, swept)
+pub type AirdropConfig = (B256, U256, U256, u64, bool);
+
+/// Conditional order returned by `fetch_conditional_order`.
+///
+/// (maker, market index, direction, condition, trigger rate, amount in, min amount out,
+/// expires at, min fill)
+pub type ConditionalOrderInfo = (Address, U256, u8, u8, U256, U256, U256, u64, U256);
+
+/// Protocol configuration returned by `fetch_config`.
+///
+/// (owner, pending owner, admin threshold, deterministic market ids, market id salt,
+/// max market fee bps, next market index, protocol fee bps, treasury recipient, fee collector,
+/// sunset, creation mode)
+pub type ProtocolConfig = (
+    Address,
+    Address,
+    u64,
+    bool,
+    B256,
+    u16,
+    U256,
+    u16,
+    Address,
+    Address,
+    bool,
+    u8,
+);
+
 // Define Erc20 interface
 sol_interface! {
     interface IErc20 {
-        function transfer(address,uint256) external;
+        function decimals() external view returns (uint8);
+        function balanceOf(address) external view returns (uint256);
+        function allowance(address,address) external view returns (uint256);
+        function transfer(address,uint256) external returns (bool);
         function transferFrom(address,address,uint256) external returns (bool);
+        function approve(address,uint256) external returns (bool);
+    }
+
+    interface IComplianceRegistry {
+        function isAllowed(address) external view returns (bool);
+    }
+
+    interface IFeeCollector {
+        function collectFees(address,uint256) external;
+    }
+
+    interface IMarketHook {
+        function beforeSwap(uint256,address,uint8,uint256,uint16) external returns (uint16);
+        function afterSwap(uint256,address,uint8,uint256,uint256) external;
+        function beforeLiquidity(uint256,address,bool,uint256,uint256) external;
+    }
+
+    interface ISwapCallback {
+        function onSwapReceived(address,address,uint256,bytes) external;
+    }
+
+    interface IErc4626 {
+        function asset() external view returns (address);
+        function deposit(uint256,address) external returns (uint256);
+        function previewDeposit(uint256) external view returns (uint256);
+        function redeem(uint256,address,address) external returns (uint256);
     }
 }
 
-// Define events and error types
+// Define error types
 sol! {
-    // Events for the Contract
-    event Initialized();
-    event MarketCreated(address indexed base_token, address indexed quote_token, uint256 exchange_rate);
-    event SwappedBaseTokenForQuoteToken(address indexed base_token, address indexed quote_token, uint256 amount_in, uint256 amount_out);
+    #![sol(all_derives)]
 
     // Error types for the Contract
     error AlreadyInitialized();
@@ -78,10 +1025,125 @@ sol! {
     error DivisionUnderflow();
     error MultiplicationOverflow();
     error OutOfBoundIndex();
+    error NotOwner();
+    error NotPendingOwner();
+    error OwnerCanNotBeZeroAddress();
+    error NotAdmin();
+    error AdminCanNotBeZeroAddress();
+    error AdminExists();
+    error AdminNotFound();
+    error InvalidAdminThreshold();
+    error AdminActionNotFound();
+    error AdminActionAlreadyConfirmed();
+    error AdminActionAlreadyExecuted();
+    error InsufficientAdminConfirmations();
+    error InvalidAdminActionData();
+    error MarketNotFound();
+    error MarketNotActive(uint8 status);
+    error MarketNotPaused(uint8 status);
+    error NotMarketCreator();
+    error MarketAlreadyClosed();
+    error InsufficientLiquidity();
+    error BaseTokenTransferFailed();
+    error QuoteTokenTransferFailed();
+    error RateChanged(uint256 expected, uint256 actual);
+    error CommitRevealRequired();
+    error SwapCommitmentExists();
+    error SwapCommitmentNotFound();
+    error RevealTooEarly();
+    error InvalidSwapDirection();
+    error DepositRefundFailed();
+    error FeeTooHigh(uint16 fee_bps, uint16 max_fee_bps);
+    error FeatureDisabled();
+    error InsufficientTreasuryBalance();
+    error RecipientCanNotBeZeroAddress();
+    error BuybackTokenNotInMarket();
+    error InsufficientAmountOut(uint256 min_amount_out, uint256 amount_out);
+    error TokenTransferFailed(address token);
+    error NotGuardian();
+    error TokenNotInMarket(address token);
+    error InsufficientInsuranceBalance();
+    error MarketNotFlagged(uint8 status);
+    error CreatorNotAllowed();
+    error InvalidCreationMode();
+    error IncorrectListingFee(uint256 expected, uint256 paid);
+    error RateOutOfBounds(uint256 min_rate, uint256 max_rate, uint256 attempted_rate);
+    error InvalidRateBounds();
+    error EffectiveTimeNotInFuture(uint64 effective_at);
+    error InvalidDecayMode();
+    error InvalidRateDecay();
+    error SplitLegPairMismatch(uint256 market_index);
+    error InvalidBasket();
+    error QuoteTokenNotAccepted(address quote_token);
+    error BasketSoldCapReached(uint256 sold_cap, uint256 sold);
+    error NotBasketCreator();
+    error VaultCallFailed(address vault);
+    error MarketHasReserves();
+    error InvalidSwapData();
+    error SwapCallbackFailed(address target);
+    error HookNotApproved(address hook);
+    error HookRejected(address hook);
+    error NotFeeCollector();
+    error NotCompliant(address account);
+    error ContractCallersNotAllowed(address account);
+    error ExternalCallFailed(address target, bytes revert_data);
+    error InvariantViolated();
+    error NotRateOperator();
+    error NotL1Governor();
+    error L1MessageNotAllowed(bytes4 selector);
+    error WithdrawalDelayed(uint64 delay);
+    error NoPendingWithdrawal();
+    error WithdrawalNotReady(uint64 executable_at);
+    error LiquidityLocked(uint256 locked);
+    error InvalidVoucher();
+    error VoucherExpired(uint64 deadline);
+    error VoucherUsed(uint256 nonce);
+    error VoucherAmountExceeded(uint256 max_amount_in);
+    error PurchaseCapExceeded(uint256 remaining);
+    error InvalidMerkleProof();
+    error PresaleAccessDenied(address account);
+    error SaleNotOpen(uint8 status);
+    error SaleStillOpen(uint64 ends_at);
+    error SaleNotFailed();
+    error InvalidSaleCaps();
+    error HardCapExceeded(uint256 remaining);
+    error SaleNotSucceeded(uint8 status);
+    error AirdropInProgress();
+    error AirdropClaimsClosed(uint64 claim_deadline);
+    error AirdropClaimsOpen(uint64 claim_deadline);
+    error AirdropAlreadyClaimed(address account);
+    error InvalidTriggerCondition();
+    error TriggerAlreadyReached(uint256 rate);
+    error TriggerNotReached(uint256 rate);
+    error OrderNotOpen(uint256 order_id);
+    error NotOrderMaker();
+    error OrderMarketMismatch(uint256 order_id);
+    error InvalidKeeperTipMode();
+    error InvalidKeeperTipBounds();
+    error RecipientNotAllowed(address recipient);
+    error InvalidDepositTolerance();
+    error DepositRatioMismatch(uint256 expected_quote_amount, uint256 quote_amount);
+    error NotPendingCreator();
+    error CreatorCanNotBeZeroAddress();
+    error FeeTierNotFound(uint64 tier_id);
+    error MarketFeeTiered(uint64 tier_id);
+    error SwapTooOld(uint64 expired_at);
+    error RateChangeTooLarge(uint256 reference_rate, uint256 new_rate, uint16 max_change_bps);
+    error ContractSunset();
+    error InvalidSolvencyTolerance();
+    error SameMarket();
+    error InvalidDirectionMode();
+    error DirectionDisabled(uint8 direction);
+    error CommitmentNotExpired(uint64 cancellable_at);
+    error AfterSwapHookRejected(address hook);
+    error RebasingMarketNotSupported();
+    error InvalidOrderExpiry();
+    error OrderExpired(uint256 order_id);
+    error InvalidFillAmount(uint256 min_fill, uint256 remaining);
 }
 
 /// Represents the ways methods may fail.
-#[derive(SolidityError)]
+#[derive(SolidityError, Debug, PartialEq, Eq)]
 pub enum ContractError {
     AlreadyInitialized(AlreadyInitialized),
     MarketExists(MarketExists),
@@ -94,402 +1156,9296 @@ pub enum ContractError {
     DivisionUnderflow(DivisionUnderflow),
     MultiplicationOverflow(MultiplicationOverflow),
     OutOfBoundIndex(OutOfBoundIndex),
+    NotOwner(NotOwner),
+    NotPendingOwner(NotPendingOwner),
+    OwnerCanNotBeZeroAddress(OwnerCanNotBeZeroAddress),
+    NotAdmin(NotAdmin),
+    AdminCanNotBeZeroAddress(AdminCanNotBeZeroAddress),
+    AdminExists(AdminExists),
+    AdminNotFound(AdminNotFound),
+    InvalidAdminThreshold(InvalidAdminThreshold),
+    AdminActionNotFound(AdminActionNotFound),
+    AdminActionAlreadyConfirmed(AdminActionAlreadyConfirmed),
+    AdminActionAlreadyExecuted(AdminActionAlreadyExecuted),
+    InsufficientAdminConfirmations(InsufficientAdminConfirmations),
+    InvalidAdminActionData(InvalidAdminActionData),
+    MarketNotFound(MarketNotFound),
+    MarketNotActive(MarketNotActive),
+    MarketNotPaused(MarketNotPaused),
+    NotMarketCreator(NotMarketCreator),
+    MarketAlreadyClosed(MarketAlreadyClosed),
+    InsufficientLiquidity(InsufficientLiquidity),
+    BaseTokenTransferFailed(BaseTokenTransferFailed),
+    QuoteTokenTransferFailed(QuoteTokenTransferFailed),
+    RateChanged(RateChanged),
+    CommitRevealRequired(CommitRevealRequired),
+    SwapCommitmentExists(SwapCommitmentExists),
+    SwapCommitmentNotFound(SwapCommitmentNotFound),
+    RevealTooEarly(RevealTooEarly),
+    InvalidSwapDirection(InvalidSwapDirection),
+    DepositRefundFailed(DepositRefundFailed),
+    FeeTooHigh(FeeTooHigh),
+    FeatureDisabled(FeatureDisabled),
+    InsufficientTreasuryBalance(InsufficientTreasuryBalance),
+    RecipientCanNotBeZeroAddress(RecipientCanNotBeZeroAddress),
+    BuybackTokenNotInMarket(BuybackTokenNotInMarket),
+    InsufficientAmountOut(InsufficientAmountOut),
+    TokenTransferFailed(TokenTransferFailed),
+    NotGuardian(NotGuardian),
+    TokenNotInMarket(TokenNotInMarket),
+    InsufficientInsuranceBalance(InsufficientInsuranceBalance),
+    MarketNotFlagged(MarketNotFlagged),
+    CreatorNotAllowed(CreatorNotAllowed),
+    InvalidCreationMode(InvalidCreationMode),
+    IncorrectListingFee(IncorrectListingFee),
+    RateOutOfBounds(RateOutOfBounds),
+    InvalidRateBounds(InvalidRateBounds),
+    EffectiveTimeNotInFuture(EffectiveTimeNotInFuture),
+    InvalidDecayMode(InvalidDecayMode),
+    InvalidRateDecay(InvalidRateDecay),
+    SplitLegPairMismatch(SplitLegPairMismatch),
+    InvalidBasket(InvalidBasket),
+    QuoteTokenNotAccepted(QuoteTokenNotAccepted),
+    BasketSoldCapReached(BasketSoldCapReached),
+    NotBasketCreator(NotBasketCreator),
+    VaultCallFailed(VaultCallFailed),
+    MarketHasReserves(MarketHasReserves),
+    InvalidSwapData(InvalidSwapData),
+    SwapCallbackFailed(SwapCallbackFailed),
+    HookNotApproved(HookNotApproved),
+    HookRejected(HookRejected),
+    NotFeeCollector(NotFeeCollector),
+    NotCompliant(NotCompliant),
+    ContractCallersNotAllowed(ContractCallersNotAllowed),
+    ExternalCallFailed(ExternalCallFailed),
+    InvariantViolated(InvariantViolated),
+    NotRateOperator(NotRateOperator),
+    NotL1Governor(NotL1Governor),
+    L1MessageNotAllowed(L1MessageNotAllowed),
+    WithdrawalDelayed(WithdrawalDelayed),
+    NoPendingWithdrawal(NoPendingWithdrawal),
+    WithdrawalNotReady(WithdrawalNotReady),
+    LiquidityLocked(LiquidityLocked),
+    InvalidVoucher(InvalidVoucher),
+    VoucherExpired(VoucherExpired),
+    VoucherUsed(VoucherUsed),
+    VoucherAmountExceeded(VoucherAmountExceeded),
+    PurchaseCapExceeded(PurchaseCapExceeded),
+    InvalidMerkleProof(InvalidMerkleProof),
+    PresaleAccessDenied(PresaleAccessDenied),
+    SaleNotOpen(SaleNotOpen),
+    SaleStillOpen(SaleStillOpen),
+    SaleNotFailed(SaleNotFailed),
+    InvalidSaleCaps(InvalidSaleCaps),
+    HardCapExceeded(HardCapExceeded),
+    SaleNotSucceeded(SaleNotSucceeded),
+    AirdropInProgress(AirdropInProgress),
+    AirdropClaimsClosed(AirdropClaimsClosed),
+    AirdropClaimsOpen(AirdropClaimsOpen),
+    AirdropAlreadyClaimed(AirdropAlreadyClaimed),
+    InvalidTriggerCondition(InvalidTriggerCondition),
+    TriggerAlreadyReached(TriggerAlreadyReached),
+    TriggerNotReached(TriggerNotReached),
+    OrderNotOpen(OrderNotOpen),
+    NotOrderMaker(NotOrderMaker),
+    OrderMarketMismatch(OrderMarketMismatch),
+    InvalidKeeperTipMode(InvalidKeeperTipMode),
+    InvalidKeeperTipBounds(InvalidKeeperTipBounds),
+    RecipientNotAllowed(RecipientNotAllowed),
+    InvalidDepositTolerance(InvalidDepositTolerance),
+    DepositRatioMismatch(DepositRatioMismatch),
+    NotPendingCreator(NotPendingCreator),
+    CreatorCanNotBeZeroAddress(CreatorCanNotBeZeroAddress),
+    FeeTierNotFound(FeeTierNotFound),
+    MarketFeeTiered(MarketFeeTiered),
+    SwapTooOld(SwapTooOld),
+    RateChangeTooLarge(RateChangeTooLarge),
+    ContractSunset(ContractSunset),
+    InvalidSolvencyTolerance(InvalidSolvencyTolerance),
+    SameMarket(SameMarket),
+    InvalidDirectionMode(InvalidDirectionMode),
+    DirectionDisabled(DirectionDisabled),
+    CommitmentNotExpired(CommitmentNotExpired),
+    AfterSwapHookRejected(AfterSwapHookRejected),
+    RebasingMarketNotSupported(RebasingMarketNotSupported),
+    InvalidOrderExpiry(InvalidOrderExpiry),
+    OrderExpired(OrderExpired),
+    InvalidFillAmount(InvalidFillAmount),
 }
 
 /// Declare that `Contract` is a contract with the following external methods.
 #[public]
 impl Contract {
-    /// Initialize contract.
+    /// Initialize contract, the caller becomes the owner.
+    ///
+    /// Kept for deployments scripted before `initialize(owner, ...)`, see
+    /// `initialize_with_config`.
     pub fn initialize(&mut self) -> Result<(), ContractError> {
-        // Ensure the contract has not been initialized.
-        if self.initialized.get() {
-            return Err(ContractError::AlreadyInitialized(AlreadyInitialized {}));
+        self.initialize_internal(msg::sender())
+    }
+
+    /// Initialize contract with its configuration in one call.
+    ///
+    /// `fee_recipient` is the fee collector protocol fees are pushed to, 0 to keep them in the
+    /// treasury, and `creation_mode` is a `CreationMode`. Shares the version guard of
+    /// `initialize`, so only one of them can run.
+    #[selector(name = "initialize")]
+    pub fn initialize_with_config(
+        &mut self,
+        owner: Address,
+        fee_recipient: Address,
+        protocol_fee_bps: u16,
+        creation_mode: u8,
+    ) -> Result<(), ContractError> {
+        // Ensures the owner is not a zero address.
+        if owner == Address::ZERO {
+            return Err(ContractError::OwnerCanNotBeZeroAddress(
+                OwnerCanNotBeZeroAddress {},
+            ));
         }
 
-        // Set initialized.
-        self.initialized.set(true);
+        // Ensure the fee is within the cap.
+        if protocol_fee_bps > MAX_PROTOCOL_FEE_BPS {
+            return Err(ContractError::FeeTooHigh(FeeTooHigh {
+                fee_bps: protocol_fee_bps,
+                max_fee_bps: MAX_PROTOCOL_FEE_BPS,
+            }));
+        }
 
-        // Initialize market index.
-        self.market_index.set(U64::from(1));
+        // Ensure the treasury is compiled in if it is configured.
+        if protocol_fee_bps != 0 || fee_recipient != Address::ZERO {
+            Self::ensure_feature(cfg!(feature = "treasury"))?;
+        }
 
-        // Emit event
-        evm::log(Initialized {});
+        // Ensure the mode exists.
+        CreationMode::try_from(creation_mode)?;
+
+        // Initialize contract.
+        self.initialize_internal(owner)?;
+
+        // Set configuration.
+        let previous_collector = self.fee_collector.get();
+        self.fee_collector.set(fee_recipient);
+        self.protocol_fee_bps.set(U16::from(protocol_fee_bps));
+        self.creation_mode.set(U8::from(creation_mode));
+
+        // Emit events.
+        evm::log(FeeCollectorSet {
+            previous_collector,
+            new_collector: fee_recipient,
+        });
+        evm::log(ProtocolFeeSet {
+            previous_fee_bps: 0,
+            new_fee_bps: protocol_fee_bps,
+        });
+        evm::log(CreationModeSet {
+            previous_mode: CreationMode::Open as u8,
+            new_mode: creation_mode,
+        });
 
         Ok(())
     }
 
     /// Create a new market.
     ///
-    /// Return market index.
+    /// Fund it with `deposit_liquidity`, or use `create_and_fund`.
+    ///
+    /// Return the index of the created market.
+    #[payable]
     pub fn create_market(
         &mut self,
         base_token: Address,
         quote_token: Address,
         exchange_rate: U256, // eg. 3.
-        base_amount: U256,   // eg. 2.
-        quote_amount: U256,  // eg. base_amount * rate; 2 * 3 = 6.
     ) -> Result<U256, ContractError> {
-        // Ensures rate is not 0.
-        if exchange_rate == U256::from(0) {
-            return Err(ContractError::ExchangeRateCanNotBeZero(
-                ExchangeRateCanNotBeZero {},
-            ));
-        }
-
-        // Ensures the base token address is not a zero address.
-        if base_token == Address::ZERO {
-            return Err(ContractError::BaseTokenCanNotBeZeroAddress(
-                BaseTokenCanNotBeZeroAddress {},
-            ));
-        }
-
-        // Ensures the quote token address is not a zero address.
-        if quote_token == Address::ZERO {
-            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
-                QuoteTokenCanNotBeZeroAddress {},
-            ));
-        }
-
-        // Safely unwrap the calculated base token amount.
-        let expected_base_amount = quote_amount.checked_div(exchange_rate);
-        if expected_base_amount.is_none() {
-            return Err(ContractError::DivisionUnderflow(DivisionUnderflow {}));
-        }
-
-        // Safely unwrap the calculated base token amount.
-        let expected_base_amount = expected_base_amount.unwrap();
+        // Create market and charge the listing fee.
+        let market_index = self.create_market_internal(base_token, quote_token, exchange_rate)?;
+        let fee = self.charge_listing_fee(market_index)?;
+        self.pull_listing_fee(fee)?;
 
-        // Ensure the correct amount of base token was supplied.
-        if base_amount.ne(&expected_base_amount) {
-            return Err(ContractError::IncorrectBaseAmount(IncorrectBaseAmount {}));
-        }
+        // Return market index.
+        Ok(U256::from(market_index))
+    }
 
-        // Calculate the expected base token amount.
-        let expected_quote_amount = base_amount.checked_mul(exchange_rate);
-        if expected_quote_amount.is_none() {
-            return Err(ContractError::MultiplicationOverflow(
-                MultiplicationOverflow {},
-            ));
-        }
+    /// Create a new market charging the fee of an owner-managed fee tier.
+    ///
+    /// Fund it with `deposit_liquidity`, or use `create_and_fund`.
+    ///
+    /// Return the index of the created market.
+    #[payable]
+    pub fn create_market_with_fee_tier(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        exchange_rate: U256, // eg. 3.
+        tier_id: u64,        // eg. 1 for a standard 30bps tier.
+    ) -> Result<U256, ContractError> {
+        // Ensure the tier exists.
+        self.ensure_fee_tier(tier_id)?;
 
-        // Safely unwrap the calculated quote token amount.
-        let expected_quote_amount = expected_quote_amount.unwrap();
+        // Create market and charge the listing fee.
+        let market_index = self.create_market_internal(base_token, quote_token, exchange_rate)?;
+        let fee = self.charge_listing_fee(market_index)?;
+        self.set_market_fee_tier_internal(market_index, tier_id);
+        self.pull_listing_fee(fee)?;
 
-        // Assert enough quote token was supplied.
-        if quote_amount.ne(&expected_quote_amount) {
-            return Err(ContractError::IncorrectQuoteAmount(IncorrectQuoteAmount {}));
-        }
+        // Return market index.
+        Ok(U256::from(market_index))
+    }
 
-        // Get the current market index.
-        let mut current_market_index = self.market_index.get();
+    /// Create a new market identified by a salt the creator picks, or return the market the
+    /// caller already created with it.
+    ///
+    /// Repeat calls with the same salt and market parameters charge no listing fee and refund
+    /// attached ETH, so deployment scripts may retry them after a reorg or timeout. Reusing a
+    /// salt for another pair or initial rate reverts with `MarketExists`. The market id is
+    /// `compute_salted_market_id(creator, salt)`.
+    ///
+    /// Return the index of the market.
+    #[payable]
+    pub fn create_market_with_salt(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        exchange_rate: U256, // eg. 3.
+        salt: B256,
+    ) -> Result<U256, ContractError> {
+        let creator = msg::sender();
+        let market_id = Self::derive_salted_market_id(creator, salt);
 
-        // Ensure the market does not exist.
-        let mut base_token_map = self.indexes.setter(base_token);
-        let quote_token_map = base_token_map.setter(quote_token);
-        let market_index = quote_token_map.get();
+        // Return the market already created with the salt, if it has the same parameters.
+        let existing_market_index = self.salted_indexes.get(market_id);
+        if !existing_market_index.is_zero() {
+            let market = self.markets.get(existing_market_index);
+            if market.base_token.get() != base_token
+                || market.quote_token.get() != quote_token
+                || self.salted_rates.get(market_id) != exchange_rate
+            {
+                return Err(ContractError::MarketExists(MarketExists {}));
+            }
 
-        // Return error if the market exists.
-        if !market_index.is_zero() {
-            return Err(ContractError::MarketExists(MarketExists {}));
+            let value = msg::value();
+            if !value.is_zero() && call::transfer_eth(creator, value).is_err() {
+                return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                    token: Address::ZERO,
+                }));
+            }
+            return Ok(U256::from(existing_market_index));
         }
 
-        // Create a new market in storage.
-        let mut market = self.markets.setter(current_market_index);
-        market.base_token.set(base_token);
-        market.quote_token.set(quote_token);
-        market.exchange_rate.set(exchange_rate);
-
-        // Map (base_token_address, quote_token_address) => market_index.
-        let mut base_token_map = self.indexes.setter(base_token);
-        let mut quote_token_map = base_token_map.setter(quote_token);
-        quote_token_map.set(current_market_index);
-
-        // Set new market index.
-        current_market_index += U64::from(1);
-        self.market_index.set(current_market_index);
-
-        // Transfer base token from creator.
-        let base_token_contract = IErc20::new(market.base_token.get());
-        let _ =
-            base_token_contract.transfer_from(Call::new(), msg::sender(), address(), base_amount);
-
-        // Transfer quote token from creator.
-        let quote_token_contract = IErc20::new(market.quote_token.get());
-        let _ =
-            quote_token_contract.transfer_from(Call::new(), msg::sender(), address(), quote_amount);
+        // Create market and charge the listing fee.
+        let market_index = self.create_market_internal(base_token, quote_token, exchange_rate)?;
+        let fee = self.charge_listing_fee(market_index)?;
+        self.salted_indexes.insert(market_id, market_index);
+        self.salted_rates.insert(market_id, exchange_rate);
 
         // Emit event.
-        evm::log(MarketCreated {
-            base_token,
-            quote_token,
-            exchange_rate,
+        evm::log(SaltedMarketCreated {
+            market_index: U256::from(market_index),
+            creator,
+            salt,
+            market_id,
         });
 
+        self.pull_listing_fee(fee)?;
+
         // Return market index.
-        Ok(U256::from(current_market_index))
+        Ok(U256::from(market_index))
     }
 
-    /// Swap base token for quote token.
-    pub fn swap_base_token_for_quote_token(
+    /// Create a new market and deposit its initial liquidity.
+    ///
+    /// Either amount may be 0 to seed one side only.
+    ///
+    /// Return the index of the created market.
+    #[payable]
+    pub fn create_and_fund(
         &mut self,
         base_token: Address,
         quote_token: Address,
-        base_amount: U256,
-    ) -> Result<(), ContractError> {
-        // Ensures base amount is not 0.
-        if base_amount == U256::from(0) {
-            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
-        }
+        exchange_rate: U256, // eg. 3.
+        base_amount: U256,   // eg. 2.
+        quote_amount: U256,  // eg. 6.
+    ) -> Result<U256, ContractError> {
+        // Create market and charge the listing fee.
+        let market_index = self.create_market_internal(base_token, quote_token, exchange_rate)?;
+        let fee = self.charge_listing_fee(market_index)?;
 
-        // Ensures base token address is not a zero address.
+        // Deposit initial liquidity, then collect the fee.
+        self.deposit_liquidity_internal(market_index, base_amount, quote_amount)?;
+        self.pull_listing_fee(fee)?;
+
+        // Return market index.
+        Ok(U256::from(market_index))
+    }
+
+    /// Preview `create_and_fund` for the caller without changing state.
+    ///
+    /// Reverts with the error creation would fail with, including token transfers the caller's
+    /// balance or allowance can not cover. Pass zero amounts to preview `create_market`.
+    ///
+    /// Return (market index, market id or 0 if ids are not deterministic, base decimals,
+    /// quote decimals) the market would be created with.
+    pub fn preview_create_market(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+        exchange_rate: U256,
+        base_amount: U256,
+        quote_amount: U256,
+    ) -> Result<(U256, U256, u8, u8), ContractError> {
+        // Ensure the market can be created.
+        self.ensure_market_creatable(base_token, quote_token, exchange_rate)?;
+
+        // Ensure the caller can fund the initial liquidity.
+        let creator = msg::sender();
+        if !base_amount.is_zero() && Self::spendable_tokens(base_token, creator) < base_amount {
+            return Err(ContractError::BaseTokenTransferFailed(
+                BaseTokenTransferFailed {},
+            ));
+        }
+        if !quote_amount.is_zero() && Self::spendable_tokens(quote_token, creator) < quote_amount {
+            return Err(ContractError::QuoteTokenTransferFailed(
+                QuoteTokenTransferFailed {},
+            ));
+        }
+
+        // Derive the market id if deterministic ids are enabled.
+        let market_id = if self.deterministic_market_ids.get() {
+            Self::derive_market_id(base_token, quote_token, self.market_id_salt.get())
+        } else {
+            U256::ZERO
+        };
+
+        Ok((
+            U256::from(self.market_index.get()),
+            market_id,
+            Self::token_decimals(base_token).to::<u8>(),
+            Self::token_decimals(quote_token).to::<u8>(),
+        ))
+    }
+
+    /// Deposit liquidity into a market.
+    ///
+    /// Callable by the market creator, either amount may be 0.
+    pub fn deposit_liquidity(
+        &mut self,
+        market_index: U256,
+        base_amount: U256,
+        quote_amount: U256,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Deposit liquidity.
+        self.deposit_liquidity_internal(market_index, base_amount, quote_amount)
+    }
+
+    /// Withdraw liquidity from a market.
+    ///
+    /// Callable by the market creator, either amount may be 0. Markets with a withdrawal delay
+    /// withdraw through `request_withdrawal` instead.
+    pub fn withdraw_liquidity(
+        &mut self,
+        market_index: U256,
+        base_amount: U256,
+        quote_amount: U256,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure withdrawals from the market are instant.
+        let delay = self.withdrawal_queues.get(market_index).delay.get();
+        if !delay.is_zero() {
+            return Err(ContractError::WithdrawalDelayed(WithdrawalDelayed {
+                delay: delay.to::<u64>(),
+            }));
+        }
+
+        // Withdraw liquidity.
+        self.withdraw_liquidity_internal(market_index, base_amount, quote_amount, msg::sender())
+    }
+
+    /// Withdraw liquidity from a market to `recipient`, eg. a custody address apart from the
+    /// signing one.
+    ///
+    /// Callable by the market creator, either amount may be 0. Markets with a withdrawal delay
+    /// withdraw through `request_withdrawal` instead.
+    #[selector(name = "withdrawLiquidity")]
+    pub fn withdraw_liquidity_to(
+        &mut self,
+        market_index: U256,
+        base_amount: U256,
+        quote_amount: U256,
+        recipient: Address,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure withdrawals from the market are instant.
+        let delay = self.withdrawal_queues.get(market_index).delay.get();
+        if !delay.is_zero() {
+            return Err(ContractError::WithdrawalDelayed(WithdrawalDelayed {
+                delay: delay.to::<u64>(),
+            }));
+        }
+
+        // Withdraw liquidity.
+        self.withdraw_liquidity_internal(market_index, base_amount, quote_amount, recipient)
+    }
+
+    /// Move reserves of a token between two markets, eg. to rebalance inventory across
+    /// listings, without transferring the token out and back in.
+    ///
+    /// Callable by the creator of both markets. The token may sit on either side of each
+    /// market. The move counts as a withdrawal from `from_market`, so markets with a withdrawal
+    /// delay can not send reserves, and as a deposit to `to_market`.
+    pub fn transfer_reserves(
+        &mut self,
+        from_market: U256,
+        to_market: U256,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), ContractError> {
+        let from_market = Self::market_key(from_market)?;
+        let to_market = Self::market_key(to_market)?;
+
+        // Ensure the caller is the creator of both markets.
+        self.only_market_creator(from_market)?;
+        self.only_market_creator(to_market)?;
+
+        // Ensures the amount is not 0 and the markets differ.
+        if amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+        if from_market == to_market {
+            return Err(ContractError::SameMarket(SameMarket {}));
+        }
+
+        // Ensure withdrawals from the source market are instant.
+        let delay = self.withdrawal_queues.get(from_market).delay.get();
+        if !delay.is_zero() {
+            return Err(ContractError::WithdrawalDelayed(WithdrawalDelayed {
+                delay: delay.to::<u64>(),
+            }));
+        }
+
+        // Ensure the destination market is not expired or closed.
+        let status = self.market_status(to_market);
+        if status != MarketStatus::Active && status != MarketStatus::Paused {
+            return Err(ContractError::MarketNotActive(MarketNotActive {
+                status: status as u8,
+            }));
+        }
+
+        // Ensure the token is listed on both markets.
+        let (base_out, quote_out) = self.reserve_amounts(from_market, token, amount)?;
+        let (base_in, quote_in) = self.reserve_amounts(to_market, token, amount)?;
+
+        // Let the hooks veto the withdrawal and the deposit.
+        self.call_before_liquidity(from_market, false, base_out, quote_out)?;
+        self.call_before_liquidity(to_market, true, base_in, quote_in)?;
+
+        // Resync rebasing reserves of both markets before either changes.
+        self.sync_rebasing_reserves(from_market);
+        self.sync_rebasing_reserves(to_market);
+
+        // Ensure the source market holds enough liquidity.
+        self.ensure_liquidity_withdrawable(from_market, base_out, quote_out)?;
+
+        // Update reserves, the total value locked does not change.
+        let mut market = self.markets.setter(from_market);
+        let base_reserve = market.base_reserve.get();
+        let quote_reserve = market.quote_reserve.get();
+        market.base_reserve.set(base_reserve - base_out);
+        market.quote_reserve.set(quote_reserve - quote_out);
+        let mut market = self.markets.setter(to_market);
+        let base_reserve = market.base_reserve.get();
+        let quote_reserve = market.quote_reserve.get();
+        market.base_reserve.set(base_reserve + base_in);
+        market.quote_reserve.set(quote_reserve + quote_in);
+        self.commit_rebasing_reserves(from_market);
+        self.commit_rebasing_reserves(to_market);
+
+        // Emit events.
+        let provider = msg::sender();
+        evm::log(LiquidityWithdrawn {
+            market_index: U256::from(from_market),
+            provider,
+            base_amount: base_out,
+            quote_amount: quote_out,
+        });
+        evm::log(LiquidityDeposited {
+            market_index: U256::from(to_market),
+            provider,
+            base_amount: base_in,
+            quote_amount: quote_in,
+        });
+        evm::log(ReservesTransferred {
+            from_market: U256::from(from_market),
+            to_market: U256::from(to_market),
+            token,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Set the delay creator withdrawals from a market wait before they execute.
+    ///
+    /// Callable by the market creator or the owner. Only the owner may shorten the delay or
+    /// disable the auto-pause, so a creator cannot lift them right before withdrawing. With
+    /// `auto_pause` set, requesting a withdrawal pauses the market until it executes or is
+    /// cancelled.
+    pub fn set_withdrawal_delay(
+        &mut self,
+        market_index: U256,
+        delay: u64,
+        auto_pause: bool,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller may manage the market.
+        self.only_market_creator_or_owner(market_index)?;
+
+        // Ensure only the owner loosens the delay.
+        let queue = self.withdrawal_queues.get(market_index);
+        let previous_delay = queue.delay.get().to::<u64>();
+        let previous_auto_pause = queue.auto_pause.get();
+        if delay < previous_delay || (previous_auto_pause && !auto_pause) {
+            self.only_owner()?;
+        }
+
+        // Set withdrawal delay.
+        let mut queue = self.withdrawal_queues.setter(market_index);
+        queue.delay.set(U64::from(delay));
+        queue.auto_pause.set(auto_pause);
+
+        // Emit event.
+        evm::log(WithdrawalDelaySet {
+            market_index: U256::from(market_index),
+            previous_delay,
+            previous_auto_pause,
+            new_delay: delay,
+            new_auto_pause: auto_pause,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the withdrawal delay of a market and whether requests pause it.
+    pub fn fetch_withdrawal_delay(&self, market_index: U256) -> Result<(u64, bool), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        let queue = self.withdrawal_queues.get(market_index);
+        Ok((queue.delay.get().to::<u64>(), queue.auto_pause.get()))
+    }
+
+    /// Queue a creator withdrawal that executes after the market's withdrawal delay, replacing
+    /// any pending one.
+    ///
+    /// Callable by the market creator, either amount may be 0. Trading continues during the
+    /// delay unless the market auto-pauses.
+    pub fn request_withdrawal(
+        &mut self,
+        market_index: U256,
+        base_amount: U256,
+        quote_amount: U256,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensures the amounts are not both 0.
+        if base_amount.is_zero() && quote_amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Queue withdrawal.
+        let mut queue = self.withdrawal_queues.setter(market_index);
+        let executable_at = chain::timestamp().saturating_add(queue.delay.get().to::<u64>());
+        let auto_pause = queue.auto_pause.get();
+        queue.base_amount.set(base_amount);
+        queue.quote_amount.set(quote_amount);
+        queue.executable_at.set(U64::from(executable_at));
+
+        // Pause the market until the withdrawal executes.
+        if cfg!(feature = "pausable")
+            && auto_pause
+            && self.market_status(market_index) == MarketStatus::Active
+        {
+            self.withdrawal_queues.setter(market_index).paused.set(true);
+            self.set_market_status(market_index, MarketStatus::Paused);
+            let auto_pauses = self.auto_pauses.get();
+            self.auto_pauses.set(auto_pauses + U64::from(1));
+        }
+
+        // Emit event.
+        evm::log(WithdrawalRequested {
+            market_index: U256::from(market_index),
+            base_amount,
+            quote_amount,
+            executable_at,
+        });
+
+        Ok(())
+    }
+
+    /// Execute a market's pending creator withdrawal once its delay passed.
+    ///
+    /// Callable by the market creator.
+    pub fn execute_withdrawal(&mut self, market_index: U256) -> Result<(), ContractError> {
+        self.execute_withdrawal_to(market_index, msg::sender())
+    }
+
+    /// Execute a market's pending creator withdrawal once its delay passed, paying `recipient`.
+    ///
+    /// Callable by the market creator.
+    #[selector(name = "executeWithdrawal")]
+    pub fn execute_withdrawal_to(
+        &mut self,
+        market_index: U256,
+        recipient: Address,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the delay passed.
+        let executable_at = self.pending_withdrawal_at(market_index)?;
+        if chain::timestamp() < executable_at {
+            return Err(ContractError::WithdrawalNotReady(WithdrawalNotReady {
+                executable_at,
+            }));
+        }
+
+        // Withdraw liquidity.
+        let (base_amount, quote_amount) = self.clear_pending_withdrawal(market_index);
+        self.withdraw_liquidity_internal(market_index, base_amount, quote_amount, recipient)
+    }
+
+    /// Cancel a market's pending creator withdrawal.
+    ///
+    /// Callable by the market creator.
+    pub fn cancel_withdrawal(&mut self, market_index: U256) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure a withdrawal is pending.
+        self.pending_withdrawal_at(market_index)?;
+
+        // Cancel withdrawal.
+        let (base_amount, quote_amount) = self.clear_pending_withdrawal(market_index);
+
+        // Emit event.
+        evm::log(WithdrawalCancelled {
+            market_index: U256::from(market_index),
+            base_amount,
+            quote_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the pending creator withdrawal of a market, all 0 if none.
+    pub fn fetch_pending_withdrawal(
+        &self,
+        market_index: U256,
+    ) -> Result<PendingWithdrawal, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        let queue = self.withdrawal_queues.get(market_index);
+        Ok((
+            queue.base_amount.get(),
+            queue.quote_amount.get(),
+            queue.executable_at.get().to::<u64>(),
+        ))
+    }
+
+    /// Set the base token locked in every market created afterwards, 0 to lock none.
+    ///
+    /// Callable by the owner. Like an AMM's minimum liquidity, locked base token can not be
+    /// withdrawn or bought while the market is open, so its base reserve never drops below it
+    /// once funded.
+    pub fn set_min_locked_liquidity(&mut self, amount: U256) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Set minimum locked liquidity.
+        let previous_amount = self.min_locked_liquidity.get();
+        self.min_locked_liquidity.set(amount);
+
+        // Emit event.
+        evm::log(MinLockedLiquiditySet {
+            previous_amount,
+            new_amount: amount,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the base token locked in every market created afterwards.
+    pub fn fetch_min_locked_liquidity(&self) -> Result<U256, ContractError> {
+        Ok(self.min_locked_liquidity.get())
+    }
+
+    /// Raise the base token locked in a market above the minimum it was created with.
+    ///
+    /// Callable by the market creator. The lock can not be lowered, it is released when the
+    /// market closes.
+    pub fn lock_liquidity(
+        &mut self,
+        market_index: U256,
+        amount: U256,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the lock only grows.
+        let previous_amount = self.locked_liquidity.get(market_index);
+        if amount < previous_amount {
+            return Err(ContractError::LiquidityLocked(LiquidityLocked {
+                locked: previous_amount,
+            }));
+        }
+
+        // Set locked liquidity.
+        self.locked_liquidity.insert(market_index, amount);
+
+        // Emit event.
+        evm::log(LockedLiquiditySet {
+            market_index: U256::from(market_index),
+            previous_amount,
+            new_amount: amount,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the base token locked in a market.
+    pub fn fetch_locked_liquidity(&self, market_index: U256) -> Result<U256, ContractError> {
+        Ok(self.locked_liquidity.get(Self::market_key(market_index)?))
+    }
+
+    /// Swap base token for quote token.
+    ///
+    /// Pass `type(uint256).max` as `base_amount` to swap the caller's full balance, capped by its allowance.
+    pub fn swap_base_token_for_quote_token(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        base_amount: U256,
+    ) -> Result<(), ContractError> {
+        // Ensure the market accepts direct swaps.
+        self.ensure_direct_swap_allowed(base_token, quote_token)?;
+
+        self.swap_base_token_for_quote_token_internal(base_token, quote_token, base_amount, None)
+    }
+
+    /// Swap base token for quote token at the exchange rate the caller was quoted.
+    ///
+    /// Revert with `RateChanged` if the market rate differs from `expected_rate`.
+    #[selector(name = "swapBaseTokenForQuoteToken")]
+    pub fn swap_base_token_for_quote_token_at_rate(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        base_amount: U256,
+        expected_rate: U256,
+    ) -> Result<(), ContractError> {
+        // Ensure the market accepts direct swaps.
+        self.ensure_direct_swap_allowed(base_token, quote_token)?;
+
+        self.swap_base_token_for_quote_token_internal(
+            base_token,
+            quote_token,
+            base_amount,
+            Some(expected_rate),
+        )
+    }
+
+    /// Swap quote token for base token.
+    ///
+    /// Pass `type(uint256).max` as `quote_amount` to swap the caller's full balance, capped by its allowance.
+    pub fn swap_quote_token_for_base_token(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        quote_amount: U256,
+    ) -> Result<(), ContractError> {
+        // Ensure the market accepts direct swaps.
+        self.ensure_direct_swap_allowed(base_token, quote_token)?;
+
+        self.swap_quote_token_for_base_token_internal(base_token, quote_token, quote_amount, None)
+    }
+
+    /// Swap quote token for base token at the exchange rate the caller was quoted.
+    ///
+    /// Revert with `RateChanged` if the market rate differs from `expected_rate`.
+    #[selector(name = "swapQuoteTokenForBaseToken")]
+    pub fn swap_quote_token_for_base_token_at_rate(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        quote_amount: U256,
+        expected_rate: U256,
+    ) -> Result<(), ContractError> {
+        // Ensure the market accepts direct swaps.
+        self.ensure_direct_swap_allowed(base_token, quote_token)?;
+
+        self.swap_quote_token_for_base_token_internal(
+            base_token,
+            quote_token,
+            quote_amount,
+            Some(expected_rate),
+        )
+    }
+
+    /// Swap base token for quote token, reverting with `SwapTooOld` once the protocol's max
+    /// swap age passed since `submitted_at`, the unix timestamp the caller signed at.
+    pub fn swap_base_token_for_quote_token_submitted_at(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        base_amount: U256,
+        submitted_at: u64,
+    ) -> Result<(), ContractError> {
+        // Ensure the market accepts direct swaps.
+        self.ensure_direct_swap_allowed(base_token, quote_token)?;
+
+        // Ensure the swap has not been pending too long.
+        self.ensure_swap_fresh(submitted_at)?;
+
+        self.swap_base_token_for_quote_token_internal(base_token, quote_token, base_amount, None)
+    }
+
+    /// Swap quote token for base token, reverting with `SwapTooOld` once the protocol's max
+    /// swap age passed since `submitted_at`, the unix timestamp the caller signed at.
+    pub fn swap_quote_token_for_base_token_submitted_at(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        quote_amount: U256,
+        submitted_at: u64,
+    ) -> Result<(), ContractError> {
+        // Ensure the market accepts direct swaps.
+        self.ensure_direct_swap_allowed(base_token, quote_token)?;
+
+        // Ensure the swap has not been pending too long.
+        self.ensure_swap_fresh(submitted_at)?;
+
+        self.swap_quote_token_for_base_token_internal(base_token, quote_token, quote_amount, None)
+    }
+
+    /// Set how long swaps submitted without a deadline stay executable, 0 to never expire them.
+    ///
+    /// Callable by the owner. Protects wallets that do not set deadlines from long-pending
+    /// swaps executing at stale terms.
+    pub fn set_max_swap_age(&mut self, max_age: u64) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Set max swap age.
+        let previous_max_age = self.max_swap_age.get().to::<u64>();
+        self.max_swap_age.set(U64::from(max_age));
+
+        // Emit event.
+        evm::log(MaxSwapAgeSet {
+            previous_max_age,
+            new_max_age: max_age,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch how long swaps submitted without a deadline stay executable, 0 if unbounded.
+    pub fn fetch_max_swap_age(&self) -> Result<u64, ContractError> {
+        Ok(self.max_swap_age.get().to::<u64>())
+    }
+
+    /// Swap an exact amount in across the markets of a pair, best effective rate first.
+    ///
+    /// Each market fills as much as its output reserve allows before the next best one takes
+    /// the rest. Markets requiring commit-reveal are skipped, and so are markets rejecting the
+    /// caller: presale allowlists, compliance registries, contract callers, purchase and sale
+    /// caps and hook vetoes. `direction` is a `SwapDirection`.
+    ///
+    /// Return the total amount out.
+    pub fn swap_best(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        direction: u8,
+        amount_in: U256,
+        min_amount_out: U256,
+    ) -> Result<U256, ContractError> {
+        let direction = SwapDirection::try_from(direction)?;
+
+        // Ensures the amount is not 0.
+        if amount_in.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Fill the best market until the amount in is used up or no market is left.
+        let mut remaining = amount_in;
+        let mut amount_out = U256::ZERO;
+        let mut filled = Vec::new();
+        while !remaining.is_zero() {
+            let market_index = self.best_market(base_token, quote_token, direction, &filled);
+            if market_index.is_zero() {
+                break;
+            }
+            filled.push(market_index);
+
+            // Size the leg so its gross amount out fits the output reserve.
+            let reserve_out = self.reserve_out(market_index, direction);
+            let (numerator, denominator) =
+                Self::direction_rate(direction, self.market_rate(market_index));
+            let capacity = math::mul_div(reserve_out, denominator, numerator, math::Rounding::Down)
+                .unwrap_or(U256::MAX);
+            let leg_amount_in = remaining.min(capacity);
+            if leg_amount_in.is_zero() {
+                continue;
+            }
+
+            // Fill the leg, moving on to the next market if this one rejects the caller.
+            match self.fill_swap(msg::sender(), market_index, direction, leg_amount_in, None) {
+                Ok(leg_amount_out) => {
+                    amount_out += leg_amount_out;
+                    remaining -= leg_amount_in;
+                }
+                Err(error) if Self::rejects_trader(&error) => continue,
+                Err(error) => return Err(error),
+            }
+        }
+
+        // Ensure the markets filled the whole amount in.
+        if !remaining.is_zero() {
+            return Err(ContractError::InsufficientLiquidity(
+                InsufficientLiquidity {},
+            ));
+        }
+
+        // Ensure the amount out is not below the minimum.
+        if amount_out < min_amount_out {
+            return Err(ContractError::InsufficientAmountOut(InsufficientAmountOut {
+                min_amount_out,
+                amount_out,
+            }));
+        }
+
+        // Settle the swap with the caller.
+        Self::settle_swap(base_token, quote_token, direction, amount_in, amount_out)?;
+
+        Ok(amount_out)
+    }
+
+    /// Swap exact amounts in across chosen markets of one pair, with a single transfer in and
+    /// a single payment out.
+    ///
+    /// `direction` is a `SwapDirection` shared by every leg.
+    ///
+    /// Return the total amount out.
+    pub fn swap_split(
+        &mut self,
+        direction: u8,
+        legs: Vec<SplitLeg>,
+        min_total_out: U256,
+    ) -> Result<U256, ContractError> {
+        let direction = SwapDirection::try_from(direction)?;
+
+        // Ensure there is a leg to fill.
+        let Some(&(first_market_index, _)) = legs.first() else {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        };
+
+        // Get the pair from the first leg.
+        let market = self.markets.get(Self::market_key(first_market_index)?);
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
+
+        let mut amount_in = U256::ZERO;
+        let mut total_out = U256::ZERO;
+        for (market_index, leg_amount_in) in legs {
+            // Ensures the amount is not 0.
+            if leg_amount_in.is_zero() {
+                return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+            }
+
+            // Ensure the leg trades the same pair and accepts direct swaps.
+            let market_key = Self::market_key(market_index)?;
+            let market = self.markets.get(market_key);
+            if market.base_token.get() != base_token || market.quote_token.get() != quote_token {
+                return Err(ContractError::SplitLegPairMismatch(SplitLegPairMismatch {
+                    market_index,
+                }));
+            }
+            if market.commit_reveal.get() {
+                return Err(ContractError::CommitRevealRequired(CommitRevealRequired {}));
+            }
+
+            // Fill the leg.
+            total_out +=
+                self.fill_swap(msg::sender(), market_key, direction, leg_amount_in, None)?;
+            amount_in += leg_amount_in;
+        }
+
+        // Ensure the amount out is not below the minimum.
+        if total_out < min_total_out {
+            return Err(ContractError::InsufficientAmountOut(InsufficientAmountOut {
+                min_amount_out: min_total_out,
+                amount_out: total_out,
+            }));
+        }
+
+        // Settle the swap with the caller.
+        Self::settle_swap(base_token, quote_token, direction, amount_in, total_out)?;
+
+        Ok(total_out)
+    }
+
+    /// Swap through a market at the terms of a discount voucher signed by its creator.
+    ///
+    /// The voucher grants its trader a rate, 0 to keep the market rate, and a market fee capped by
+    /// the current one, 0 to waive it, for up to `max_amount_in`. Each creator nonce can be
+    /// redeemed once, and a `deadline` of 0 never expires. `direction` is a `SwapDirection`.
+    ///
+    /// Return the amount out.
+    pub fn swap_with_voucher(
+        &mut self,
+        voucher: Voucher,
+        direction: u8,
+        amount_in: U256,
+        min_amount_out: U256,
+        signature: Bytes,
+    ) -> Result<U256, ContractError> {
+        let direction = SwapDirection::try_from(direction)?;
+        let (market_index, trader, exchange_rate, fee_bps, max_amount_in, nonce, deadline) =
+            voucher;
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensures the amount is not 0.
+        if amount_in.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensure the voucher is for the caller and covers the amount.
+        if trader != msg::sender() {
+            return Err(ContractError::InvalidVoucher(InvalidVoucher {}));
+        }
+        if amount_in > max_amount_in {
+            return Err(ContractError::VoucherAmountExceeded(
+                VoucherAmountExceeded { max_amount_in },
+            ));
+        }
+
+        // Ensure the voucher is still valid.
+        if deadline != 0 && chain::timestamp() > deadline {
+            return Err(ContractError::VoucherExpired(VoucherExpired { deadline }));
+        }
+        let market = self.markets.get(market_index);
+        let creator = market.creator.get();
+        if self.used_voucher_nonces.getter(creator).get(nonce) {
+            return Err(ContractError::VoucherUsed(VoucherUsed { nonce }));
+        }
+
+        // Ensure the market creator signed the voucher.
+        let digest = eip712::typed_data_hash(
+            self.current_domain_separator(),
+            eip712::voucher_hash(&voucher),
+        );
+        if eip712::recover(digest, &signature) != Some(creator) {
+            return Err(ContractError::InvalidVoucher(InvalidVoucher {}));
+        }
+
+        // Ensure the market accepts direct swaps.
+        if market.commit_reveal.get() {
+            return Err(ContractError::CommitRevealRequired(CommitRevealRequired {}));
+        }
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
+
+        // Redeem the voucher.
+        self.used_voucher_nonces.setter(creator).insert(nonce, true);
+        evm::log(VoucherRedeemed {
+            market_index: U256::from(market_index),
+            trader,
+            nonce,
+        });
+
+        // Fill the swap at the voucher terms.
+        let terms = SwapTerms::Voucher(exchange_rate, fee_bps);
+        let amount_out =
+            self.fill_swap_at(trader, market_index, direction, amount_in, None, terms)?;
+
+        // Ensure the amount out is not below the minimum.
+        if amount_out < min_amount_out {
+            return Err(ContractError::InsufficientAmountOut(
+                InsufficientAmountOut {
+                    min_amount_out,
+                    amount_out,
+                },
+            ));
+        }
+
+        // Settle the swap with the caller.
+        Self::settle_swap(base_token, quote_token, direction, amount_in, amount_out)?;
+
+        Ok(amount_out)
+    }
+
+    /// Fetch whether a market creator's voucher nonce was redeemed.
+    pub fn fetch_voucher_used(&self, creator: Address, nonce: U256) -> Result<bool, ContractError> {
+        Ok(self.used_voucher_nonces.getter(creator).get(nonce))
+    }
+
+    /// Swap through a market, paying and receiving the underlying asset of its vault sides.
+    ///
+    /// A vault input is swapped as the shares `previewDeposit` quotes and deposited into the
+    /// vault after the swap is recorded, a vault output is redeemed to the caller, see
+    /// `set_market_vaults`. `direction` is a `SwapDirection`, and `min_amount_out` applies to
+    /// the amount delivered.
+    ///
+    /// Return the amount delivered, in underlying assets for a vault output.
+    pub fn swap_via_underlying(
+        &mut self,
+        market_index: U256,
+        direction: u8,
+        amount_in: U256,
+        min_amount_out: U256,
+    ) -> Result<U256, ContractError> {
+        let direction = SwapDirection::try_from(direction)?;
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensures the amount is not 0.
+        if amount_in.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensure the market accepts direct swaps.
+        let market = self.markets.get(market_index);
+        if market.commit_reveal.get() {
+            return Err(ContractError::CommitRevealRequired(CommitRevealRequired {}));
+        }
+
+        // Get the tokens and vault flags of each side.
+        let (token_in, vault_in, token_out, vault_out) = match direction {
+            SwapDirection::BaseForQuote => (
+                market.base_token.get(),
+                market.base_vault.get(),
+                market.quote_token.get(),
+                market.quote_vault.get(),
+            ),
+            SwapDirection::QuoteForBase => (
+                market.quote_token.get(),
+                market.quote_vault.get(),
+                market.base_token.get(),
+                market.base_vault.get(),
+            ),
+        };
+        let trader = msg::sender();
+
+        // Get the vault shares the input deposits for.
+        let shares_in = if vault_in {
+            IErc4626::new(token_in)
+                .preview_deposit(Call::new(), amount_in)
+                .map_err(|_| ContractError::VaultCallFailed(VaultCallFailed { vault: token_in }))?
+        } else {
+            amount_in
+        };
+
+        // Fill the swap against the market reserves.
+        let amount_out = self.fill_swap(trader, market_index, direction, shares_in, None)?;
+
+        // Transfer the input from the caller, depositing underlying assets for at least the
+        // quoted shares.
+        if vault_in {
+            let shares = Self::deposit_to_vault(token_in, trader, amount_in);
+            if !shares.is_some_and(|shares| shares >= shares_in) {
+                return Err(ContractError::VaultCallFailed(VaultCallFailed {
+                    vault: token_in,
+                }));
+            }
+        } else if !Self::pull_tokens(token_in, trader, amount_in)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token: token_in,
+            }));
+        }
+
+        // Pay the output to the caller, redeeming vault shares for underlying assets.
+        let delivered = if vault_out {
+            IErc4626::new(token_out)
+                .redeem(Call::new(), amount_out, trader, address())
+                .map_err(|_| ContractError::VaultCallFailed(VaultCallFailed { vault: token_out }))?
+        } else if Self::push_tokens(token_out, trader, amount_out)? {
+            amount_out
+        } else {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token: token_out,
+            }));
+        };
+
+        // Ensure the amount delivered is not below the minimum.
+        if delivered < min_amount_out {
+            return Err(ContractError::InsufficientAmountOut(InsufficientAmountOut {
+                min_amount_out,
+                amount_out: delivered,
+            }));
+        }
+
+        Ok(delivered)
+    }
+
+    /// Swap through a market, paying the output to a callback target and then calling it.
+    ///
+    /// The target's `onSwapReceived(trader, tokenOut, amountOut, data)` runs after it holds
+    /// the output, so it can act on it in the same transaction, eg. stake it for the trader.
+    ///
+    /// Return the amount out.
+    pub fn swap_and_call(
+        &mut self,
+        market_index: U256,
+        direction: u8,
+        amount_in: U256,
+        min_amount_out: U256,
+        callback_target: Address,
+        data: Bytes,
+    ) -> Result<U256, ContractError> {
+        let direction = SwapDirection::try_from(direction)?;
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensures the amount is not 0.
+        if amount_in.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensure the market accepts direct swaps.
+        let market = self.markets.get(market_index);
+        if market.commit_reveal.get() {
+            return Err(ContractError::CommitRevealRequired(CommitRevealRequired {}));
+        }
+        let (token_in, token_out) = match direction {
+            SwapDirection::BaseForQuote => (market.base_token.get(), market.quote_token.get()),
+            SwapDirection::QuoteForBase => (market.quote_token.get(), market.base_token.get()),
+        };
+        let trader = msg::sender();
+
+        // Fill the swap against the market reserves.
+        let amount_out = self.fill_swap(trader, market_index, direction, amount_in, None)?;
+
+        // Ensure the amount out is not below the minimum.
+        if amount_out < min_amount_out {
+            return Err(ContractError::InsufficientAmountOut(InsufficientAmountOut {
+                min_amount_out,
+                amount_out,
+            }));
+        }
+
+        // Ensure the target passes the market's compliance registry and recipient policy.
+        self.ensure_compliant(market_index, callback_target)?;
+        self.ensure_recipient_allowed(market_index, trader, callback_target)?;
+
+        // Transfer the input token from the caller and the output token to the target.
+        if !Self::pull_tokens(token_in, trader, amount_in)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token: token_in,
+            }));
+        }
+        if !Self::push_tokens(token_out, callback_target, amount_out)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token: token_out,
+            }));
+        }
+
+        // Call the target with the swap results.
+        ISwapCallback::new(callback_target)
+            .on_swap_received(Call::new(), trader, token_out, amount_out, data.0.into())
+            .map_err(|_| {
+                ContractError::SwapCallbackFailed(SwapCallbackFailed {
+                    target: callback_target,
+                })
+            })?;
+
+        Ok(amount_out)
+    }
+
+    /// Swap tokens received through an ERC-1363 `transferAndCall`, paying the output to the
+    /// sender.
+    ///
+    /// Callable by the market's input token. `data` ABI-encodes the market index, the
+    /// `SwapDirection`, and the minimum amount out as (uint256, uint8, uint256). Rebasing markets
+    /// are not supported, their balance sync would count the received amount as yield.
+    ///
+    /// Return the ERC-1363 receiver selector.
+    pub fn on_transfer_received(
+        &mut self,
+        _operator: Address,
+        from: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<FixedBytes<4>, ContractError> {
+        // Decode the swap.
+        type SwapData = (sol_data::Uint<256>, sol_data::Uint<8>, sol_data::Uint<256>);
+        let (market_index, direction, min_amount_out) = SwapData::abi_decode(&data, true)
+            .map_err(|_| ContractError::InvalidSwapData(InvalidSwapData {}))?;
+        let direction = SwapDirection::try_from(direction)?;
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensures the amount is not 0.
+        if value.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensure the market accepts direct swaps and tracks raw amounts.
+        let market = self.markets.get(market_index);
+        if market.commit_reveal.get() {
+            return Err(ContractError::CommitRevealRequired(CommitRevealRequired {}));
+        }
+        if market.rebasing.get() {
+            return Err(ContractError::RebasingMarketNotSupported(
+                RebasingMarketNotSupported {},
+            ));
+        }
+
+        // Ensure the caller is the input token, it has already transferred the amount in.
+        let (token_in, token_out) = match direction {
+            SwapDirection::BaseForQuote => (market.base_token.get(), market.quote_token.get()),
+            SwapDirection::QuoteForBase => (market.quote_token.get(), market.base_token.get()),
+        };
+        if msg::sender() != token_in {
+            return Err(ContractError::TokenNotInMarket(TokenNotInMarket {
+                token: msg::sender(),
+            }));
+        }
+
+        // Fill the swap against the market reserves.
+        let amount_out = self.fill_swap(from, market_index, direction, value, None)?;
+
+        // Ensure the amount out is not below the minimum.
+        if amount_out < min_amount_out {
+            return Err(ContractError::InsufficientAmountOut(InsufficientAmountOut {
+                min_amount_out,
+                amount_out,
+            }));
+        }
+
+        // Transfer the output token to the sender.
+        if !Self::push_tokens(token_out, from, amount_out)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token: token_out,
+            }));
+        }
+
+        Ok(FixedBytes(ERC1363_RECEIVED))
+    }
+
+    /// Commit to a swap without revealing it, escrowing the attached ETH as a deposit.
+    ///
+    /// `commitment` is `compute_swap_commitment` of the swap to reveal in a later block. A
+    /// commitment never revealed can be cancelled with `cancel_swap_commitment`.
+    #[payable]
+    pub fn commit_swap(&mut self, commitment: B256) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "commit-reveal"))?;
+
+        // Ensure the commitment is not in use.
+        let mut swap_commitment = self.swap_commitments.setter(commitment);
+        if swap_commitment.committer.get() != Address::ZERO {
+            return Err(ContractError::SwapCommitmentExists(SwapCommitmentExists {}));
+        }
+
+        // Store commitment.
+        let committer = msg::sender();
+        let deposit = msg::value();
+        swap_commitment.committer.set(committer);
+        swap_commitment.deposit.set(deposit);
+        swap_commitment.committed_block.set(U64::from(chain::block_number()));
+
+        // Emit event.
+        evm::log(SwapCommitted {
+            commitment,
+            committer,
+            deposit,
+        });
+
+        Ok(())
+    }
+
+    /// Reveal a committed swap, execute it, and refund the deposit.
+    ///
+    /// `direction` is a `SwapDirection`, the swap must be revealed after its commit block.
+    pub fn reveal_and_execute(
+        &mut self,
+        market_index: U256,
+        direction: u8,
+        amount: U256,
+        salt: B256,
+    ) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "commit-reveal"))?;
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller committed to this swap.
+        let committer = msg::sender();
+        let commitment =
+            Self::derive_swap_commitment(committer, market_index, direction, amount, salt);
+        let swap_commitment = self.swap_commitments.get(commitment);
+        if swap_commitment.committer.get() != committer {
+            return Err(ContractError::SwapCommitmentNotFound(
+                SwapCommitmentNotFound {},
+            ));
+        }
+
+        // Ensure the swap is revealed after the commit block.
+        if chain::block_number() <= swap_commitment.committed_block.get().to::<u64>() {
+            return Err(ContractError::RevealTooEarly(RevealTooEarly {}));
+        }
+
+        // Consume commitment.
+        let deposit = swap_commitment.deposit.get();
+        let mut swap_commitment = self.swap_commitments.setter(commitment);
+        swap_commitment.committer.set(Address::ZERO);
+        swap_commitment.deposit.set(U256::ZERO);
+        swap_commitment.committed_block.set(U64::ZERO);
+
+        // Ensure the market exists, any market of the pair may be revealed against.
+        if self.market_status(market_index) == MarketStatus::None {
+            return Err(ContractError::MarketNotFound(MarketNotFound {}));
+        }
+
+        // Ensures the amount is not 0.
+        let direction = SwapDirection::try_from(direction)?;
+        if amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Emit event.
+        evm::log(SwapRevealed {
+            commitment,
+            committer,
+            market_index: U256::from(market_index),
+        });
+
+        // Fill the swap against the market reserves and settle it with the caller.
+        let amount_out = self.fill_swap(committer, market_index, direction, amount, None)?;
+        let market = self.markets.get(market_index);
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
+        Self::settle_swap(base_token, quote_token, direction, amount, amount_out)?;
+
+        // Refund deposit.
+        if !deposit.is_zero() && call::transfer_eth(committer, deposit).is_err() {
+            return Err(ContractError::DepositRefundFailed(DepositRefundFailed {}));
+        }
+
+        Ok(())
+    }
+
+    /// Cancel a swap commitment that was not revealed and refund its deposit.
+    ///
+    /// Callable by the committer once `SWAP_COMMITMENT_TIMEOUT` blocks passed since the commit
+    /// block, eg. after the market closed or the reveal keeps reverting. The delay keeps
+    /// committers from cancelling right after seeing where the rate moved.
+    pub fn cancel_swap_commitment(&mut self, commitment: B256) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "commit-reveal"))?;
+
+        // Ensure the caller committed to the swap.
+        let committer = msg::sender();
+        let swap_commitment = self.swap_commitments.get(commitment);
+        if swap_commitment.committer.get() != committer {
+            return Err(ContractError::SwapCommitmentNotFound(
+                SwapCommitmentNotFound {},
+            ));
+        }
+
+        // Ensure the commitment timed out.
+        let committed_block = swap_commitment.committed_block.get().to::<u64>();
+        let cancellable_at = committed_block.saturating_add(SWAP_COMMITMENT_TIMEOUT);
+        if chain::block_number() < cancellable_at {
+            return Err(ContractError::CommitmentNotExpired(CommitmentNotExpired {
+                cancellable_at,
+            }));
+        }
+
+        // Consume commitment.
+        let deposit = swap_commitment.deposit.get();
+        let mut swap_commitment = self.swap_commitments.setter(commitment);
+        swap_commitment.committer.set(Address::ZERO);
+        swap_commitment.deposit.set(U256::ZERO);
+        swap_commitment.committed_block.set(U64::ZERO);
+
+        // Emit event.
+        evm::log(SwapCommitmentCancelled {
+            commitment,
+            committer,
+            deposit,
+        });
+
+        // Refund deposit.
+        if !deposit.is_zero() && call::transfer_eth(committer, deposit).is_err() {
+            return Err(ContractError::DepositRefundFailed(DepositRefundFailed {}));
+        }
+
+        Ok(())
+    }
+
+    /// Compute the commitment of a swap for `commit_swap`.
+    pub fn compute_swap_commitment(
+        committer: Address,
+        market_index: U256,
+        direction: u8,
+        amount: U256,
+        salt: B256,
+    ) -> Result<B256, ContractError> {
+        Ok(Self::derive_swap_commitment(
+            committer,
+            Self::market_key(market_index)?,
+            direction,
+            amount,
+            salt,
+        ))
+    }
+
+    /// Fetch a swap commitment.
+    ///
+    /// Return swap commitment (committer, deposit, committed block).
+    pub fn fetch_swap_commitment(
+        &self,
+        commitment: B256,
+    ) -> Result<(Address, U256, u64), ContractError> {
+        let swap_commitment = self.swap_commitments.get(commitment);
+
+        Ok((
+            swap_commitment.committer.get(),
+            swap_commitment.deposit.get(),
+            swap_commitment.committed_block.get().to::<u64>(),
+        ))
+    }
+
+    /// Place a conditional order, escrowing `amount_in` until the market rate crosses
+    /// `trigger_rate`.
+    ///
+    /// `direction` is a `SwapDirection` and `condition` a `TriggerCondition`, eg. a
+    /// `BaseForQuote` order triggered `Below` a rate is a stop that exits once the rate drops.
+    /// Once triggered, anyone may fill the order with `fill_conditional_order`, so markets
+    /// restricted to EOA callers check the maker here rather than at the fill. An order with a
+    /// non-zero `expires_at` can not be filled from that timestamp on, and anyone may refund it
+    /// with `cancel_expired_orders`. A non-zero `min_fill` lets keepers fill the order in parts
+    /// of at least that much with `fill_conditional_order_partial`, `min_amount_out` applying
+    /// pro rata.
+    ///
+    /// Return the order id.
+    #[allow(clippy::too_many_arguments)]
+    pub fn place_conditional_order(
+        &mut self,
+        market_index: U256,
+        direction: u8,
+        condition: u8,
+        trigger_rate: U256,
+        amount_in: U256,
+        min_amount_out: U256,
+        expires_at: u64,
+        min_fill: U256,
+    ) -> Result<U256, ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "orders"))?;
+        let direction = SwapDirection::try_from(direction)?;
+        let condition = TriggerCondition::try_from(condition)?;
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensures the amount is not 0 and covers the minimum fill.
+        if amount_in.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+        if min_fill > amount_in {
+            return Err(ContractError::InvalidFillAmount(InvalidFillAmount {
+                min_fill,
+                remaining: amount_in,
+            }));
+        }
+
+        // Ensure the market accepts direct swaps from the maker.
+        self.ensure_market_active(market_index)?;
+        self.ensure_caller_allowed(market_index, msg::sender())?;
+        let market = self.markets.get(market_index);
+        if market.commit_reveal.get() {
+            return Err(ContractError::CommitRevealRequired(CommitRevealRequired {}));
+        }
+
+        // Ensures trigger rate is not 0.
+        if trigger_rate.is_zero() {
+            return Err(ContractError::ExchangeRateCanNotBeZero(
+                ExchangeRateCanNotBeZero {},
+            ));
+        }
+
+        // Ensure the order does not expire already.
+        if expires_at != 0 && expires_at <= chain::timestamp() {
+            return Err(ContractError::InvalidOrderExpiry(InvalidOrderExpiry {}));
+        }
+
+        // Ensure the trigger is within the rate band and not reached yet.
+        let (min_rate, max_rate) = (market.min_rate.get(), market.max_rate.get());
+        Self::ensure_rate_in_bounds(min_rate, max_rate, trigger_rate)?;
+        let rate = self.market_rate(market_index);
+        if condition.is_reached(rate, trigger_rate) {
+            return Err(ContractError::TriggerAlreadyReached(
+                TriggerAlreadyReached { rate },
+            ));
+        }
+        let token_in = match direction {
+            SwapDirection::BaseForQuote => market.base_token.get(),
+            SwapDirection::QuoteForBase => market.quote_token.get(),
+        };
+
+        // Store order.
+        let order_id = self.conditional_order_count.get() + U256::from(1);
+        self.conditional_order_count.set(order_id);
+        let maker = msg::sender();
+        let mut order = self.conditional_orders.setter(order_id);
+        order.maker.set(maker);
+        order.market_index.set(market_index);
+        order.direction.set(U8::from(direction as u8));
+        order.condition.set(U8::from(condition as u8));
+        order.trigger_rate.set(trigger_rate);
+        order.amount_in.set(amount_in);
+        order.min_amount_out.set(min_amount_out);
+        order.expires_at.set(U64::from(expires_at));
+        order.min_fill.set(min_fill);
+
+        // List the order in the market's open orders.
+        let mut open_orders = self.open_orders.setter(market_index);
+        open_orders.push(order_id);
+        let open_position = U64::from(open_orders.len());
+        self.conditional_orders
+            .setter(order_id)
+            .open_position
+            .set(open_position);
+
+        // Update total value locked.
+        self.add_tvl(token_in, amount_in);
+
+        // Emit event.
+        evm::log(ConditionalOrderPlaced {
+            order_id,
+            market_index: U256::from(market_index),
+            maker,
+            direction: direction as u8,
+            condition: condition as u8,
+            trigger_rate,
+            amount_in,
+            expires_at,
+            min_fill,
+        });
+
+        // Transfer the input token from the maker.
+        if !Self::pull_tokens(token_in, maker, amount_in)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token: token_in,
+            }));
+        }
+
+        Ok(order_id)
+    }
+
+    /// Cancel an open conditional order and refund its escrow to the maker.
+    ///
+    /// Callable by the order maker.
+    pub fn cancel_conditional_order(&mut self, order_id: U256) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "orders"))?;
+
+        // Ensure the order is open and the caller made it.
+        let order = self.conditional_orders.get(order_id);
+        let amount_in = order.amount_in.get();
+        if amount_in.is_zero() {
+            return Err(ContractError::OrderNotOpen(OrderNotOpen { order_id }));
+        }
+        let maker = order.maker.get();
+        if maker != msg::sender() {
+            return Err(ContractError::NotOrderMaker(NotOrderMaker {}));
+        }
+        let token_in = self.conditional_order_token_in(order_id)?;
+
+        // Close order.
+        self.close_conditional_order(order_id);
+
+        // Update total value locked.
+        self.sub_tvl(token_in, amount_in);
+
+        // Emit event.
+        evm::log(ConditionalOrderCancelled {
+            order_id,
+            maker,
+            amount_in,
+        });
+
+        // Refund the input token to the maker.
+        if !Self::push_tokens(token_in, maker, amount_in)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token: token_in,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Fill a triggered conditional order against the market reserves, paying the output to
+    /// the maker.
+    ///
+    /// Callable by anyone, eg. a keeper watching `fetch_conditional_order_triggered`.
+    ///
+    /// Return the amount out.
+    pub fn fill_conditional_order(&mut self, order_id: U256) -> Result<U256, ContractError> {
+        self.fill_conditional_order_internal(order_id, None)
+    }
+
+    /// Fill part of a triggered conditional order like `fill_conditional_order`.
+    ///
+    /// Callable by anyone. `amount_in` is taken out of the order's escrow, at least the order's
+    /// `min_fill` unless it is all that remains. Orders with a `min_fill` of 0 only fill whole.
+    ///
+    /// Return the amount out.
+    pub fn fill_conditional_order_partial(
+        &mut self,
+        order_id: U256,
+        amount_in: U256,
+    ) -> Result<U256, ContractError> {
+        self.fill_conditional_order_internal(order_id, Some(amount_in))
+    }
+
+    /// Settle triggered conditional orders of one market together, netting opposite directions
+    /// against each other.
+    ///
+    /// Callable by anyone. Each order is priced like `fill_conditional_order`, but only the net
+    /// of what the orders pay in and take out moves through the market reserves.
+    ///
+    /// Return the number of orders settled.
+    pub fn settle_orders(&mut self, order_ids: Vec<U256>) -> Result<U256, ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "orders"))?;
+
+        // Ensure there is an order to settle.
+        let Some(&first_order_id) = order_ids.first() else {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        };
+        let first_order = self.conditional_orders.get(first_order_id);
+        let market_index = first_order.market_index.get();
+
+        // Ensure the market accepts direct swaps.
+        let market = self.markets.get(market_index);
+        if market.commit_reveal.get() {
+            return Err(ContractError::CommitRevealRequired(CommitRevealRequired {}));
+        }
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
+
+        // Store the scheduled rate and the public rate if they took effect, and publish the
+        // effective rate if a decay moved it.
+        self.apply_pending_rate(market_index);
+        self.apply_sale_phase(market_index);
+        self.sync_effective_rate(market_index, RateChangeReason::Decay);
+
+        // Price each order, tallying (base, quote) paid in and taken out.
+        let mut fills = Vec::with_capacity(order_ids.len());
+        let (mut base_in, mut quote_in) = (U256::ZERO, U256::ZERO);
+        let (mut base_out, mut quote_out) = (U256::ZERO, U256::ZERO);
+        let (mut base_tips, mut quote_tips) = (U256::ZERO, U256::ZERO);
+        for order_id in order_ids {
+            let fill = self.price_conditional_order(order_id, market_index)?;
+            let (_, direction, amount_in, amount_out, protocol_fee, keeper_tip) = fill;
+            match direction {
+                SwapDirection::BaseForQuote => {
+                    base_in += amount_in;
+                    base_tips += keeper_tip;
+                    quote_out += amount_out + protocol_fee;
+                }
+                SwapDirection::QuoteForBase => {
+                    quote_in += amount_in;
+                    quote_tips += keeper_tip;
+                    base_out += amount_out + protocol_fee;
+                }
+            }
+            fills.push((order_id, fill));
+        }
+
+        // Ensure the market covers the net outflow of each token.
+        if base_out > base_in + self.reserve_out(market_index, SwapDirection::QuoteForBase)
+            || quote_out > quote_in + self.reserve_out(market_index, SwapDirection::BaseForQuote)
+        {
+            return Err(ContractError::InsufficientLiquidity(
+                InsufficientLiquidity {},
+            ));
+        }
+
+        // Update reserves by the net amounts.
+        let mut market = self.markets.setter(market_index);
+        let base_reserve = market.base_reserve.get();
+        let quote_reserve = market.quote_reserve.get();
+        market.base_reserve.set(base_reserve + base_in - base_out);
+        market
+            .quote_reserve
+            .set(quote_reserve + quote_in - quote_out);
+
+        // Update total value locked, the escrow already counts the amounts in and the tips.
+        self.sub_tvl(base_token, base_out + base_tips);
+        self.sub_tvl(quote_token, quote_out + quote_tips);
+        self.commit_rebasing_reserves(market_index);
+        self.last_swap_at.set(U64::from(chain::timestamp()));
+
+        // Emit event.
+        let settled = U256::from(fills.len());
+        evm::log(OrdersSettled {
+            market_index: U256::from(market_index),
+            orders: settled,
+            base_in,
+            quote_in,
+            base_out,
+            quote_out,
+        });
+
+        // Pay each maker and account the fill.
+        let keeper = msg::sender();
+        let creator = self.markets.get(market_index).creator.get();
+        for (order_id, (maker, direction, amount_in, amount_out, protocol_fee, _)) in fills {
+            let token_out = match direction {
+                SwapDirection::BaseForQuote => quote_token,
+                SwapDirection::QuoteForBase => base_token,
+            };
+
+            // Move the protocol fee to the creator and the treasury.
+            self.accrue_protocol_fee(token_out, creator, protocol_fee);
+
+            // Emit event.
+            evm::log(ConditionalOrderFilled {
+                order_id,
+                keeper,
+                amount_in,
+                amount_out,
+            });
+
+            // Let the hook see the fill.
+            self.call_after_swap(maker, market_index, direction, amount_in, amount_out)?;
+
+            // Transfer the output token to the maker.
+            if !Self::push_tokens(token_out, maker, amount_out)? {
+                return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                    token: token_out,
+                }));
+            }
+        }
+
+        // Transfer the tips to the keeper.
+        Self::pay_keeper_tip(keeper, base_token, base_tips)?;
+        Self::pay_keeper_tip(keeper, quote_token, quote_tips)?;
+
+        // Offer the fees to the collector.
+        self.push_fees(base_token);
+        self.push_fees(quote_token);
+
+        Ok(settled)
+    }
+
+    /// Fetch a conditional order.
+    pub fn fetch_conditional_order(
+        &self,
+        order_id: U256,
+    ) -> Result<ConditionalOrderInfo, ContractError> {
+        let order = self.conditional_orders.get(order_id);
+        Ok((
+            order.maker.get(),
+            U256::from(order.market_index.get()),
+            order.direction.get().to::<u8>(),
+            order.condition.get().to::<u8>(),
+            order.trigger_rate.get(),
+            order.amount_in.get(),
+            order.min_amount_out.get(),
+            order.expires_at.get().to::<u64>(),
+            order.min_fill.get(),
+        ))
+    }
+
+    /// Fetch whether a conditional order is open, not expired and its trigger reached.
+    pub fn fetch_conditional_order_triggered(&self, order_id: U256) -> Result<bool, ContractError> {
+        let order = self.conditional_orders.get(order_id);
+        if order.amount_in.get().is_zero() || self.conditional_order_expired(order_id) {
+            return Ok(false);
+        }
+        let condition = TriggerCondition::try_from(order.condition.get().to::<u8>())?;
+        let rate = self.market_rate(order.market_index.get());
+        Ok(condition.is_reached(rate, order.trigger_rate.get()))
+    }
+
+    /// Fetch the number of conditional orders placed, the id of the latest one.
+    pub fn fetch_conditional_order_count(&self) -> Result<U256, ContractError> {
+        Ok(self.conditional_order_count.get())
+    }
+
+    /// Fetch up to `limit` ids of a market's open conditional orders from `offset`, expired
+    /// orders included until they are cancelled.
+    ///
+    /// Closing an order moves the last open order into its place, so pages are not stable
+    /// across fills. A short page is the last one.
+    pub fn fetch_open_orders(
+        &self,
+        market_index: U256,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<U256>, ContractError> {
+        let open_orders = self.open_orders.get(Self::market_key(market_index)?);
+        let len = open_orders.len() as u64;
+        let end = offset.saturating_add(limit).min(len);
+
+        let mut order_ids = Vec::new();
+        for i in offset..end {
+            if let Some(order_id) = open_orders.get(i as usize) {
+                order_ids.push(order_id);
+            }
+        }
+
+        Ok(order_ids)
+    }
+
+    /// Cancel expired conditional orders, refunding their escrow to the makers.
+    ///
+    /// Callable by anyone. The caller earns each order's keeper tip out of its escrow, see
+    /// `set_market_keeper_tip`. Orders that are closed or not expired are skipped.
+    ///
+    /// Return the number of orders cancelled.
+    pub fn cancel_expired_orders(&mut self, order_ids: Vec<U256>) -> Result<U256, ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "orders"))?;
+
+        // Close each expired order.
+        let keeper = msg::sender();
+        let mut refunds = Vec::with_capacity(order_ids.len());
+        for order_id in order_ids {
+            let order = self.conditional_orders.get(order_id);
+            let amount_in = order.amount_in.get();
+            if amount_in.is_zero() || !self.conditional_order_expired(order_id) {
+                continue;
+            }
+            let maker = order.maker.get();
+            let market_index = order.market_index.get();
+            let token_in = self.conditional_order_token_in(order_id)?;
+
+            // Close order.
+            self.close_conditional_order(order_id);
+
+            // Take the keeper tip out of the escrow.
+            let keeper_tip = self.keeper_tip(market_index, amount_in);
+            let refund = amount_in - keeper_tip;
+
+            // Update total value locked.
+            self.sub_tvl(token_in, amount_in);
+
+            // Emit event.
+            evm::log(ConditionalOrderCancelled {
+                order_id,
+                maker,
+                amount_in: refund,
+            });
+
+            refunds.push((maker, token_in, refund, keeper_tip));
+        }
+
+        // Refund the makers and tip the caller.
+        let cancelled = U256::from(refunds.len());
+        for (maker, token_in, refund, keeper_tip) in refunds {
+            if !Self::push_tokens(token_in, maker, refund)? {
+                return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                    token: token_in,
+                }));
+            }
+            Self::pay_keeper_tip(keeper, token_in, keeper_tip)?;
+        }
+
+        Ok(cancelled)
+    }
+
+    /// Set the bounds keeper tips are clamped to, in basis points of the amount an execution
+    /// moves.
+    ///
+    /// Callable by the owner, capped by `MAX_KEEPER_TIP_BPS`. A `max_bps` of 0 turns tips off.
+    pub fn set_keeper_tip_bounds(
+        &mut self,
+        min_bps: u16,
+        max_bps: u16,
+    ) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensure the bounds are ordered and within the cap.
+        if max_bps > MAX_KEEPER_TIP_BPS {
+            return Err(ContractError::FeeTooHigh(FeeTooHigh {
+                fee_bps: max_bps,
+                max_fee_bps: MAX_KEEPER_TIP_BPS,
+            }));
+        }
+        if min_bps > max_bps {
+            return Err(ContractError::InvalidKeeperTipBounds(
+                InvalidKeeperTipBounds {},
+            ));
+        }
+
+        // Set keeper tip bounds.
+        let previous_min_bps = self.min_keeper_tip_bps.get().to::<u16>();
+        let previous_max_bps = self.max_keeper_tip_bps.get().to::<u16>();
+        self.min_keeper_tip_bps.set(U16::from(min_bps));
+        self.max_keeper_tip_bps.set(U16::from(max_bps));
+
+        // Emit event.
+        evm::log(KeeperTipBoundsSet {
+            previous_min_bps,
+            previous_max_bps,
+            new_min_bps: min_bps,
+            new_max_bps: max_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the (min, max) bounds of keeper tips in basis points.
+    pub fn fetch_keeper_tip_bounds(&self) -> Result<(u16, u16), ContractError> {
+        Ok((
+            self.min_keeper_tip_bps.get().to::<u16>(),
+            self.max_keeper_tip_bps.get().to::<u16>(),
+        ))
+    }
+
+    /// Set the tip keepers earn executing orders of a market, paid out of the order's input.
+    ///
+    /// Callable by the market creator. `mode` is a `KeeperTipMode`, `amount` a flat amount of
+    /// the input token or basis points of it. Tips are clamped to `fetch_keeper_tip_bounds`.
+    pub fn set_market_keeper_tip(
+        &mut self,
+        market_index: U256,
+        mode: u8,
+        amount: U256,
+    ) -> Result<(), ContractError> {
+        let mode = KeeperTipMode::try_from(mode)?;
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Set keeper tip.
+        let mut keeper_tip = self.keeper_tips.setter(market_index);
+        let previous_mode = keeper_tip.mode.get().to::<u8>();
+        let previous_amount = keeper_tip.amount.get();
+        keeper_tip.mode.set(U8::from(mode as u8));
+        keeper_tip.amount.set(amount);
+
+        // Emit event.
+        evm::log(MarketKeeperTipSet {
+            market_index: U256::from(market_index),
+            previous_mode,
+            previous_amount,
+            new_mode: mode as u8,
+            new_amount: amount,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the keeper tip of a market as (mode, amount).
+    pub fn fetch_market_keeper_tip(&self, market_index: U256) -> Result<(u8, U256), ContractError> {
+        let keeper_tip = self.keeper_tips.get(Self::market_key(market_index)?);
+        Ok((keeper_tip.mode.get().to::<u8>(), keeper_tip.amount.get()))
+    }
+
+    /// Fetch the tip a keeper earns executing an open conditional order now, in its input token.
+    pub fn fetch_conditional_order_tip(&self, order_id: U256) -> Result<U256, ContractError> {
+        let order = self.conditional_orders.get(order_id);
+        Ok(self.keeper_tip(order.market_index.get(), order.amount_in.get()))
+    }
+
+    /// Quote the amount out of an exact-in swap, net of the market and protocol fees.
+    ///
+    /// Rounded down, as swaps are. Ignores the market status and reserves.
+    pub fn quote_exact_in(
+        &self,
+        market_index: U256,
+        direction: u8,
+        amount_in: U256,
+    ) -> Result<U256, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the market exists.
+        if self.market_status(market_index) == MarketStatus::None {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        // Calculate the amount out.
+        let (numerator, denominator) =
+            Self::direction_rate(SwapDirection::try_from(direction)?, self.market_rate(market_index));
+        math::amount_out(amount_in, numerator, denominator, self.swap_fee_bps(market_index))
+            .ok_or(ContractError::MultiplicationOverflow(MultiplicationOverflow {}))
+    }
+
+    /// Quote many exact-in swaps, net of the market and protocol fees, in one call.
+    ///
+    /// Return the amount out of each request, 0 if it can not be priced, and whether the swap
+    /// would currently succeed: the market is active and its reserve covers the amount out.
+    pub fn quote_batch(
+        &self,
+        requests: Vec<QuoteRequest>,
+    ) -> Result<(Vec<U256>, Vec<bool>), ContractError> {
+        let mut amounts_out = Vec::with_capacity(requests.len());
+        let mut valid = Vec::with_capacity(requests.len());
+
+        for (market_index, direction, amount_in) in requests {
+            let market_index = narrow_market_index(market_index).unwrap_or_default();
+            let status = self.market_status(market_index);
+
+            // Skip unknown markets and directions.
+            let direction = SwapDirection::try_from(direction);
+            if status == MarketStatus::None || direction.is_err() {
+                amounts_out.push(U256::ZERO);
+                valid.push(false);
+                continue;
+            }
+            let direction = direction?;
+
+            // Calculate the amount out.
+            let (numerator, denominator) =
+                Self::direction_rate(direction, self.market_rate(market_index));
+            let amount_out = math::amount_out(
+                amount_in,
+                numerator,
+                denominator,
+                self.swap_fee_bps(market_index),
+            );
+            let protocol_fee = math::protocol_fee(
+                amount_in,
+                numerator,
+                denominator,
+                self.protocol_fee_bps.get().to::<u16>(),
+            )
+            .unwrap_or_default();
+
+            // Ensure the market can pay the amount out and the protocol fee.
+            let reserve = self.reserve_out(market_index, direction);
+            let executable = status == MarketStatus::Active
+                && !self.sunset.get()
+                && self.direction_mode(market_index).allows(direction)
+                && !amount_in.is_zero()
+                && amount_out.is_some_and(|amount_out| amount_out + protocol_fee <= reserve);
+
+            amounts_out.push(amount_out.unwrap_or_default());
+            valid.push(executable);
+        }
+
+        Ok((amounts_out, valid))
+    }
+
+    /// Quote the amount in an exact-out swap needs, including the market and protocol fees.
+    ///
+    /// Rounded up, so swapping it yields at least `amount_out`. Ignores the market status and reserves.
+    pub fn quote_exact_out(
+        &self,
+        market_index: U256,
+        direction: u8,
+        amount_out: U256,
+    ) -> Result<U256, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the market exists.
+        if self.market_status(market_index) == MarketStatus::None {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        // Calculate the amount in.
+        let (numerator, denominator) =
+            Self::direction_rate(SwapDirection::try_from(direction)?, self.market_rate(market_index));
+        math::amount_in(amount_out, numerator, denominator, self.swap_fee_bps(market_index))
+            .ok_or(ContractError::MultiplicationOverflow(MultiplicationOverflow {}))
+    }
+
+    /// Check whether an exact-in swap of `amount` by `user` would currently succeed, so UIs can
+    /// show what is missing before the user signs.
+    ///
+    /// Return a mask of the failed `SwapPrecondition`s, 0 if none failed. Ignores the market's
+    /// hook and caller policy.
+    pub fn check_swap_preconditions(
+        &self,
+        user: Address,
+        market_index: U256,
+        direction: u8,
+        amount: U256,
+    ) -> Result<u16, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        let direction = SwapDirection::try_from(direction)?;
+
+        // Ensure the market exists.
+        let status = self.market_status(market_index);
+        if status == MarketStatus::None {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        // Get the trader's balance and allowance of the input token.
+        let market = self.markets.get(market_index);
+        let token_in = IErc20::new(match direction {
+            SwapDirection::BaseForQuote => market.base_token.get(),
+            SwapDirection::QuoteForBase => market.quote_token.get(),
+        });
+        let balance = token_in.balance_of(Call::new(), user).unwrap_or_default();
+        let allowance = token_in
+            .allowance(Call::new(), user, address())
+            .unwrap_or_default();
+
+        // Calculate the amount out.
+        let (numerator, denominator) =
+            Self::direction_rate(direction, self.market_rate(market_index));
+        let amount_out = math::amount_out(
+            amount,
+            numerator,
+            denominator,
+            self.swap_fee_bps(market_index),
+        );
+        let protocol_fee = math::protocol_fee(
+            amount,
+            numerator,
+            denominator,
+            self.protocol_fee_bps.get().to::<u16>(),
+        )
+        .unwrap_or_default();
+
+        // Check the market can pay the amount out and the protocol fee.
+        let reserve = self.reserve_out(market_index, direction);
+        let reserve_short =
+            amount_out.map_or(true, |amount_out| amount_out + protocol_fee > reserve);
+
+        // Check the trader may swap.
+        let access_denied = self.ensure_compliant(market_index, user).is_err()
+            || (self.sale_phase(market_index) == SalePhase::Presale
+                && !self.presales.get(market_index).allowed.get(user));
+
+        // Check a buy stays within the trader's allocation and the market's sale.
+        let buy = direction == SwapDirection::QuoteForBase;
+        let remaining = self.remaining_allocation(market_index, user);
+        let over_allocation = buy && amount_out.unwrap_or_default() > remaining;
+        let sale = self.sales.get(market_index);
+        let sale_status = SaleStatus::from(sale.status.get().to::<u8>());
+        let hard_cap = sale.hard_cap.get();
+        let sale_capped = sale_status != SaleStatus::None
+            && (sale_status != SaleStatus::Open
+                || chain::timestamp() >= sale.ends_at.get().to::<u64>()
+                || (!hard_cap.is_zero() && sale.raised.get() + amount > hard_cap));
+
+        let inactive = status != MarketStatus::Active || self.sunset.get();
+        let direction_enabled = self.direction_mode(market_index).allows(direction);
+        let checks = [
+            (SwapPrecondition::AmountZero, amount.is_zero()),
+            (SwapPrecondition::MarketNotActive, inactive),
+            (SwapPrecondition::InsufficientBalance, balance < amount),
+            (SwapPrecondition::InsufficientAllowance, allowance < amount),
+            (SwapPrecondition::InsufficientReserve, reserve_short),
+            (SwapPrecondition::AccessDenied, access_denied),
+            (SwapPrecondition::PurchaseCapExceeded, over_allocation),
+            (SwapPrecondition::SaleCapExceeded, buy && sale_capped),
+            (SwapPrecondition::DirectionDisabled, !direction_enabled),
+        ];
+
+        let mut failures = 0;
+        for (precondition, failed) in checks {
+            if failed {
+                failures |= 1 << precondition as u16;
+            }
+        }
+
+        Ok(failures)
+    }
+
+    /// Quote an exact-in swap of `amount` in both directions, net of the market and protocol fees.
+    ///
+    /// Return (quote token out for `amount` base token, base token out for `amount` quote token,
+    /// base token per quote token scaled by 1e18), all rounded down as swaps are. Ignores the
+    /// market status and reserves.
+    pub fn quote_both_directions(
+        &self,
+        market_index: U256,
+        amount: U256,
+    ) -> Result<(U256, U256, U256), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the market exists.
+        if self.market_status(market_index) == MarketStatus::None {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        // Calculate the amount out in each direction.
+        let exchange_rate = self.market_rate(market_index);
+        let fee_bps = self.swap_fee_bps(market_index);
+        let quote_out = math::amount_out(amount, exchange_rate, U256::from(1), fee_bps)
+            .ok_or(ContractError::MultiplicationOverflow(MultiplicationOverflow {}))?;
+        let base_out = math::amount_out(amount, U256::from(1), exchange_rate, fee_bps)
+            .ok_or(ContractError::DivisionUnderflow(DivisionUnderflow {}))?;
+        let inverse_rate = math::inverse_rate(exchange_rate)
+            .ok_or(ContractError::DivisionUnderflow(DivisionUnderflow {}))?;
+
+        Ok((quote_out, base_out, inverse_rate))
+    }
+
+    /// Fetch initialization status.
+    pub fn fetch_initialization_status(&self) -> Result<bool, ContractError> {
+        Ok(self.current_initialized_version() > 0)
+    }
+
+    /// Fetch the latest initialized version.
+    pub fn initialized_version(&self) -> Result<u64, ContractError> {
+        Ok(self.current_initialized_version())
+    }
+
+    /// Fetch contract name.
+    pub fn name(&self) -> Result<String, ContractError> {
+        Ok(self.name.get_string())
+    }
+
+    /// Fetch semantic version stored at initialization.
+    pub fn version(&self) -> Result<String, ContractError> {
+        Ok(self.version.get_string())
+    }
+
+    /// Fetch implementation info.
+    /// Useful for telling deployments apart across upgrades and networks.
+    ///
+    /// Return (name, stored version, code version, chain id, block number, timestamp)
+    /// where chain id, block number and timestamp were recorded at initialization.
+    pub fn implementation_info(
+        &self,
+    ) -> Result<(String, String, String, u64, u64, u64), ContractError> {
+        Ok((
+            self.name.get_string(),
+            self.version.get_string(),
+            String::from(CONTRACT_VERSION),
+            self.initialized_chain_id.get().to::<u64>(),
+            self.initialized_block.get().to::<u64>(),
+            self.initialized_at.get().to::<u64>(),
+        ))
+    }
+
+    /// Fetch protocol configuration in one call.
+    /// Useful for frontends bootstrapping their state.
+    ///
+    /// Return (owner, pending owner, admin threshold, deterministic market ids, market id salt,
+    /// max market fee bps, next market index, protocol fee bps, treasury recipient, fee collector,
+    /// sunset, creation mode) where the creation mode is a `CreationMode`.
+    pub fn fetch_config(&self) -> Result<ProtocolConfig, ContractError> {
+        Ok((
+            self.owner.get(),
+            self.pending_owner.get(),
+            self.admin_threshold.get().to::<u64>(),
+            self.deterministic_market_ids.get(),
+            self.market_id_salt.get(),
+            MAX_MARKET_FEE_BPS,
+            U256::from(self.market_index.get()),
+            self.protocol_fee_bps.get().to::<u16>(),
+            self.treasury_recipient.get(),
+            self.fee_collector.get(),
+            self.sunset.get(),
+            self.creation_mode.get().to::<u8>(),
+        ))
+    }
+
+    /// Fetch EIP-712 domain separator.
+    ///
+    /// The separator is rebuilt when the chain id differs from the cached one (eg. after a fork).
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    pub fn domain_separator(&self) -> Result<B256, ContractError> {
+        Ok(self.current_domain_separator())
+    }
+
+    /// Fetch the next unused signature nonce of an address.
+    pub fn nonces(&self, owner: Address) -> Result<U256, ContractError> {
+        Ok(self.nonces.get(owner))
+    }
+
+    /// Fetch current market index.
+    pub fn fetch_current_market_index(&self) -> Result<U256, ContractError> {
+        Ok(U256::from(self.market_index.get()))
+    }
+
+    /// Fetch exchange rate.
+    pub fn fetch_exchange_rate(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+    ) -> Result<U256, ContractError> {
+        // Ensures base token address is not a zero address.
+        if base_token == Address::ZERO {
+            return Err(ContractError::BaseTokenCanNotBeZeroAddress(
+                BaseTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Ensures quote token address is not a zero address.
+        if quote_token == Address::ZERO {
+            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
+                QuoteTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Get market from the base token and quote token.
+        let base_token_map = self.indexes.getter(base_token);
+        let quote_token_map = base_token_map.getter(quote_token);
+        let market_index = quote_token_map.get();
+
+        // Get the exchange rate.
+        let exchange_rate = self.market_rate(market_index);
+
+        Ok(exchange_rate)
+    }
+
+    /// Fetch market index.
+    /// A pair with several markets resolves to the oldest open one.
+    pub fn fetch_market_id(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+    ) -> Result<U256, ContractError> {
+        // Ensures the base token address is not a zero address.
+        if base_token == Address::ZERO {
+            return Err(ContractError::BaseTokenCanNotBeZeroAddress(
+                BaseTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Ensures the quote token address is not a zero address.
+        if quote_token == Address::ZERO {
+            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
+                QuoteTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Get market from the base token and quote token.
+        let base_token_map = self.indexes.getter(base_token);
+        let quote_token_map = base_token_map.getter(quote_token);
+        let market_index = quote_token_map.get();
+
+        Ok(U256::from(market_index))
+    }
+
+    /// Fetch market by tokens.
+    /// A pair with several markets resolves to the oldest open one.
+    pub fn fetch_market_by_tokens(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+    ) -> Result<(Address, Address, U256, u8), ContractError> {
+        // Ensures the base token address is not a zero address.
+        if base_token == Address::ZERO {
+            return Err(ContractError::BaseTokenCanNotBeZeroAddress(
+                BaseTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Ensures the quote token address is not a zero address.
+        if quote_token == Address::ZERO {
+            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
+                QuoteTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Get market from the base token and quote token.
+        let base_token_map = self.indexes.getter(base_token);
+        let quote_token_map = base_token_map.getter(quote_token);
+        let market_index = quote_token_map.get();
+
+        // Get market.
+        let market = self.markets.get(market_index);
+
+        Ok((
+            market.base_token.get(),
+            market.quote_token.get(),
+            self.market_rate(market_index),
+            self.market_status(market_index) as u8,
+        ))
+    }
+
+    /// Fetch market by id.
+    /// Useful for pagination.
+    ///
+    /// Return market (base_token, quote_token, exchange_rate, status, base_symbol,
+    /// quote_symbol). Symbols are read at creation, up to 32 bytes and right-padded with zeros,
+    /// or zero if the token does not report one.
+    pub fn fetch_market_by_id(
+        &self,
+        market_index: U256,
+    ) -> Result<(Address, Address, U256, u8, B256, B256), ContractError> {
+        // Ensure the index is valid.
+        let market_index = Self::market_key(market_index)?;
+        if market_index.ge(&self.market_index.get()) || market_index.eq(&U64::from(0)) {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        // Get market.
+        let market = self.markets.get(market_index);
+
+        Ok((
+            market.base_token.get(),
+            market.quote_token.get(),
+            self.market_rate(market_index),
+            self.market_status(market_index) as u8,
+            self.base_symbols.get(market_index),
+            self.quote_symbols.get(market_index),
+        ))
+    }
+
+    /// Fetch up to `limit` markets from `offset` with the data a market list renders, so a
+    /// frontend can bootstrap in one call.
+    ///
+    /// Markets are ordered by index, offset 0 being market 1, a short page is the last one.
+    /// Fees are in basis points, volumes count both directions since volume tracking began.
+    pub fn fetch_markets_enriched(
+        &self,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<MarketSummary>, ContractError> {
+        let len = self.market_index.get().to::<u64>().saturating_sub(1);
+        let end = offset.saturating_add(limit).min(len);
+
+        let protocol_fee_bps = self.protocol_fee_bps.get().to::<u16>();
+        let mut markets = Vec::new();
+        for i in offset..end {
+            let market_index = U64::from(i + 1);
+            let market = self.markets.get(market_index);
+            let volume = self.market_volumes.get(market_index);
+            markets.push((
+                U256::from(market_index),
+                market.base_token.get(),
+                market.quote_token.get(),
+                market.base_decimals.get().to::<u8>(),
+                market.quote_decimals.get().to::<u8>(),
+                self.market_rate(market_index),
+                market.base_reserve.get(),
+                market.quote_reserve.get(),
+                self.market_status(market_index) as u8,
+                self.direction_mode(market_index) as u8,
+                self.market_fee_bps(market_index, false),
+                protocol_fee_bps,
+                volume.base_volume.get(),
+                volume.quote_volume.get(),
+                market.creator.get(),
+            ));
+        }
+
+        Ok(markets)
+    }
+
+    /// Fetch market status.
+    pub fn fetch_market_status(&self, market_index: U256) -> Result<u8, ContractError> {
+        Ok(self.market_status(Self::market_key(market_index)?) as u8)
+    }
+
+    /// Fetch market health flags.
+    /// Useful for routers filtering out markets they should not quote against.
+    ///
+    /// Return (base reserve empty, quote reserve empty, expired, paused or flagged, closed).
+    pub fn fetch_market_health(
+        &self,
+        market_index: U256,
+    ) -> Result<(bool, bool, bool, bool, bool), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the market exists.
+        let status = self.market_status(market_index);
+        if status == MarketStatus::None {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        let market = self.markets.get(market_index);
+        Ok((
+            market.base_reserve.get().is_zero(),
+            market.quote_reserve.get().is_zero(),
+            status == MarketStatus::Expired,
+            status == MarketStatus::Paused || status == MarketStatus::Flagged,
+            status == MarketStatus::Closed,
+        ))
+    }
+
+    /// Fetch the anomaly counters and heartbeat of the contract, for monitoring.
+    ///
+    /// Only failures the contract survives are counted, a reverted call leaves no trace.
+    ///
+    /// Return (failed external calls, auto-pauses, circuit breaker trips, last swap at).
+    pub fn fetch_health(&self) -> Result<(u64, u64, u64, u64), ContractError> {
+        Ok((
+            self.failed_external_calls.get().to::<u64>(),
+            self.auto_pauses.get().to::<u64>(),
+            self.circuit_breaker_trips.get().to::<u64>(),
+            self.last_swap_at.get().to::<u64>(),
+        ))
+    }
+
+    /// Pause an active market.
+    ///
+    /// Callable by the market creator or the owner.
+    pub fn pause_market(&mut self, market_index: U256) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "pausable"))?;
+
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller may manage the market.
+        self.only_market_creator_or_owner(market_index)?;
+
+        // Ensure the market is active.
+        self.ensure_market_active(market_index)?;
+
+        // Set market status.
+        self.set_market_status(market_index, MarketStatus::Paused);
+
+        Ok(())
+    }
+
+    /// Resume a paused market.
+    ///
+    /// Callable by the market creator or the owner.
+    pub fn unpause_market(&mut self, market_index: U256) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "pausable"))?;
+
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller may manage the market.
+        self.only_market_creator_or_owner(market_index)?;
+
+        // Ensure the market is paused.
+        let status = self.market_status(market_index);
+        if status != MarketStatus::Paused {
+            return Err(ContractError::MarketNotPaused(MarketNotPaused {
+                status: status as u8,
+            }));
+        }
+
+        // Set market status.
+        self.set_market_status(market_index, MarketStatus::Active);
+
+        Ok(())
+    }
+
+    /// Flag a market as mispriced or listing a scam token, halting swaps until the owner
+    /// resolves the flag.
+    ///
+    /// Callable by a guardian. `reason_hash` commits to the report, eg. the hash of an
+    /// off-chain write-up.
+    pub fn flag_market(
+        &mut self,
+        market_index: U256,
+        reason_hash: B256,
+    ) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "pausable"))?;
+
+        // Ensure the caller is a guardian.
+        self.only_guardian()?;
+
+        // Ensure the market is active or paused.
+        let market_index = Self::market_key(market_index)?;
+        let status = self.market_status(market_index);
+        if status != MarketStatus::Active && status != MarketStatus::Paused {
+            return Err(ContractError::MarketNotActive(MarketNotActive {
+                status: status as u8,
+            }));
+        }
+
+        // Set market flag and status.
+        self.market_flags.insert(market_index, reason_hash);
+        self.set_market_status(market_index, MarketStatus::Flagged);
+
+        // Emit event.
+        evm::log(MarketFlagged {
+            market_index: U256::from(market_index),
+            guardian: msg::sender(),
+            reason_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Resolve a flagged market.
+    ///
+    /// Callable by the owner. Reinstated markets become active again, others are closed and
+    /// their reserves returned to the creator.
+    ///
+    /// Clears the solvency breaches recorded for the market's tokens, so `verify_solvency` pays
+    /// for the next one.
+    pub fn resolve_flag(
+        &mut self,
+        market_index: U256,
+        reinstate: bool,
+    ) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "pausable"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensure the market is flagged.
+        let market_index = Self::market_key(market_index)?;
+        let status = self.market_status(market_index);
+        if status != MarketStatus::Flagged {
+            return Err(ContractError::MarketNotFlagged(MarketNotFlagged {
+                status: status as u8,
+            }));
+        }
+
+        // Clear market flag and the shortfalls of its tokens.
+        self.market_flags.delete(market_index);
+        let market = self.markets.get(market_index);
+        let tokens = [market.base_token.get(), market.quote_token.get()];
+        for token in tokens {
+            self.breached_tokens.delete(token);
+        }
+
+        // Emit event.
+        evm::log(MarketFlagResolved {
+            market_index: U256::from(market_index),
+            reinstated: reinstate,
+        });
+
+        // Reinstate or close the market.
+        if reinstate {
+            self.set_market_status(market_index, MarketStatus::Active);
+            return Ok(());
+        }
+
+        self.close_market_internal(market_index)
+    }
+
+    /// Fetch the reason hash of a market's pending flag, 0 if not flagged.
+    pub fn fetch_market_flag(&self, market_index: U256) -> Result<B256, ContractError> {
+        Ok(self.market_flags.get(Self::market_key(market_index)?))
+    }
+
+    /// Set the timestamp after which a market stops accepting swaps.
+    ///
+    /// An expiry of 0 disables it. Callable by the market creator.
+    pub fn set_market_expiry(
+        &mut self,
+        market_index: U256,
+        expires_at: u64,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the market has not expired or closed.
+        let status = self.market_status(market_index);
+        if status != MarketStatus::Active && status != MarketStatus::Paused {
+            return Err(ContractError::MarketNotActive(MarketNotActive {
+                status: status as u8,
+            }));
+        }
+
+        // Set market expiry.
+        let mut market = self.markets.setter(market_index);
+        let previous_expires_at = market.expires_at.get().to::<u64>();
+        market.expires_at.set(U64::from(expires_at));
+
+        // Emit event.
+        evm::log(MarketExpirySet {
+            market_index: U256::from(market_index),
+            previous_expires_at,
+            new_expires_at: expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Set the fee charged on swap output, kept in the market reserves.
+    ///
+    /// Callable by the market creator, capped by `MAX_MARKET_FEE_BPS`.
+    pub fn set_market_fee(
+        &mut self,
+        market_index: U256,
+        fee_bps: u16,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the market does not charge a fee tier.
+        let tier_id = self.market_fee_tiers.get(market_index).to::<u64>();
+        if tier_id != 0 {
+            return Err(ContractError::MarketFeeTiered(MarketFeeTiered { tier_id }));
+        }
+
+        // Ensure the fee is within the cap.
+        if fee_bps > MAX_MARKET_FEE_BPS {
+            return Err(ContractError::FeeTooHigh(FeeTooHigh {
+                fee_bps,
+                max_fee_bps: MAX_MARKET_FEE_BPS,
+            }));
+        }
+
+        // Set market fee.
+        let mut market = self.markets.setter(market_index);
+        let previous_fee_bps = market.fee_bps.get().to::<u16>();
+        market.fee_bps.set(U16::from(fee_bps));
+
+        // Emit event.
+        evm::log(MarketFeeSet {
+            market_index: U256::from(market_index),
+            previous_fee_bps,
+            new_fee_bps: fee_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch market fee in basis points, the fee of its tier if it charges one.
+    pub fn fetch_market_fee(&self, market_index: U256) -> Result<u16, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        Ok(self.market_fee_bps(market_index, false))
+    }
+
+    /// Set the market fee of a fee tier, for every market charging it.
+    ///
+    /// Callable by the owner, capped by `MAX_MARKET_FEE_BPS`. Tier 0 is reserved for markets
+    /// charging their own fee.
+    pub fn set_fee_tier(&mut self, tier_id: u64, fee_bps: u16) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensure the tier is not the reserved one.
+        if tier_id == 0 {
+            return Err(ContractError::FeeTierNotFound(FeeTierNotFound { tier_id }));
+        }
+
+        // Ensure the fee is within the cap.
+        if fee_bps > MAX_MARKET_FEE_BPS {
+            return Err(ContractError::FeeTooHigh(FeeTooHigh {
+                fee_bps,
+                max_fee_bps: MAX_MARKET_FEE_BPS,
+            }));
+        }
+
+        // Set fee tier.
+        let mut tier = self.fee_tiers.setter(U64::from(tier_id));
+        let previous_fee_bps = tier.fee_bps.get().to::<u16>();
+        tier.enabled.set(true);
+        tier.fee_bps.set(U16::from(fee_bps));
+
+        // Emit event.
+        evm::log(FeeTierSet {
+            tier_id,
+            previous_fee_bps,
+            new_fee_bps: fee_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether a fee tier was set and its market fee in basis points.
+    pub fn fetch_fee_tier(&self, tier_id: u64) -> Result<(bool, u16), ContractError> {
+        let tier = self.fee_tiers.get(U64::from(tier_id));
+        Ok((tier.enabled.get(), tier.fee_bps.get().to::<u16>()))
+    }
+
+    /// Move a market to a fee tier, or back to its own fee of `set_market_fee` with tier 0.
+    ///
+    /// Callable by the market creator.
+    pub fn set_market_fee_tier(
+        &mut self,
+        market_index: U256,
+        tier_id: u64,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the tier exists.
+        if tier_id != 0 {
+            self.ensure_fee_tier(tier_id)?;
+        }
+
+        // Set market fee tier.
+        self.set_market_fee_tier_internal(market_index, tier_id);
+
+        Ok(())
+    }
+
+    /// Fetch the fee tier a market charges, 0 if it charges its own fee.
+    pub fn fetch_market_fee_tier(&self, market_index: U256) -> Result<u64, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        Ok(self.market_fee_tiers.get(market_index).to::<u64>())
+    }
+
+    /// Set the market fee conditional order fills pay, instead of the swap fee of
+    /// `set_market_fee`.
+    ///
+    /// Callable by the market creator, capped by `MAX_MARKET_FEE_BPS`. Like the swap fee, it is
+    /// kept in the market reserves and charged before the protocol fee.
+    pub fn set_market_maker_fee(
+        &mut self,
+        market_index: U256,
+        fee_bps: u16,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the fee is within the cap.
+        if fee_bps > MAX_MARKET_FEE_BPS {
+            return Err(ContractError::FeeTooHigh(FeeTooHigh {
+                fee_bps,
+                max_fee_bps: MAX_MARKET_FEE_BPS,
+            }));
+        }
+
+        // Set maker fee.
+        let previous_fee_bps = self.market_fee_bps(market_index, true);
+        let mut maker_fee = self.maker_fees.setter(market_index);
+        maker_fee.enabled.set(true);
+        maker_fee.fee_bps.set(U16::from(fee_bps));
+
+        // Emit event.
+        evm::log(MarketMakerFeeSet {
+            market_index: U256::from(market_index),
+            previous_fee_bps,
+            new_fee_bps: fee_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Require two-sided deposits into a market to match its rate, within `tolerance_bps` of
+    /// the quote amount the base amount is worth.
+    ///
+    /// Callable by the market creator. Guards against mispricing the reserves relative to the
+    /// posted rate, one-sided deposits are always accepted.
+    pub fn set_market_deposit_tolerance(
+        &mut self,
+        market_index: U256,
+        enabled: bool,
+        tolerance_bps: u16,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the tolerance is at most 100%.
+        if tolerance_bps > BPS {
+            return Err(ContractError::InvalidDepositTolerance(
+                InvalidDepositTolerance {},
+            ));
+        }
+
+        // Set deposit tolerance.
+        let mut tolerance = self.deposit_tolerances.setter(market_index);
+        let previous_enabled = tolerance.enabled.get();
+        let previous_tolerance_bps = tolerance.tolerance_bps.get().to::<u16>();
+        tolerance.enabled.set(enabled);
+        tolerance.tolerance_bps.set(U16::from(tolerance_bps));
+
+        // Emit event.
+        evm::log(MarketDepositToleranceSet {
+            market_index: U256::from(market_index),
+            previous_enabled,
+            previous_tolerance_bps,
+            new_enabled: enabled,
+            new_tolerance_bps: tolerance_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether two-sided deposits into a market must match its rate, and the tolerance in
+    /// basis points.
+    pub fn fetch_market_deposit_tolerance(
+        &self,
+        market_index: U256,
+    ) -> Result<(bool, u16), ContractError> {
+        let tolerance = self.deposit_tolerances.get(Self::market_key(market_index)?);
+        Ok((
+            tolerance.enabled.get(),
+            tolerance.tolerance_bps.get().to::<u16>(),
+        ))
+    }
+
+    /// Fetch the fee schedule of a market in basis points, before any hook adjusts it.
+    ///
+    /// Return (maker fee, taker fee, protocol fee), the protocol fee is charged on top of both.
+    pub fn fetch_market_fee_schedule(
+        &self,
+        market_index: U256,
+    ) -> Result<(u16, u16, u16), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        Ok((
+            self.market_fee_bps(market_index, true),
+            self.market_fee_bps(market_index, false),
+            self.protocol_fee_bps.get().to::<u16>(),
+        ))
+    }
+
+    /// Approve or revoke a hook contract markets may register.
+    ///
+    /// Callable by the owner. Markets stop calling a hook once its approval is revoked.
+    pub fn set_hook_approval(
+        &mut self,
+        hook: Address,
+        approved: bool,
+    ) -> Result<(), ContractError> {
+        Self::ensure_feature(cfg!(feature = "hooks"))?;
+
+        // Ensure the caller is the owner.
+        self.only_owner()?;
+
+        // Set hook approval.
+        let previous_approved = self.is_approved_hook.get(hook);
+        self.is_approved_hook.insert(hook, approved);
+
+        // Emit event.
+        evm::log(HookApprovalSet {
+            hook,
+            previous_approved,
+            new_approved: approved,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether a hook contract is approved.
+    pub fn fetch_is_approved_hook(&self, hook: Address) -> Result<bool, ContractError> {
+        Ok(self.is_approved_hook.get(hook))
+    }
+
+    /// Set the gas forwarded to each hook call, 0 to forward all gas.
+    ///
+    /// Callable by the owner.
+    pub fn set_hook_gas_limit(&mut self, gas_limit: u64) -> Result<(), ContractError> {
+        Self::ensure_feature(cfg!(feature = "hooks"))?;
+
+        // Ensure the caller is the owner.
+        self.only_owner()?;
+
+        // Set hook gas limit.
+        let previous_gas_limit = self.hook_gas_limit.get().to::<u64>();
+        self.hook_gas_limit.set(U64::from(gas_limit));
+
+        // Emit event.
+        evm::log(HookGasLimitSet {
+            previous_gas_limit,
+            new_gas_limit: gas_limit,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the gas forwarded to each hook call.
+    pub fn fetch_hook_gas_limit(&self) -> Result<u64, ContractError> {
+        Ok(self.hook_gas_limit.get().to::<u64>())
+    }
+
+    /// Register a hook contract on a market, 0 to remove it.
+    ///
+    /// Callable by the market creator. The hook's `beforeSwap` returns the market fee to charge
+    /// and may revert to veto the swap, `afterSwap` sees the fill and may revert it, and
+    /// `beforeLiquidity` may veto deposits and withdrawals. Quotes do not include fees set by
+    /// the hook.
+    pub fn set_market_hook(
+        &mut self,
+        market_index: U256,
+        hook: Address,
+    ) -> Result<(), ContractError> {
+        Self::ensure_feature(cfg!(feature = "hooks"))?;
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the hook is approved.
+        if hook != Address::ZERO && !self.is_approved_hook.get(hook) {
+            return Err(ContractError::HookNotApproved(HookNotApproved { hook }));
+        }
+
+        // Set market hook.
+        let previous_hook = self.market_hooks.get(market_index);
+        self.market_hooks.insert(market_index, hook);
+
+        // Emit event.
+        evm::log(MarketHookSet {
+            market_index: U256::from(market_index),
+            previous_hook,
+            new_hook: hook,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the hook contract of a market, 0 if none.
+    pub fn fetch_market_hook(&self, market_index: U256) -> Result<Address, ContractError> {
+        Ok(self.market_hooks.get(Self::market_key(market_index)?))
+    }
+
+    /// Set the compliance registry a market's traders and recipients are checked against, 0 to
+    /// remove it.
+    ///
+    /// Callable by the market creator or the owner. Swaps revert with `NotCompliant` unless the
+    /// registry's `isAllowed` returns true for every account involved.
+    pub fn set_market_compliance_registry(
+        &mut self,
+        market_index: U256,
+        registry: Address,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator or the owner.
+        self.only_market_creator_or_owner(market_index)?;
+
+        // Set market compliance registry.
+        let previous_registry = self.compliance_registries.get(market_index);
+        self.compliance_registries.insert(market_index, registry);
+
+        // Emit event.
+        evm::log(MarketComplianceRegistrySet {
+            market_index: U256::from(market_index),
+            previous_registry,
+            new_registry: registry,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the compliance registry of a market, 0 if none.
+    pub fn fetch_market_compliance_registry(
+        &self,
+        market_index: U256,
+    ) -> Result<Address, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        Ok(self.compliance_registries.get(market_index))
+    }
+
+    /// Set whether a market only trades with externally owned accounts.
+    ///
+    /// Callable by the market creator. Traders with code, or that are not the transaction
+    /// origin, eg. contracts calling from their constructor, are rejected with
+    /// `ContractCallersNotAllowed` unless allowed by `set_market_contract_allowed`.
+    pub fn set_market_eoa_only(
+        &mut self,
+        market_index: U256,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Set market EOA-only mode.
+        let mut market = self.markets.setter(market_index);
+        let previous_enabled = market.eoa_only.get();
+        market.eoa_only.set(enabled);
+
+        // Emit event.
+        evm::log(MarketEoaOnlySet {
+            market_index: U256::from(market_index),
+            previous_enabled,
+            new_enabled: enabled,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether a market only trades with externally owned accounts.
+    pub fn fetch_market_eoa_only(&self, market_index: U256) -> Result<bool, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        Ok(self.markets.get(market_index).eoa_only.get())
+    }
+
+    /// Set the directions a market swaps in, see `DirectionMode`.
+    ///
+    /// Callable by the market creator, eg. to sell the base token without buying it back.
+    /// Swaps in a disabled direction revert with `DirectionDisabled`.
+    pub fn set_market_direction_mode(
+        &mut self,
+        market_index: U256,
+        mode: u8,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the mode is valid.
+        DirectionMode::try_from(mode)?;
+
+        // Set market direction mode.
+        let previous_mode = self.direction_mode(market_index) as u8;
+        self.direction_modes.insert(market_index, U8::from(mode));
+
+        // Emit event.
+        evm::log(MarketDirectionModeSet {
+            market_index: U256::from(market_index),
+            previous_mode,
+            new_mode: mode,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the directions a market swaps in, see `DirectionMode`.
+    pub fn fetch_market_direction_mode(&self, market_index: U256) -> Result<u8, ContractError> {
+        Ok(self.direction_mode(Self::market_key(market_index)?) as u8)
+    }
+
+    /// Allow or disallow a contract to trade on an EOA-only market.
+    ///
+    /// Callable by the market creator.
+    pub fn set_market_contract_allowed(
+        &mut self,
+        market_index: U256,
+        account: Address,
+        allowed: bool,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Set contract allowance.
+        let mut allowed_contracts = self.allowed_contracts.setter(market_index);
+        let previous_allowed = allowed_contracts.get(account);
+        allowed_contracts.insert(account, allowed);
+
+        // Emit event.
+        evm::log(MarketContractAllowedSet {
+            market_index: U256::from(market_index),
+            account,
+            previous_allowed,
+            new_allowed: allowed,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether a contract may trade on an EOA-only market.
+    pub fn fetch_market_contract_allowed(
+        &self,
+        market_index: U256,
+        account: Address,
+    ) -> Result<bool, ContractError> {
+        Ok(self
+            .allowed_contracts
+            .get(Self::market_key(market_index)?)
+            .get(account))
+    }
+
+    /// Set whether a market only pays swap output to the trader, or a custodian allowed by
+    /// `set_market_custodian_allowed`.
+    ///
+    /// Callable by the market creator. Swaps paying anyone else revert with
+    /// `RecipientNotAllowed`.
+    pub fn set_market_recipient_restricted(
+        &mut self,
+        market_index: U256,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Set recipient restriction.
+        let previous_enabled = self.recipient_restricted.get(market_index);
+        self.recipient_restricted.insert(market_index, enabled);
+
+        // Emit event.
+        evm::log(MarketRecipientRestrictedSet {
+            market_index: U256::from(market_index),
+            previous_enabled,
+            new_enabled: enabled,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether a market only pays swap output to the trader or an allowed custodian.
+    pub fn fetch_market_recipient_restricted(
+        &self,
+        market_index: U256,
+    ) -> Result<bool, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        Ok(self.recipient_restricted.get(market_index))
+    }
+
+    /// Allow or disallow a custodian to receive swap output on a recipient-restricted market.
+    ///
+    /// Callable by the market creator.
+    pub fn set_market_custodian_allowed(
+        &mut self,
+        market_index: U256,
+        custodian: Address,
+        allowed: bool,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Set custodian allowance.
+        let mut allowed_custodians = self.allowed_custodians.setter(market_index);
+        let previous_allowed = allowed_custodians.get(custodian);
+        allowed_custodians.insert(custodian, allowed);
+
+        // Emit event.
+        evm::log(MarketCustodianAllowedSet {
+            market_index: U256::from(market_index),
+            custodian,
+            previous_allowed,
+            new_allowed: allowed,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether a custodian may receive swap output on a recipient-restricted market.
+    pub fn fetch_market_custodian_allowed(
+        &self,
+        market_index: U256,
+        custodian: Address,
+    ) -> Result<bool, ContractError> {
+        Ok(self
+            .allowed_custodians
+            .get(Self::market_key(market_index)?)
+            .get(custodian))
+    }
+
+    /// Set the band later rate updates must stay within.
+    ///
+    /// Callable by the market creator or the owner. A `max_rate` of 0 leaves the band open
+    /// above, and the current rate must lie within the band.
+    pub fn set_market_rate_bounds(
+        &mut self,
+        market_index: U256,
+        min_rate: U256,
+        max_rate: U256,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller may manage the market.
+        self.only_market_creator_or_owner(market_index)?;
+
+        // Ensure the band is not empty.
+        if !max_rate.is_zero() && min_rate > max_rate {
+            return Err(ContractError::InvalidRateBounds(InvalidRateBounds {}));
+        }
+
+        // Ensure the current rate is within the band.
+        let exchange_rate = self.market_rate(market_index);
+        Self::ensure_rate_in_bounds(min_rate, max_rate, exchange_rate)?;
+
+        // Set rate bounds.
+        let mut market = self.markets.setter(market_index);
+        let (previous_min_rate, previous_max_rate) = (market.min_rate.get(), market.max_rate.get());
+        market.min_rate.set(min_rate);
+        market.max_rate.set(max_rate);
+
+        // Emit event.
+        evm::log(MarketRateBoundsSet {
+            market_index: U256::from(market_index),
+            previous_min_rate,
+            previous_max_rate,
+            new_min_rate: min_rate,
+            new_max_rate: max_rate,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the band rate updates must stay within.
+    ///
+    /// Return (min rate, max rate), a max rate of 0 if unbounded above.
+    pub fn fetch_market_rate_bounds(
+        &self,
+        market_index: U256,
+    ) -> Result<(U256, U256), ContractError> {
+        let market = self.markets.get(Self::market_key(market_index)?);
+        Ok((market.min_rate.get(), market.max_rate.get()))
+    }
+
+    /// Limit how far rate updates may move a market's rate, `max_change_bps` per update and
+    /// `max_window_change_bps` per `window` seconds, 0 for no limit.
+    ///
+    /// Callable by the market creator or the owner. Only the owner may loosen the limit, so a
+    /// creator cannot lift it right before repricing. Applies to scheduled updates, the start
+    /// of a rate decay and presale rates, measured from the current rate.
+    pub fn set_market_rate_limit(
+        &mut self,
+        market_index: U256,
+        max_change_bps: u16,
+        max_window_change_bps: u16,
+        window: u64,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller may manage the market.
+        self.only_market_creator_or_owner(market_index)?;
+
+        // Ensure only the owner loosens the limit.
+        let limit = self.rate_limits.get(market_index);
+        let previous_max_change_bps = limit.max_change_bps.get().to::<u16>();
+        let previous_max_window_change_bps = limit.max_window_change_bps.get().to::<u16>();
+        let previous_window = limit.window.get().to::<u64>();
+        let loosens = |previous: u16, new: u16| previous != 0 && (new == 0 || new > previous);
+        if loosens(previous_max_change_bps, max_change_bps)
+            || loosens(previous_max_window_change_bps, max_window_change_bps)
+            || window < previous_window
+        {
+            self.only_owner()?;
+        }
+
+        // Set rate limit, starting a new window.
+        let exchange_rate = self.market_rate(market_index);
+        let mut limit = self.rate_limits.setter(market_index);
+        limit.max_change_bps.set(U16::from(max_change_bps));
+        limit
+            .max_window_change_bps
+            .set(U16::from(max_window_change_bps));
+        limit.window.set(U64::from(window));
+        limit.window_start.set(U64::from(chain::timestamp()));
+        limit.window_start_rate.set(exchange_rate);
+
+        // Emit event.
+        evm::log(MarketRateLimitSet {
+            market_index: U256::from(market_index),
+            previous_max_change_bps,
+            previous_max_window_change_bps,
+            previous_window,
+            new_max_change_bps: max_change_bps,
+            new_max_window_change_bps: max_window_change_bps,
+            new_window: window,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the rate limit of a market.
+    ///
+    /// Return (max change bps per update, max change bps per window, window).
+    pub fn fetch_market_rate_limit(
+        &self,
+        market_index: U256,
+    ) -> Result<(u16, u16, u64), ContractError> {
+        let limit = self.rate_limits.get(Self::market_key(market_index)?);
+        Ok((
+            limit.max_change_bps.get().to::<u16>(),
+            limit.max_window_change_bps.get().to::<u16>(),
+            limit.window.get().to::<u64>(),
+        ))
+    }
+
+    /// Delegate a market's pricing to a rate operator, replacing any previous one.
+    ///
+    /// Callable by the market creator. The operator may schedule rate updates and set rate
+    /// decays, the creator keeps every other right over the market.
+    pub fn grant_rate_operator(
+        &mut self,
+        market_index: U256,
+        operator: Address,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        self.set_rate_operator(market_index, operator);
+
+        Ok(())
+    }
+
+    /// Revoke a market's rate operator.
+    ///
+    /// Callable by the market creator.
+    pub fn revoke_rate_operator(&mut self, market_index: U256) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        self.set_rate_operator(market_index, Address::ZERO);
+
+        Ok(())
+    }
+
+    /// Fetch the rate operator of a market, 0 if none.
+    pub fn fetch_rate_operator(&self, market_index: U256) -> Result<Address, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        Ok(self.rate_operators.get(market_index))
+    }
+
+    /// Nominate a new creator for a market.
+    ///
+    /// Callable by the market creator. The nominee must call `accept_market_creator` to
+    /// complete the transfer.
+    pub fn transfer_market_creator(
+        &mut self,
+        market_index: U256,
+        new_creator: Address,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensures the new creator is not a zero address.
+        if new_creator == Address::ZERO {
+            return Err(ContractError::CreatorCanNotBeZeroAddress(
+                CreatorCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Set pending creator.
+        self.pending_creators.insert(market_index, new_creator);
+
+        // Emit event.
+        evm::log(MarketCreatorTransferStarted {
+            market_index: U256::from(market_index),
+            previous_creator: msg::sender(),
+            new_creator,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a pending creator transfer of a market.
+    pub fn accept_market_creator(&mut self, market_index: U256) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the pending creator.
+        let new_creator = msg::sender();
+        if new_creator != self.pending_creators.get(market_index) {
+            return Err(ContractError::NotPendingCreator(NotPendingCreator {}));
+        }
+
+        // Set new creator.
+        let mut market = self.markets.setter(market_index);
+        let previous_creator = market.creator.get();
+        market.creator.set(new_creator);
+        self.pending_creators.insert(market_index, Address::ZERO);
+
+        // Emit event.
+        evm::log(MarketCreatorTransferred {
+            market_index: U256::from(market_index),
+            previous_creator,
+            new_creator,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the pending creator of a market, zero if none.
+    pub fn fetch_pending_market_creator(
+        &self,
+        market_index: U256,
+    ) -> Result<Address, ContractError> {
+        Ok(self.pending_creators.get(Self::market_key(market_index)?))
+    }
+
+    /// Start a presale, where only allowlisted traders may swap at `presale_rate` until
+    /// `ends_at`, replacing any configured one.
+    ///
+    /// Callable by the market creator. The public phase then starts on its own at `public_rate`,
+    /// 0 to keep the market rate. Traders are allowlisted with `set_presale_allowlist`, or prove
+    /// they are in the `merkle_root` address tree with `prove_presale_access`. An `ends_at` of
+    /// 0 removes the presale.
+    pub fn set_presale(
+        &mut self,
+        market_index: U256,
+        ends_at: u64,
+        presale_rate: U256,
+        public_rate: U256,
+        merkle_root: B256,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the market has not expired or closed.
+        let status = self.market_status(market_index);
+        if status != MarketStatus::Active && status != MarketStatus::Paused {
+            return Err(ContractError::MarketNotActive(MarketNotActive {
+                status: status as u8,
+            }));
+        }
+
+        if ends_at != 0 {
+            // Ensures rate is not 0.
+            if presale_rate.is_zero() {
+                return Err(ContractError::ExchangeRateCanNotBeZero(
+                    ExchangeRateCanNotBeZero {},
+                ));
+            }
+
+            // Ensure the presale has not ended already.
+            if ends_at <= chain::timestamp() {
+                return Err(ContractError::EffectiveTimeNotInFuture(
+                    EffectiveTimeNotInFuture {
+                        effective_at: ends_at,
+                    },
+                ));
+            }
+
+            // Ensure the rates are within the band.
+            let market = self.markets.get(market_index);
+            let (min_rate, max_rate) = (market.min_rate.get(), market.max_rate.get());
+            Self::ensure_rate_in_bounds(min_rate, max_rate, presale_rate)?;
+            if !public_rate.is_zero() {
+                Self::ensure_rate_in_bounds(min_rate, max_rate, public_rate)?;
+            }
+
+            // Ensure the rates are within the rate limit.
+            self.ensure_rate_change_allowed(market_index, presale_rate)?;
+            if !public_rate.is_zero() {
+                self.ensure_rate_change_allowed(market_index, public_rate)?;
+            }
+        }
+
+        // Store the public phase of the previous presale if it started.
+        self.apply_sale_phase(market_index);
+
+        // Set presale.
+        let previous_phase = self.sale_phase(market_index);
+        let mut presale = self.presales.setter(market_index);
+        let previous_ends_at = presale.ends_at.get().to::<u64>();
+        let previous_presale_rate = presale.presale_rate.get();
+        let previous_public_rate = presale.public_rate.get();
+        let previous_merkle_root = presale.merkle_root.get();
+        presale.ends_at.set(U64::from(ends_at));
+        presale.public_started.set(false);
+        presale.presale_rate.set(presale_rate);
+        presale.public_rate.set(public_rate);
+        presale.merkle_root.set(merkle_root);
+
+        // Emit events.
+        evm::log(PresaleSet {
+            market_index: U256::from(market_index),
+            previous_ends_at,
+            previous_presale_rate,
+            previous_public_rate,
+            previous_merkle_root,
+            new_ends_at: ends_at,
+            new_presale_rate: presale_rate,
+            new_public_rate: public_rate,
+            new_merkle_root: merkle_root,
+        });
+        let new_phase = self.sale_phase(market_index);
+        if new_phase != previous_phase {
+            evm::log(SalePhaseChanged {
+                market_index: U256::from(market_index),
+                previous_phase: previous_phase as u8,
+                new_phase: new_phase as u8,
+            });
+        }
+        self.sync_effective_rate(market_index, RateChangeReason::Manual);
+
+        Ok(())
+    }
+
+    /// Set whether accounts may swap during a market's presale.
+    ///
+    /// Callable by the market creator.
+    pub fn set_presale_allowlist(
+        &mut self,
+        market_index: U256,
+        accounts: Vec<Address>,
+        allowed: bool,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        for account in accounts {
+            self.set_presale_allowed(market_index, account, allowed);
+        }
+
+        Ok(())
+    }
+
+    /// Allowlist the caller for a market's presale with a proof that it is in the presale's
+    /// merkle tree.
+    pub fn prove_presale_access(
+        &mut self,
+        market_index: U256,
+        proof: Vec<B256>,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the proof links the caller to the root.
+        let account = msg::sender();
+        let root = self.presales.get(market_index).merkle_root.get();
+        if root.is_zero() || !merkle::verify(&proof, root, merkle::address_leaf(account)) {
+            return Err(ContractError::InvalidMerkleProof(InvalidMerkleProof {}));
+        }
+
+        self.set_presale_allowed(market_index, account, true);
+
+        Ok(())
+    }
+
+    /// Fetch whether an account may swap during a market's presale.
+    pub fn fetch_presale_allowed(
+        &self,
+        market_index: U256,
+        account: Address,
+    ) -> Result<bool, ContractError> {
+        let presale = self.presales.get(Self::market_key(market_index)?);
+        Ok(presale.allowed.get(account))
+    }
+
+    /// Fetch the sale phase of a market and its presale.
+    pub fn fetch_phase(&self, market_index: U256) -> Result<PresaleConfig, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        let presale = self.presales.get(market_index);
+        Ok((
+            self.sale_phase(market_index) as u8,
+            presale.ends_at.get().to::<u64>(),
+            presale.presale_rate.get(),
+            presale.public_rate.get(),
+            presale.merkle_root.get(),
+        ))
+    }
+
+    /// Run a market in sale mode until `ends_at`, escrowing the quote token buyers pay.
+    ///
+    /// Callable by the market creator before the sale raised anything. Buys may not take the
+    /// quote token raised over `hard_cap`, 0 if unlimited. After the deadline, or once the hard
+    /// cap is reached, `finalize_sale` releases the proceeds to the creator if `soft_cap` was
+    /// raised, and lets buyers `claim_refund` otherwise.
+    pub fn set_sale(
+        &mut self,
+        market_index: U256,
+        soft_cap: U256,
+        hard_cap: U256,
+        ends_at: u64,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the sale has not started raising.
+        let sale = self.sales.get(market_index);
+        let status = SaleStatus::from(sale.status.get().to::<u8>());
+        if !sale.raised.get().is_zero() || !matches!(status, SaleStatus::None | SaleStatus::Open) {
+            return Err(ContractError::SaleNotOpen(SaleNotOpen {
+                status: status as u8,
+            }));
+        }
+
+        // Ensure the caps and deadline are consistent.
+        if !hard_cap.is_zero() && soft_cap > hard_cap {
+            return Err(ContractError::InvalidSaleCaps(InvalidSaleCaps {}));
+        }
+        if ends_at <= chain::timestamp() {
+            return Err(ContractError::EffectiveTimeNotInFuture(
+                EffectiveTimeNotInFuture {
+                    effective_at: ends_at,
+                },
+            ));
+        }
+
+        // Set sale.
+        let mut sale = self.sales.setter(market_index);
+        let previous_soft_cap = sale.soft_cap.get();
+        let previous_hard_cap = sale.hard_cap.get();
+        let previous_ends_at = sale.ends_at.get().to::<u64>();
+        sale.status.set(U8::from(SaleStatus::Open as u8));
+        sale.soft_cap.set(soft_cap);
+        sale.hard_cap.set(hard_cap);
+        sale.ends_at.set(U64::from(ends_at));
+
+        // Emit event.
+        evm::log(SaleSet {
+            market_index: U256::from(market_index),
+            previous_soft_cap,
+            previous_hard_cap,
+            previous_ends_at,
+            new_soft_cap: soft_cap,
+            new_hard_cap: hard_cap,
+            new_ends_at: ends_at,
+        });
+
+        Ok(())
+    }
+
+    /// Settle a sale once its deadline passed or its hard cap was reached.
+    ///
+    /// Callable by anyone. Sends the proceeds to the creator if the soft cap was raised, and
+    /// opens refunds otherwise.
+    pub fn finalize_sale(&mut self, market_index: U256) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the sale is open.
+        let sale = self.sales.get(market_index);
+        let status = SaleStatus::from(sale.status.get().to::<u8>());
+        if status != SaleStatus::Open {
+            return Err(ContractError::SaleNotOpen(SaleNotOpen {
+                status: status as u8,
+            }));
+        }
+
+        // Ensure the sale ended.
+        let ends_at = sale.ends_at.get().to::<u64>();
+        let hard_cap = sale.hard_cap.get();
+        let raised = sale.raised.get();
+        if chain::timestamp() < ends_at && (hard_cap.is_zero() || raised < hard_cap) {
+            return Err(ContractError::SaleStillOpen(SaleStillOpen { ends_at }));
+        }
+
+        // Settle the sale.
+        let succeeded = raised >= sale.soft_cap.get();
+        let new_status = if succeeded {
+            SaleStatus::Succeeded
+        } else {
+            SaleStatus::Failed
+        };
+        self.sales
+            .setter(market_index)
+            .status
+            .set(U8::from(new_status as u8));
+
+        // Emit event.
+        evm::log(SaleFinalized {
+            market_index: U256::from(market_index),
+            succeeded,
+            raised,
+        });
+
+        if !succeeded {
+            return Ok(());
+        }
+
+        // Release the proceeds from the quote reserve.
+        self.sync_rebasing_reserves(market_index);
+        let mut market = self.markets.setter(market_index);
+        let creator = market.creator.get();
+        let quote_token = market.quote_token.get();
+        let quote_reserve = market.quote_reserve.get();
+        if raised > quote_reserve {
+            return Err(ContractError::InsufficientLiquidity(
+                InsufficientLiquidity {},
+            ));
+        }
+        market.quote_reserve.set(quote_reserve - raised);
+        self.sub_tvl(quote_token, raised);
+        self.commit_rebasing_reserves(market_index);
+
+        // Transfer the proceeds to the creator.
+        if !Self::push_tokens(quote_token, creator, raised)? {
+            return Err(ContractError::QuoteTokenTransferFailed(
+                QuoteTokenTransferFailed {},
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Refund the caller's contribution to a failed sale, in exchange for the base token it
+    /// bought.
+    pub fn claim_refund(&mut self, market_index: U256) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the sale failed.
+        let sale = self.sales.get(market_index);
+        if SaleStatus::from(sale.status.get().to::<u8>()) != SaleStatus::Failed {
+            return Err(ContractError::SaleNotFailed(SaleNotFailed {}));
+        }
+
+        // Ensure the caller contributed.
+        let buyer = msg::sender();
+        let quote_amount = sale.contributions.get(buyer);
+        let base_amount = sale.purchased.get(buyer);
+        if quote_amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Clear the contribution.
+        let mut sale = self.sales.setter(market_index);
+        let raised = sale.raised.get();
+        sale.raised.set(raised - quote_amount);
+        sale.contributions.insert(buyer, U256::ZERO);
+        sale.purchased.insert(buyer, U256::ZERO);
+
+        // Return the base token to the reserve and pay the quote token out of it.
+        self.sync_rebasing_reserves(market_index);
+        let mut market = self.markets.setter(market_index);
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
+        let base_reserve = market.base_reserve.get();
+        let quote_reserve = market.quote_reserve.get();
+        if quote_amount > quote_reserve {
+            return Err(ContractError::InsufficientLiquidity(
+                InsufficientLiquidity {},
+            ));
+        }
+        market.base_reserve.set(base_reserve + base_amount);
+        market.quote_reserve.set(quote_reserve - quote_amount);
+        self.add_tvl(base_token, base_amount);
+        self.sub_tvl(quote_token, quote_amount);
+        self.commit_rebasing_reserves(market_index);
+
+        // Emit event.
+        evm::log(SaleRefunded {
+            market_index: U256::from(market_index),
+            buyer,
+            quote_amount,
+            base_amount,
+        });
+
+        // Transfer base token from the buyer.
+        if !Self::pull_tokens(base_token, buyer, base_amount)? {
+            return Err(ContractError::BaseTokenTransferFailed(
+                BaseTokenTransferFailed {},
+            ));
+        }
+
+        // Transfer quote token to the buyer.
+        if !Self::push_tokens(quote_token, buyer, quote_amount)? {
+            return Err(ContractError::QuoteTokenTransferFailed(
+                QuoteTokenTransferFailed {},
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Fetch the sale of a market.
+    pub fn fetch_sale(&self, market_index: U256) -> Result<SaleInfo, ContractError> {
+        let sale = self.sales.get(Self::market_key(market_index)?);
+        Ok((
+            sale.status.get().to::<u8>(),
+            sale.soft_cap.get(),
+            sale.hard_cap.get(),
+            sale.ends_at.get().to::<u64>(),
+            sale.raised.get(),
+        ))
+    }
+
+    /// Fetch the quote token a buyer contributed to a market's sale and the base token it
+    /// bought.
+    pub fn fetch_contribution(
+        &self,
+        market_index: U256,
+        buyer: Address,
+    ) -> Result<(U256, U256), ContractError> {
+        let sale = self.sales.get(Self::market_key(market_index)?);
+        Ok((sale.contributions.get(buyer), sale.purchased.get(buyer)))
+    }
+
+    /// Set aside leftover base token of a market whose sale succeeded as an airdrop, claimable
+    /// until `claim_deadline` by the accounts of the `root` tree, replacing an unclaimed one.
+    ///
+    /// Callable by the market creator. The `(address, uint256)` tree is built off-chain,
+    /// typically splitting `amount` in proportion to `fetch_contribution`, and an `amount` of 0
+    /// removes the airdrop. Unclaimed base token is swept to the creator after the deadline.
+    pub fn set_airdrop_root(
+        &mut self,
+        market_index: U256,
+        root: B256,
+        amount: U256,
+        claim_deadline: u64,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the market's sale succeeded.
+        let status = SaleStatus::from(self.sales.get(market_index).status.get().to::<u8>());
+        if status != SaleStatus::Succeeded {
+            return Err(ContractError::SaleNotSucceeded(SaleNotSucceeded {
+                status: status as u8,
+            }));
+        }
+
+        // Ensure no airdrop was claimed yet.
+        let airdrop = self.airdrops.get(market_index);
+        if !airdrop.claimed.get().is_zero() || airdrop.swept.get() {
+            return Err(ContractError::AirdropInProgress(AirdropInProgress {}));
+        }
+
+        if !amount.is_zero() {
+            // Ensure the claim window is in the future.
+            if claim_deadline <= chain::timestamp() {
+                return Err(ContractError::EffectiveTimeNotInFuture(
+                    EffectiveTimeNotInFuture {
+                        effective_at: claim_deadline,
+                    },
+                ));
+            }
+
+            // Ensure the market holds the leftover base token.
+            let available =
+                self.reserve_out(market_index, SwapDirection::QuoteForBase) + airdrop.amount.get();
+            if amount > available {
+                return Err(ContractError::InsufficientLiquidity(
+                    InsufficientLiquidity {},
+                ));
+            }
+        }
+
+        // Set airdrop.
+        let mut airdrop = self.airdrops.setter(market_index);
+        let previous_root = airdrop.root.get();
+        let previous_amount = airdrop.amount.get();
+        let previous_claim_deadline = airdrop.claim_deadline.get().to::<u64>();
+        airdrop.root.set(root);
+        airdrop.amount.set(amount);
+        airdrop.claim_deadline.set(U64::from(claim_deadline));
+
+        // Emit event.
+        evm::log(AirdropSet {
+            market_index: U256::from(market_index),
+            previous_root,
+            previous_amount,
+            previous_claim_deadline,
+            new_root: root,
+            new_amount: amount,
+            new_claim_deadline: claim_deadline,
+        });
+
+        Ok(())
+    }
+
+    /// Claim the caller's share of a market's airdrop with a proof that `(caller, amount)` is in
+    /// its merkle tree.
+    pub fn claim_airdrop(
+        &mut self,
+        market_index: U256,
+        amount: U256,
+        proof: Vec<B256>,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the claim window is open.
+        let airdrop = self.airdrops.get(market_index);
+        let claim_deadline = airdrop.claim_deadline.get().to::<u64>();
+        if airdrop.amount.get().is_zero() || chain::timestamp() >= claim_deadline {
+            return Err(ContractError::AirdropClaimsClosed(AirdropClaimsClosed {
+                claim_deadline,
+            }));
+        }
+
+        // Ensure the caller has not claimed.
+        let account = msg::sender();
+        if airdrop.claimed_by.get(account) {
+            return Err(ContractError::AirdropAlreadyClaimed(
+                AirdropAlreadyClaimed { account },
+            ));
+        }
+
+        // Ensure the proof links the claim to the root.
+        let leaf = merkle::amount_leaf(account, amount);
+        if !merkle::verify(&proof, airdrop.root.get(), leaf) {
+            return Err(ContractError::InvalidMerkleProof(InvalidMerkleProof {}));
+        }
+
+        // Ensure the claim stays within the airdrop.
+        let claimed = airdrop.claimed.get();
+        if claimed + amount > airdrop.amount.get() {
+            return Err(ContractError::InsufficientLiquidity(
+                InsufficientLiquidity {},
+            ));
+        }
+
+        // Record the claim.
+        let mut airdrop = self.airdrops.setter(market_index);
+        airdrop.claimed.set(claimed + amount);
+        airdrop.claimed_by.insert(account, true);
+
+        // Pay the claim out of the base reserve.
+        let base_token = self.release_base_reserve(market_index, amount);
+
+        // Emit event.
+        evm::log(AirdropClaimed {
+            market_index: U256::from(market_index),
+            account,
+            amount,
+        });
+
+        // Transfer base token to the account.
+        if !Self::push_tokens(base_token, account, amount)? {
+            return Err(ContractError::BaseTokenTransferFailed(
+                BaseTokenTransferFailed {},
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Sweep the unclaimed base token of a market's airdrop to its creator once the claim
+    /// window closed.
+    ///
+    /// Callable by anyone.
+    pub fn sweep_airdrop(&mut self, market_index: U256) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the claim window closed.
+        let airdrop = self.airdrops.get(market_index);
+        let claim_deadline = airdrop.claim_deadline.get().to::<u64>();
+        if chain::timestamp() < claim_deadline {
+            return Err(ContractError::AirdropClaimsOpen(AirdropClaimsOpen {
+                claim_deadline,
+            }));
+        }
+
+        // Ensure there is something to sweep.
+        let amount = self.airdrop_escrow(market_index);
+        if amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Mark the airdrop swept.
+        self.airdrops.setter(market_index).swept.set(true);
+
+        // Pay the unclaimed base token out of the base reserve.
+        let base_token = self.release_base_reserve(market_index, amount);
+
+        // Emit event.
+        let creator = self.markets.get(market_index).creator.get();
+        evm::log(AirdropSwept {
+            market_index: U256::from(market_index),
+            creator,
+            amount,
+        });
+
+        // Transfer base token to the creator.
+        if !Self::push_tokens(base_token, creator, amount)? {
+            return Err(ContractError::BaseTokenTransferFailed(
+                BaseTokenTransferFailed {},
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Fetch the airdrop of a market.
+    pub fn fetch_airdrop(&self, market_index: U256) -> Result<AirdropConfig, ContractError> {
+        let airdrop = self.airdrops.get(Self::market_key(market_index)?);
+        Ok((
+            airdrop.root.get(),
+            airdrop.amount.get(),
+            airdrop.claimed.get(),
+            airdrop.claim_deadline.get().to::<u64>(),
+            airdrop.swept.get(),
+        ))
+    }
+
+    /// Fetch whether an account claimed its share of a market's airdrop.
+    pub fn fetch_airdrop_claimed(
+        &self,
+        market_index: U256,
+        account: Address,
+    ) -> Result<bool, ContractError> {
+        let airdrop = self.airdrops.get(Self::market_key(market_index)?);
+        Ok(airdrop.claimed_by.get(account))
+    }
+
+    /// Schedule a rate update that takes effect at `effective_at`, replacing any pending one.
+    ///
+    /// Callable by the market's rate operator, the market creator or the owner. The new rate must
+    /// lie within the rate bounds, and traders see it ahead of time through `fetch_pending_rate`.
+    pub fn schedule_rate_update(
+        &mut self,
+        market_index: U256,
+        new_rate: U256,
+        effective_at: u64,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller may price the market.
+        self.only_rate_operator(market_index)?;
+
+        // Ensure the market has not expired or closed.
+        let status = self.market_status(market_index);
+        if status != MarketStatus::Active && status != MarketStatus::Paused {
+            return Err(ContractError::MarketNotActive(MarketNotActive {
+                status: status as u8,
+            }));
+        }
+
+        // Ensures rate is not 0.
+        if new_rate.is_zero() {
+            return Err(ContractError::ExchangeRateCanNotBeZero(
+                ExchangeRateCanNotBeZero {},
+            ));
+        }
+
+        // Ensure the update is announced ahead of time.
+        if effective_at <= chain::timestamp() {
+            return Err(ContractError::EffectiveTimeNotInFuture(
+                EffectiveTimeNotInFuture { effective_at },
+            ));
+        }
+
+        // Ensure the new rate is within the band.
+        let market = self.markets.get(market_index);
+        Self::ensure_rate_in_bounds(market.min_rate.get(), market.max_rate.get(), new_rate)?;
+
+        // Ensure the new rate is within the rate limit.
+        self.ensure_rate_change_allowed(market_index, new_rate)?;
+
+        // Store the scheduled rate if it took effect.
+        self.apply_pending_rate(market_index);
+
+        // Schedule rate update, replacing the pending one.
+        let mut market = self.markets.setter(market_index);
+        let previous_rate = market.pending_rate.get();
+        let previous_effective_at = market.pending_rate_at.get().to::<u64>();
+        market.pending_rate.set(new_rate);
+        market.pending_rate_at.set(U64::from(effective_at));
+
+        // Emit event.
+        evm::log(RateUpdateScheduled {
+            market_index: U256::from(market_index),
+            previous_rate,
+            previous_effective_at,
+            new_rate,
+            new_effective_at: effective_at,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the scheduled rate update of a market.
+    ///
+    /// Return (new rate, effective at), (0, 0) if none is pending.
+    pub fn fetch_pending_rate(&self, market_index: U256) -> Result<(U256, u64), ContractError> {
+        let market = self.markets.get(Self::market_key(market_index)?);
+        let pending_rate_at = market.pending_rate_at.get().to::<u64>();
+
+        // A rate that took effect is no longer pending.
+        if pending_rate_at == 0 || chain::timestamp() >= pending_rate_at {
+            return Ok((U256::ZERO, 0));
+        }
+
+        Ok((market.pending_rate.get(), pending_rate_at))
+    }
+
+    /// Run a dutch auction, decaying the rate from `start_rate` to `floor_rate` over
+    /// `duration` seconds from `start_time`, see `DecayMode`.
+    ///
+    /// Callable by the market's rate operator, the market creator or the owner. A `start_time`
+    /// of 0 starts now, and mode 0 stops the decay at the current rate. Both rates must lie
+    /// within the rate bounds.
+    pub fn set_rate_decay(
+        &mut self,
+        market_index: U256,
+        mode: u8,
+        start_rate: U256,
+        floor_rate: U256,
+        start_time: u64,
+        duration: u64,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller may price the market.
+        self.only_rate_operator(market_index)?;
+
+        // Ensure the market has not expired or closed.
+        let status = self.market_status(market_index);
+        if status != MarketStatus::Active && status != MarketStatus::Paused {
+            return Err(ContractError::MarketNotActive(MarketNotActive {
+                status: status as u8,
+            }));
+        }
+
+        // Get the decay being replaced.
+        let previous = self.rate_decay_config(market_index);
+
+        // Stop the decay at the current rate.
+        if DecayMode::try_from(mode)? == DecayMode::None {
+            let exchange_rate = self.market_rate(market_index);
+            self.rate_decays
+                .setter(market_index)
+                .mode
+                .set(U8::from(DecayMode::None as u8));
+            self.markets
+                .setter(market_index)
+                .exchange_rate
+                .set(exchange_rate);
+
+            // Emit events.
+            let new = (mode, exchange_rate, exchange_rate, chain::timestamp(), 0);
+            evm::log(Self::rate_decay_set(market_index, previous, new));
+            self.sync_effective_rate(market_index, RateChangeReason::Manual);
+
+            return Ok(());
+        }
+
+        // Ensure the decay runs down to a non-zero floor.
+        if floor_rate.is_zero() || start_rate < floor_rate || duration == 0 {
+            return Err(ContractError::InvalidRateDecay(InvalidRateDecay {}));
+        }
+
+        // Ensure the decay stays within the band.
+        let market = self.markets.get(market_index);
+        let (min_rate, max_rate) = (market.min_rate.get(), market.max_rate.get());
+        Self::ensure_rate_in_bounds(min_rate, max_rate, start_rate)?;
+        Self::ensure_rate_in_bounds(min_rate, max_rate, floor_rate)?;
+
+        // Ensure the decay starts within the rate limit, it then moves the rate gradually.
+        self.ensure_rate_change_allowed(market_index, start_rate)?;
+
+        // Set rate decay.
+        let start_time = if start_time == 0 {
+            chain::timestamp()
+        } else {
+            start_time
+        };
+        let mut decay = self.rate_decays.setter(market_index);
+        decay.mode.set(U8::from(mode));
+        decay.start_time.set(U64::from(start_time));
+        decay.duration.set(U64::from(duration));
+        decay.start_rate.set(start_rate);
+        decay.floor_rate.set(floor_rate);
+
+        // Emit events.
+        let new = (mode, start_rate, floor_rate, start_time, duration);
+        evm::log(Self::rate_decay_set(market_index, previous, new));
+        self.sync_effective_rate(market_index, RateChangeReason::Manual);
+
+        Ok(())
+    }
+
+    /// Fetch the rate decay of a market.
+    ///
+    /// Return (mode, start rate, floor rate, start time, duration).
+    pub fn fetch_rate_decay(&self, market_index: U256) -> Result<RateDecayConfig, ContractError> {
+        Ok(self.rate_decay_config(Self::market_key(market_index)?))
+    }
+
+    /// Fetch the rate swaps in a market execute at now, including any decay or scheduled update.
+    pub fn fetch_current_rate(&self, market_index: U256) -> Result<U256, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the market exists.
+        if self.market_status(market_index) == MarketStatus::None {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        Ok(self.market_rate(market_index))
+    }
+
+    /// Fetch the token decimals cached for a market.
+    ///
+    /// Return (base decimals, quote decimals).
+    pub fn fetch_market_decimals(&self, market_index: U256) -> Result<(u8, u8), ContractError> {
+        let market = self.markets.get(Self::market_key(market_index)?);
+
+        Ok((
+            market.base_decimals.get().to::<u8>(),
+            market.quote_decimals.get().to::<u8>(),
+        ))
+    }
+
+    /// Re-query the token decimals of a market, eg. after a proxy token upgraded.
+    ///
+    /// Callable by the market creator or the owner.
+    pub fn refresh_token_metadata(&mut self, market_index: U256) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller may manage the market.
+        self.only_market_creator_or_owner(market_index)?;
+
+        // Cache the decimals the tokens report now.
+        let mut market = self.markets.setter(market_index);
+        let previous_base_decimals = market.base_decimals.get().to::<u8>();
+        let previous_quote_decimals = market.quote_decimals.get().to::<u8>();
+        let base_decimals = Self::token_decimals(market.base_token.get());
+        let quote_decimals = Self::token_decimals(market.quote_token.get());
+        market.base_decimals.set(base_decimals);
+        market.quote_decimals.set(quote_decimals);
+        self.decimals_cached_at
+            .insert(market_index, U64::from(chain::timestamp()));
+
+        // Emit event.
+        evm::log(MarketDecimalsRefreshed {
+            market_index: U256::from(market_index),
+            previous_base_decimals,
+            previous_quote_decimals,
+            new_base_decimals: base_decimals.to::<u8>(),
+            new_quote_decimals: quote_decimals.to::<u8>(),
+        });
+
+        Ok(())
+    }
+
+    /// Fetch when the token decimals of a market were last cached, 0 for markets created before
+    /// refreshes were tracked.
+    pub fn fetch_market_decimals_cached_at(
+        &self,
+        market_index: U256,
+    ) -> Result<u64, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        Ok(self.decimals_cached_at.get(market_index).to::<u64>())
+    }
+
+    /// Require swaps on a market to go through `commit_swap` and `reveal_and_execute`.
+    ///
+    /// Callable by the market creator.
+    pub fn set_market_commit_reveal(
+        &mut self,
+        market_index: U256,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "commit-reveal"))?;
+
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Set commit-reveal requirement.
+        let mut market = self.markets.setter(market_index);
+        let previous_enabled = market.commit_reveal.get();
+        market.commit_reveal.set(enabled);
+
+        // Emit event.
+        evm::log(MarketCommitRevealSet {
+            market_index: U256::from(market_index),
+            previous_enabled,
+            new_enabled: enabled,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether a market requires commit-reveal swaps.
+    pub fn fetch_market_commit_reveal(&self, market_index: U256) -> Result<bool, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        Ok(self.markets.get(market_index).commit_reveal.get())
+    }
+
+    /// Mark which sides of a market are ERC-4626 vault shares that `swap_via_underlying` may
+    /// swap through the vault's underlying asset.
+    ///
+    /// Callable by the market creator.
+    pub fn set_market_vaults(
+        &mut self,
+        market_index: U256,
+        base_vault: bool,
+        quote_vault: bool,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the marked tokens are vaults.
+        let market = self.markets.get(market_index);
+        for (is_vault, token) in [
+            (base_vault, market.base_token.get()),
+            (quote_vault, market.quote_token.get()),
+        ] {
+            if is_vault && IErc4626::new(token).asset(Call::new()).is_err() {
+                return Err(ContractError::VaultCallFailed(VaultCallFailed {
+                    vault: token,
+                }));
+            }
+        }
+
+        // Set market vaults.
+        let mut market = self.markets.setter(market_index);
+        let previous_base_vault = market.base_vault.get();
+        let previous_quote_vault = market.quote_vault.get();
+        market.base_vault.set(base_vault);
+        market.quote_vault.set(quote_vault);
+
+        // Emit event.
+        evm::log(MarketVaultsSet {
+            market_index: U256::from(market_index),
+            previous_base_vault,
+            previous_quote_vault,
+            new_base_vault: base_vault,
+            new_quote_vault: quote_vault,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch which sides of a market are ERC-4626 vault shares.
+    ///
+    /// Return (base vault, quote vault).
+    pub fn fetch_market_vaults(&self, market_index: U256) -> Result<(bool, bool), ContractError> {
+        let market = self.markets.get(Self::market_key(market_index)?);
+        Ok((market.base_vault.get(), market.quote_vault.get()))
+    }
+
+    /// Set whether a market tracks its reserves as shares of the contract's token balances.
+    ///
+    /// Rebasing markets absorb positive and negative rebases of their tokens, their reserves
+    /// are resynced from the contract's balances on every interaction. Callable by the market
+    /// creator while the market holds no reserves.
+    pub fn set_market_rebasing(
+        &mut self,
+        market_index: U256,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Ensure the market holds no reserves.
+        let mut market = self.markets.setter(market_index);
+        if !market.base_reserve.get().is_zero() || !market.quote_reserve.get().is_zero() {
+            return Err(ContractError::MarketHasReserves(MarketHasReserves {}));
+        }
+
+        // Set market rebasing mode.
+        let previous_enabled = market.rebasing.get();
+        market.rebasing.set(enabled);
+
+        // Emit event.
+        evm::log(MarketRebasingSet {
+            market_index: U256::from(market_index),
+            previous_enabled,
+            new_enabled: enabled,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether a market tracks its reserves as shares, and its shares of each token.
+    ///
+    /// Return (rebasing, base shares, quote shares).
+    pub fn fetch_market_rebasing(
+        &self,
+        market_index: U256,
+    ) -> Result<(bool, U256, U256), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+        let shares = self.rebasing_shares.get(market_index);
+        Ok((
+            self.markets.get(market_index).rebasing.get(),
+            shares.base_shares.get(),
+            shares.quote_shares.get(),
+        ))
+    }
+
+    /// Resync the reserves of a rebasing market from the contract's token balances.
+    ///
+    /// Callable by anyone, no-op for markets that track raw amounts.
+    pub fn sync_market_reserves(&mut self, market_index: U256) -> Result<(), ContractError> {
+        self.sync_rebasing_reserves(Self::market_key(market_index)?);
+        Ok(())
+    }
+
+    /// Fetch every market index listed for a token pair, oldest first.
+    /// Closed markets stay readable by id after their pair is listed again.
+    pub fn fetch_pair_history(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+    ) -> Result<Vec<U256>, ContractError> {
+        let base_token_history = self.pair_history.getter(base_token);
+        let quote_token_history = base_token_history.getter(quote_token);
+
+        let mut history = Vec::with_capacity(quote_token_history.len());
+        for i in 0..quote_token_history.len() {
+            let market_index = quote_token_history.get(i).unwrap_or_default();
+            history.push(U256::from(market_index));
+        }
+
+        Ok(history)
+    }
+
+    /// Fetch the active market of a pair offering the best effective price in a direction.
+    ///
+    /// Return the market index, 0 if no market can fill a direct swap.
+    pub fn fetch_best_market(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+        direction: u8,
+    ) -> Result<U256, ContractError> {
+        let direction = SwapDirection::try_from(direction)?;
+        let market_index = self.best_market(base_token, quote_token, direction, &[]);
+        Ok(U256::from(market_index))
+    }
+
+    /// Fetch market reserves.
+    ///
+    /// Return (base_reserve, quote_reserve).
+    pub fn fetch_market_reserves(&self, market_index: U256) -> Result<(U256, U256), ContractError> {
+        let market = self.markets.get(Self::market_key(market_index)?);
+
+        Ok((market.base_reserve.get(), market.quote_reserve.get()))
+    }
+
+    /// Fetch the reserves of a token summed across all markets.
+    pub fn fetch_tvl(&self, token: Address) -> Result<U256, ContractError> {
+        Ok(self.token_tvl.get(token))
+    }
+
+    /// Fetch up to `limit` tokens from `offset` and their total value locked.
+    ///
+    /// Tokens are ordered by when a market first listed them, a short page is the last one.
+    pub fn fetch_tvl_all(
+        &self,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<Address>, Vec<U256>), ContractError> {
+        let len = self.tvl_tokens.len() as u64;
+        let end = offset.saturating_add(limit).min(len);
+
+        let mut tokens = Vec::new();
+        let mut tvls = Vec::new();
+        for i in offset..end {
+            if let Some(token) = self.tvl_tokens.get(i as usize) {
+                tokens.push(token);
+                tvls.push(self.token_tvl.get(token));
+            }
+        }
+
+        Ok((tokens, tvls))
+    }
+
+    /// Fetch the accounting of a market's base and quote token, so auditors and monitoring can
+    /// check the contract's balances cover everything it tracks in one call.
+    ///
+    /// Escrowed reserves are the locked and airdropped base token and the raised sale quote
+    /// token. The treasury, insurance, creator fee and total value locked figures span every
+    /// market.
+    pub fn fetch_accounting(
+        &self,
+        market_index: U256,
+    ) -> Result<(TokenAccounting, TokenAccounting), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the market exists.
+        if self.market_status(market_index) == MarketStatus::None {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        let market = self.markets.get(market_index);
+        let queue = self.withdrawal_queues.get(market_index);
+        let base_escrow =
+            self.locked_liquidity.get(market_index) + self.airdrop_escrow(market_index);
+        Ok((
+            self.token_accounting(
+                market.base_token.get(),
+                market.base_reserve.get(),
+                queue.base_amount.get(),
+                base_escrow,
+            ),
+            self.token_accounting(
+                market.quote_token.get(),
+                market.quote_reserve.get(),
+                queue.quote_amount.get(),
+                self.sale_escrow(market_index),
+            ),
+        ))
+    }
+
+    /// Check that the contract holds every token of a market it owes, flagging the market on a
+    /// shortfall beyond the solvency tolerance.
+    ///
+    /// Callable by anyone. A caller whose check flags an active or paused market earns the
+    /// solvency bounty from the treasury's ETH, as far as it covers it. The flag's reason hash
+    /// is `keccak256("verifySolvency")`, and only the owner lifts it with `resolve_flag`.
+    /// Shortfalls are recorded per token, so each breach pays one bounty however many markets
+    /// list the token, until `resolve_flag` on one of them. Liabilities are the total value
+    /// locked, treasury, insurance and creator fee balances of each token, tokens whose balance
+    /// can not be read are skipped.
+    ///
+    /// Return whether the market's tokens are covered.
+    pub fn verify_solvency(&mut self, market_index: U256) -> Result<bool, ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the market exists.
+        if self.market_status(market_index) == MarketStatus::None {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        // Compare each token's liabilities with the contract's balance.
+        let market = self.markets.get(market_index);
+        let tokens = [market.base_token.get(), market.quote_token.get()];
+        let tolerance_bps = U256::from(self.solvency_tolerance_bps.get());
+        let reporter = msg::sender();
+        let mut breached = Vec::new();
+        for token in tokens {
+            let liabilities = self.token_tvl.get(token)
+                + self.treasury_balances.get(token)
+                + self.insurance_balances.get(token)
+                + self.creator_fee_totals.get(token);
+            let Ok(balance) = IErc20::new(token).balance_of(Call::new(), address()) else {
+                continue;
+            };
+            let tolerance = math::mul_div(
+                liabilities,
+                tolerance_bps,
+                U256::from(BPS),
+                math::Rounding::Down,
+            )
+            .unwrap_or(liabilities);
+            if liabilities.saturating_sub(balance) > tolerance {
+                breached.push(token);
+                evm::log(SolvencyBreach {
+                    market_index: U256::from(market_index),
+                    token,
+                    liabilities,
+                    balance,
+                    reporter,
+                });
+            }
+        }
+
+        // Flag the market and reward the caller for tokens not reported yet.
+        let status = self.market_status(market_index);
+        let solvent = breached.is_empty();
+        if !solvent
+            && cfg!(feature = "pausable")
+            && (status == MarketStatus::Active || status == MarketStatus::Paused)
+        {
+            self.market_flags
+                .insert(market_index, crypto::keccak(b"verifySolvency"));
+            self.set_market_status(market_index, MarketStatus::Flagged);
+            let auto_pauses = self.auto_pauses.get();
+            self.auto_pauses.set(auto_pauses + U64::from(1));
+
+            let reported = breached
+                .iter()
+                .all(|&token| self.breached_tokens.get(token));
+            for token in breached {
+                self.breached_tokens.insert(token, true);
+            }
+            if !reported {
+                self.pay_solvency_bounty(reporter);
+            }
+        }
+
+        Ok(solvent)
+    }
+
+    /// Set the shortfall `verify_solvency` tolerates and the bounty it pays for flagging a
+    /// market.
+    ///
+    /// Callable by the owner.
+    pub fn set_solvency_config(
+        &mut self,
+        tolerance_bps: u16,
+        bounty: U256,
+    ) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensure the tolerance is at most 100%.
+        if tolerance_bps > BPS {
+            return Err(ContractError::InvalidSolvencyTolerance(
+                InvalidSolvencyTolerance {},
+            ));
+        }
+
+        // Set solvency config.
+        let previous_tolerance_bps = self.solvency_tolerance_bps.get().to::<u16>();
+        let previous_bounty = self.solvency_bounty.get();
+        self.solvency_tolerance_bps.set(U16::from(tolerance_bps));
+        self.solvency_bounty.set(bounty);
+
+        // Emit event.
+        evm::log(SolvencyConfigSet {
+            previous_tolerance_bps,
+            previous_bounty,
+            new_tolerance_bps: tolerance_bps,
+            new_bounty: bounty,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the shortfall `verify_solvency` tolerates in basis points and its bounty in ETH.
+    pub fn fetch_solvency_config(&self) -> Result<(u16, U256), ContractError> {
+        Ok((
+            self.solvency_tolerance_bps.get().to::<u16>(),
+            self.solvency_bounty.get(),
+        ))
+    }
+
+    /// Fetch a user's stats for a market.
+    ///
+    /// Return the swap count, the base and quote token sold to the market, and the base and
+    /// quote token bought from it.
+    pub fn fetch_user_stats(
+        &self,
+        user: Address,
+        market_index: U256,
+    ) -> Result<(u64, U256, U256, U256, U256), ContractError> {
+        let user_market_stats = self.user_stats.get(user);
+        let stats = user_market_stats.get(Self::market_key(market_index)?);
+
+        Ok((
+            stats.swap_count.get().to::<u64>(),
+            stats.base_in.get(),
+            stats.quote_in.get(),
+            stats.base_out.get(),
+            stats.quote_out.get(),
+        ))
+    }
+
+    /// Set the base token each user may buy from a market, 0 to lift the cap.
+    ///
+    /// Callable by the market creator. Purchases are counted from the user stats, so users who
+    /// bought before the cap was set keep their purchases towards it.
+    pub fn set_purchase_cap(&mut self, market_index: U256, cap: U256) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        // Set purchase cap.
+        let previous_cap = self.purchase_caps.get(market_index);
+        self.purchase_caps.insert(market_index, cap);
+
+        // Emit event.
+        evm::log(PurchaseCapSet {
+            market_index: U256::from(market_index),
+            previous_cap,
+            new_cap: cap,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the base token each user may buy from a market, 0 if uncapped.
+    pub fn fetch_purchase_cap(&self, market_index: U256) -> Result<U256, ContractError> {
+        Ok(self.purchase_caps.get(Self::market_key(market_index)?))
+    }
+
+    /// Fetch the base token a user bought from a market.
+    ///
+    /// Selling base token back to the market does not lower it.
+    pub fn fetch_purchased(
+        &self,
+        market_index: U256,
+        user: Address,
+    ) -> Result<U256, ContractError> {
+        let user_market_stats = self.user_stats.get(user);
+        let stats = user_market_stats.get(Self::market_key(market_index)?);
+        Ok(stats.base_out.get())
+    }
+
+    /// Fetch the base token a user may still buy from a market, `type(uint256).max` if uncapped.
+    pub fn fetch_remaining_allocation(
+        &self,
+        market_index: U256,
+        user: Address,
+    ) -> Result<U256, ContractError> {
+        Ok(self.remaining_allocation(Self::market_key(market_index)?, user))
+    }
+
+    /// Close a market permanently and return its reserves to the creator.
+    ///
+    /// The pair can be listed again afterwards, the closed market stays readable by id. Markets
+    /// holding contributions to an open or failed sale can not close until they are refunded.
+    pub fn close_market(&mut self, market_index: U256) -> Result<(), ContractError> {
+        let market_index = Self::market_key(market_index)?;
+
+        // Ensure the caller is the market creator.
+        self.only_market_creator(market_index)?;
+
+        self.close_market_internal(market_index)
+    }
+
+    /// Create a basket market selling `base_token` at a fixed rate per accepted quote token.
+    ///
+    /// `rates[i]` is the amount of `quote_tokens[i]` per base token, as in `create_market`.
+    /// `sold_cap` bounds the base token sold across quote tokens, 0 if unlimited.
+    ///
+    /// Return the basket id.
+    pub fn create_basket(
+        &mut self,
+        base_token: Address,
+        quote_tokens: Vec<Address>,
+        rates: Vec<U256>,
+        sold_cap: U256,
+    ) -> Result<U256, ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "basket"))?;
+
+        // Ensure the caller may create markets.
+        self.only_allowed_creator()?;
+        self.ensure_not_sunset()?;
+
+        // Ensures the base token address is not a zero address.
+        if base_token == Address::ZERO {
+            return Err(ContractError::BaseTokenCanNotBeZeroAddress(
+                BaseTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Ensure every quote token has a rate, within the cap.
+        if quote_tokens.is_empty()
+            || quote_tokens.len() != rates.len()
+            || quote_tokens.len() > MAX_BASKET_QUOTE_TOKENS
+        {
+            return Err(ContractError::InvalidBasket(InvalidBasket {}));
+        }
+
+        // Create a new basket in storage.
+        let basket_id = self.basket_count.get() + U64::from(1);
+        self.basket_count.set(basket_id);
+        let creator = msg::sender();
+        let mut basket = self.baskets.setter(basket_id);
+        basket.base_token.set(base_token);
+        basket.creator.set(creator);
+        basket.sold_cap.set(sold_cap);
+
+        for (&quote_token, &rate) in quote_tokens.iter().zip(rates.iter()) {
+            // Ensures the quote token address is not a zero address.
+            if quote_token == Address::ZERO {
+                return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
+                    QuoteTokenCanNotBeZeroAddress {},
+                ));
+            }
+
+            // Ensures rate is not 0.
+            if rate.is_zero() {
+                return Err(ContractError::ExchangeRateCanNotBeZero(
+                    ExchangeRateCanNotBeZero {},
+                ));
+            }
+
+            // Ensure the quote token is new and not the base token.
+            if quote_token == base_token || !basket.rates.get(quote_token).is_zero() {
+                return Err(ContractError::InvalidBasket(InvalidBasket {}));
+            }
+
+            basket.quote_tokens.push(quote_token);
+            basket.rates.insert(quote_token, rate);
+        }
+
+        // List the tokens for total value locked.
+        self.list_tvl_token(base_token);
+        for &quote_token in &quote_tokens {
+            self.list_tvl_token(quote_token);
+        }
+
+        // Emit event.
+        evm::log(BasketCreated {
+            basket_id: U256::from(basket_id),
+            base_token,
+            creator,
+            quote_tokens,
+            rates,
+            sold_cap,
+        });
+
+        Ok(U256::from(basket_id))
+    }
+
+    /// Deposit base token into a basket market.
+    ///
+    /// Callable by the basket creator.
+    pub fn deposit_basket(
+        &mut self,
+        basket_id: U256,
+        base_amount: U256,
+    ) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "basket"))?;
+
+        // Ensure the caller is the basket creator.
+        let basket_id = Self::basket_key(basket_id)?;
+        self.only_basket_creator(basket_id)?;
+
+        // Ensures the amount is not 0.
+        if base_amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Update reserve and total value locked.
+        let mut basket = self.baskets.setter(basket_id);
+        let base_token = basket.base_token.get();
+        let base_reserve = basket.base_reserve.get();
+        basket.base_reserve.set(base_reserve + base_amount);
+        self.add_tvl(base_token, base_amount);
+
+        // Emit event.
+        evm::log(BasketLiquidityChanged {
+            basket_id: U256::from(basket_id),
+            token: base_token,
+            deposited: base_amount,
+            withdrawn: U256::ZERO,
+        });
+
+        // Transfer base token to contract.
+        if !Self::pull_tokens(base_token, msg::sender(), base_amount)? {
+            return Err(ContractError::BaseTokenTransferFailed(
+                BaseTokenTransferFailed {},
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Withdraw unsold base token or quote token proceeds from a basket market.
+    ///
+    /// Callable by the basket creator.
+    pub fn withdraw_basket(
+        &mut self,
+        basket_id: U256,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "basket"))?;
+
+        // Ensure the caller is the basket creator.
+        let basket_id = Self::basket_key(basket_id)?;
+        self.only_basket_creator(basket_id)?;
+
+        // Ensures the amount is not 0.
+        if amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensure the basket holds enough of the token.
+        let mut basket = self.baskets.setter(basket_id);
+        let reserve = if token == basket.base_token.get() {
+            basket.base_reserve.get()
+        } else {
+            basket.quote_reserves.get(token)
+        };
+        if amount > reserve {
+            return Err(ContractError::InsufficientLiquidity(
+                InsufficientLiquidity {},
+            ));
+        }
+
+        // Update reserve and total value locked.
+        if token == basket.base_token.get() {
+            basket.base_reserve.set(reserve - amount);
+        } else {
+            basket.quote_reserves.insert(token, reserve - amount);
+        }
+        self.sub_tvl(token, amount);
+
+        // Emit event.
+        evm::log(BasketLiquidityChanged {
+            basket_id: U256::from(basket_id),
+            token,
+            deposited: U256::ZERO,
+            withdrawn: amount,
+        });
+
+        // Transfer token to creator.
+        if !Self::push_tokens(token, msg::sender(), amount)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Buy base token from a basket market with one of its quote tokens.
+    ///
+    /// The protocol fee is taken from the base token bought.
+    ///
+    /// Return the amount of base token bought.
+    pub fn buy_from_basket(
+        &mut self,
+        basket_id: U256,
+        quote_token: Address,
+        quote_amount: U256,
+        min_base_amount: U256,
+    ) -> Result<U256, ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "basket"))?;
+
+        // Ensure the contract was not decommissioned.
+        self.ensure_not_sunset()?;
+
+        // Ensures the amount is not 0.
+        if quote_amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensure the basket accepts the quote token.
+        let basket_id = Self::basket_key(basket_id)?;
+        let basket = self.baskets.get(basket_id);
+        let rate = basket.rates.get(quote_token);
+        if rate.is_zero() {
+            return Err(ContractError::QuoteTokenNotAccepted(QuoteTokenNotAccepted {
+                quote_token,
+            }));
+        }
+
+        // Calculate the base token amount, net of the protocol fee moved to the treasury.
+        let protocol_fee_bps = self.protocol_fee_bps.get().to::<u16>();
+        let base_amount = math::amount_out(quote_amount, U256::from(1), rate, protocol_fee_bps)
+            .ok_or(ContractError::DivisionUnderflow(DivisionUnderflow {}))?;
+        let protocol_fee = math::protocol_fee(quote_amount, U256::from(1), rate, protocol_fee_bps)
+            .unwrap_or_default();
+
+        // Ensure the amount out is not below the minimum.
+        if base_amount < min_base_amount {
+            return Err(ContractError::InsufficientAmountOut(InsufficientAmountOut {
+                min_amount_out: min_base_amount,
+                amount_out: base_amount,
+            }));
+        }
+
+        // Ensure the sale stays within the sold cap.
+        let gross_base_amount = base_amount + protocol_fee;
+        let sold = basket.sold.get() + gross_base_amount;
+        let sold_cap = basket.sold_cap.get();
+        if !sold_cap.is_zero() && sold > sold_cap {
+            return Err(ContractError::BasketSoldCapReached(BasketSoldCapReached {
+                sold_cap,
+                sold: basket.sold.get(),
+            }));
+        }
+
+        // Ensure the basket holds enough base token.
+        let base_reserve = basket.base_reserve.get();
+        if gross_base_amount > base_reserve {
+            return Err(ContractError::InsufficientLiquidity(
+                InsufficientLiquidity {},
+            ));
+        }
+
+        // Update reserves and total value locked.
+        let base_token = basket.base_token.get();
+        let quote_reserve = basket.quote_reserves.get(quote_token);
+        let mut basket = self.baskets.setter(basket_id);
+        basket.base_reserve.set(base_reserve - gross_base_amount);
+        basket.sold.set(sold);
+        basket
+            .quote_reserves
+            .insert(quote_token, quote_reserve + quote_amount);
+        self.add_tvl(quote_token, quote_amount);
+        self.sub_tvl(base_token, gross_base_amount);
+
+        // Move the protocol fee to the creator and the treasury.
+        let creator = self.baskets.get(basket_id).creator.get();
+        self.accrue_protocol_fee(base_token, creator, protocol_fee);
+
+        // Emit event.
+        evm::log(BasketPurchased {
+            basket_id: U256::from(basket_id),
+            buyer: msg::sender(),
+            quote_token,
+            quote_amount,
+            base_amount,
+        });
+
+        // Settle the purchase with the caller, then offer the fees to the collector.
+        Self::settle_swap(
+            base_token,
+            quote_token,
+            SwapDirection::QuoteForBase,
+            quote_amount,
+            base_amount,
+        )?;
+        self.push_fees(base_token);
+
+        Ok(base_amount)
+    }
+
+    /// Fetch a basket market.
+    ///
+    /// Return (base token, creator, base reserve, sold, sold cap, quote tokens).
+    pub fn fetch_basket(&self, basket_id: U256) -> Result<BasketInfo, ContractError> {
+        let basket_id = Self::basket_key(basket_id)?;
+
+        // Ensure the basket exists.
+        if basket_id.is_zero() || basket_id > self.basket_count.get() {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        let basket = self.baskets.get(basket_id);
+        let mut quote_tokens = Vec::with_capacity(basket.quote_tokens.len());
+        for i in 0..basket.quote_tokens.len() {
+            quote_tokens.push(basket.quote_tokens.get(i).unwrap_or_default());
+        }
+
+        Ok((
+            basket.base_token.get(),
+            basket.creator.get(),
+            basket.base_reserve.get(),
+            basket.sold.get(),
+            basket.sold_cap.get(),
+            quote_tokens,
+        ))
+    }
+
+    /// Fetch the rate and proceeds of one quote token in a basket market.
+    ///
+    /// Return (rate, quote reserve), a rate of 0 if the quote token is not accepted.
+    pub fn fetch_basket_quote(
+        &self,
+        basket_id: U256,
+        quote_token: Address,
+    ) -> Result<(U256, U256), ContractError> {
+        let basket = self.baskets.get(Self::basket_key(basket_id)?);
+        Ok((
+            basket.rates.get(quote_token),
+            basket.quote_reserves.get(quote_token),
+        ))
+    }
+
+    /// Fetch market by deterministic market id.
+    ///
+    /// Return market (base_token, quote_token, exchange_rate, status).
+    pub fn fetch_market_by_market_id(
+        &self,
+        market_id: U256,
+    ) -> Result<(Address, Address, U256, u8), ContractError> {
+        // Get market index from the market id.
+        let market_index = self.deterministic_indexes.get(market_id);
+
+        // Ensure the market id is registered.
+        if market_index.is_zero() {
+            return Err(ContractError::MarketNotFound(MarketNotFound {}));
+        }
+
+        // Get market.
+        let market = self.markets.get(market_index);
+
+        Ok((
+            market.base_token.get(),
+            market.quote_token.get(),
+            self.market_rate(market_index),
+            self.market_status(market_index) as u8,
+        ))
+    }
+
+    /// Compute the deterministic market id of a token pair.
+    ///
+    /// Return `keccak256(abi.encodePacked(base_token, quote_token, salt))`,
+    /// the same on every chain and deployment sharing the salt.
+    pub fn compute_market_id(
+        base_token: Address,
+        quote_token: Address,
+        salt: B256,
+    ) -> Result<U256, ContractError> {
+        Ok(Self::derive_market_id(base_token, quote_token, salt))
+    }
+
+    /// Compute the id of a market created with `create_market_with_salt`.
+    ///
+    /// Return `keccak256(abi.encodePacked(creator, salt))`.
+    pub fn compute_salted_market_id(creator: Address, salt: B256) -> Result<U256, ContractError> {
+        Ok(Self::derive_salted_market_id(creator, salt))
+    }
+
+    /// Fetch the index of the market a creator created with a salt, 0 if none.
+    pub fn fetch_market_by_salt(
+        &self,
+        creator: Address,
+        salt: B256,
+    ) -> Result<U256, ContractError> {
+        let market_id = Self::derive_salted_market_id(creator, salt);
+        Ok(U256::from(self.salted_indexes.get(market_id)))
+    }
+
+    /// Fetch deterministic market id mode.
+    ///
+    /// Return (enabled, salt).
+    pub fn fetch_deterministic_market_ids(&self) -> Result<(bool, B256), ContractError> {
+        Ok((
+            self.deterministic_market_ids.get(),
+            self.market_id_salt.get(),
+        ))
+    }
+
+    /// Enable or disable deterministic market ids for new markets.
+    ///
+    /// Markets created while enabled keep their id after the mode or salt changes.
+    pub fn set_deterministic_market_ids(
+        &mut self,
+        enabled: bool,
+        salt: B256,
+    ) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Set mode and salt.
+        let previous_enabled = self.deterministic_market_ids.get();
+        let previous_salt = self.market_id_salt.get();
+        self.deterministic_market_ids.set(enabled);
+        self.market_id_salt.set(salt);
+
+        // Emit event.
+        evm::log(DeterministicMarketIdsSet {
+            previous_enabled,
+            previous_salt,
+            new_enabled: enabled,
+            new_salt: salt,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch who may create markets, see `CreationMode`.
+    pub fn fetch_creation_mode(&self) -> Result<u8, ContractError> {
+        Ok(self.creation_mode.get().to::<u8>())
+    }
+
+    /// Set who may create markets, see `CreationMode`.
+    ///
+    /// Callable by the owner. Existing markets are not affected.
+    pub fn set_creation_mode(&mut self, mode: u8) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensure the mode exists.
+        CreationMode::try_from(mode)?;
+
+        // Set creation mode.
+        let previous_mode = self.creation_mode.get().to::<u8>();
+        self.creation_mode.set(U8::from(mode));
+
+        // Emit event.
+        evm::log(CreationModeSet {
+            previous_mode,
+            new_mode: mode,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether an address may create markets in allowlisted mode.
+    pub fn fetch_is_allowed_creator(&self, creator: Address) -> Result<bool, ContractError> {
+        Ok(self.is_allowed_creator.get(creator))
+    }
+
+    /// Allow an address to create markets in allowlisted mode.
+    ///
+    /// Callable by the owner.
+    pub fn grant_creator(&mut self, creator: Address) -> Result<(), ContractError> {
+        self.set_allowed_creator(creator, true)
+    }
+
+    /// Revoke an address's permission to create markets in allowlisted mode.
+    ///
+    /// Callable by the owner. Markets it already created are not affected.
+    pub fn revoke_creator(&mut self, creator: Address) -> Result<(), ContractError> {
+        self.set_allowed_creator(creator, false)
+    }
+
+    /// Fetch the listing fee charged on market creation.
+    ///
+    /// Return (token, amount), token 0 for ETH.
+    pub fn fetch_listing_fee(&self) -> Result<(Address, U256), ContractError> {
+        Ok((self.listing_fee_token.get(), self.listing_fee.get()))
+    }
+
+    /// Set the listing fee charged on market creation, credited to the treasury.
+    ///
+    /// Callable by the owner. `token` 0 charges ETH attached to the call, `amount` 0 disables the fee.
+    pub fn set_listing_fee(&mut self, token: Address, amount: U256) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Set listing fee.
+        let previous_token = self.listing_fee_token.get();
+        let previous_amount = self.listing_fee.get();
+        self.listing_fee_token.set(token);
+        self.listing_fee.set(amount);
+
+        // Emit event.
+        evm::log(ListingFeeSet {
+            previous_token,
+            previous_amount,
+            new_token: token,
+            new_amount: amount,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether an address is exempt from the listing fee.
+    pub fn fetch_listing_fee_waived(&self, account: Address) -> Result<bool, ContractError> {
+        Ok(self.listing_fee_waived.get(account))
+    }
+
+    /// Exempt an address from the listing fee, or revoke the exemption.
+    ///
+    /// Callable by the owner.
+    pub fn set_listing_fee_waiver(
+        &mut self,
+        account: Address,
+        waived: bool,
+    ) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Set listing fee waiver.
+        let previous_waived = self.listing_fee_waived.get(account);
+        self.listing_fee_waived.insert(account, waived);
+
+        // Emit event.
+        evm::log(ListingFeeWaiverSet {
+            account,
+            previous_waived,
+            new_waived: waived,
+        });
+
+        Ok(())
+    }
+
+    /// Set the protocol fee charged on swaps on top of the market fee.
+    ///
+    /// Callable by the owner, capped at `MAX_PROTOCOL_FEE_BPS`. Protocol fees accrue to the treasury.
+    pub fn set_protocol_fee(&mut self, fee_bps: u16) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensure the fee is within the cap.
+        if fee_bps > MAX_PROTOCOL_FEE_BPS {
+            return Err(ContractError::FeeTooHigh(FeeTooHigh {
+                fee_bps,
+                max_fee_bps: MAX_PROTOCOL_FEE_BPS,
+            }));
+        }
+
+        // Set protocol fee.
+        let previous_fee_bps = self.protocol_fee_bps.get().to::<u16>();
+        self.protocol_fee_bps.set(U16::from(fee_bps));
+
+        // Emit event.
+        evm::log(ProtocolFeeSet {
+            previous_fee_bps,
+            new_fee_bps: fee_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch protocol fee in basis points.
+    pub fn fetch_protocol_fee(&self) -> Result<u16, ContractError> {
+        Ok(self.protocol_fee_bps.get().to::<u16>())
+    }
+
+    /// Fetch the highest protocol fee the owner can set, in basis points.
+    ///
+    /// Fixed at build time, so no owner action can raise fees above it.
+    #[selector(name = "MAX_PROTOCOL_FEE_BPS")]
+    pub fn max_protocol_fee_bps() -> Result<u16, ContractError> {
+        Ok(MAX_PROTOCOL_FEE_BPS)
+    }
+
+    /// Set what treasury buybacks do.
+    ///
+    /// Callable by the owner. Buybacks are disabled while `buyback_token` is 0. Bought back
+    /// tokens are burned if `burn` is set, else forwarded to `recipient`, or kept in the
+    /// treasury if `recipient` is 0.
+    pub fn set_treasury_config(
+        &mut self,
+        buyback_token: Address,
+        burn: bool,
+        recipient: Address,
+    ) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Set treasury config.
+        let previous_buyback_token = self.treasury_buyback_token.get();
+        let previous_burn = self.treasury_burn.get();
+        let previous_recipient = self.treasury_recipient.get();
+        self.treasury_buyback_token.set(buyback_token);
+        self.treasury_burn.set(burn);
+        self.treasury_recipient.set(recipient);
+
+        // Emit event.
+        evm::log(TreasuryConfigSet {
+            previous_buyback_token,
+            previous_burn,
+            previous_recipient,
+            new_buyback_token: buyback_token,
+            new_burn: burn,
+            new_recipient: recipient,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch treasury config.
+    ///
+    /// Return treasury config (buyback token, burn, recipient).
+    pub fn fetch_treasury_config(&self) -> Result<(Address, bool, Address), ContractError> {
+        Ok((
+            self.treasury_buyback_token.get(),
+            self.treasury_burn.get(),
+            self.treasury_recipient.get(),
+        ))
+    }
+
+    /// Fetch the fees the treasury holds in a token, token 0 for ETH.
+    pub fn fetch_treasury_balance(&self, token: Address) -> Result<U256, ContractError> {
+        Ok(self.treasury_balances.get(token))
+    }
+
+    /// Set the contract protocol fees are pushed to, 0 to keep them in the treasury.
+    ///
+    /// Callable by the owner. Each swap offers the collector the treasury balance of its fee
+    /// token through `collectFees(token, amount)`, which pulls it with `transferFrom`. Balances
+    /// it fails to pull stay in the treasury for `collect_fees`.
+    pub fn set_fee_collector(&mut self, collector: Address) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Set fee collector.
+        let previous_collector = self.fee_collector.get();
+        self.fee_collector.set(collector);
+
+        // Emit event.
+        evm::log(FeeCollectorSet {
+            previous_collector,
+            new_collector: collector,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the contract protocol fees are pushed to.
+    pub fn fetch_fee_collector(&self) -> Result<Address, ContractError> {
+        Ok(self.fee_collector.get())
+    }
+
+    /// Set the treasury balance of a token that triggers a push to the fee collector.
+    ///
+    /// Callable by the owner, 0 pushes on every swap.
+    pub fn set_fee_push_threshold(
+        &mut self,
+        token: Address,
+        threshold: U256,
+    ) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Set fee push threshold.
+        let previous_threshold = self.fee_push_thresholds.get(token);
+        self.fee_push_thresholds.insert(token, threshold);
+
+        // Emit event.
+        evm::log(FeePushThresholdSet {
+            token,
+            previous_threshold,
+            new_threshold: threshold,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the treasury balance of a token that triggers a push to the fee collector.
+    pub fn fetch_fee_push_threshold(&self, token: Address) -> Result<U256, ContractError> {
+        Ok(self.fee_push_thresholds.get(token))
+    }
+
+    /// Pull the treasury balance of a token to the fee collector.
+    ///
+    /// Callable by the fee collector, the fallback for fees a push failed to deliver.
+    ///
+    /// Return the amount collected.
+    pub fn collect_fees(&mut self, token: Address) -> Result<U256, ContractError> {
+        self.collect_fees_to(token, msg::sender())
+    }
+
+    /// Pull the treasury balance of a token to `recipient` on behalf of the fee collector.
+    ///
+    /// Callable by the fee collector.
+    ///
+    /// Return the amount collected.
+    #[selector(name = "collectFees")]
+    pub fn collect_fees_to(
+        &mut self,
+        token: Address,
+        recipient: Address,
+    ) -> Result<U256, ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller is the fee collector.
+        let collector = self.fee_collector.get();
+        if collector == Address::ZERO || msg::sender() != collector {
+            return Err(ContractError::NotFeeCollector(NotFeeCollector {}));
+        }
+
+        // Ensures the recipient is not a zero address.
+        if recipient == Address::ZERO {
+            return Err(ContractError::RecipientCanNotBeZeroAddress(
+                RecipientCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Update treasury balance.
+        let amount = self.treasury_balances.get(token);
+        self.treasury_balances.insert(token, U256::ZERO);
+
+        // Emit event.
+        evm::log(FeesCollected {
+            collector,
+            token,
+            amount,
+        });
+
+        // Transfer token, or ETH for token 0, to the recipient.
+        let transferred = if token == Address::ZERO {
+            call::transfer_eth(recipient, amount).is_ok()
+        } else {
+            Self::push_tokens(token, recipient, amount)?
+        };
+        if !transferred {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token,
+            }));
+        }
+
+        Ok(amount)
+    }
+
+    /// Withdraw protocol fees from the treasury, token 0 for ETH.
+    ///
+    /// Callable by the owner.
+    pub fn withdraw_treasury(
+        &mut self,
+        token: Address,
+        recipient: Address,
+        amount: U256,
+    ) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensures the recipient is not a zero address.
+        if recipient == Address::ZERO {
+            return Err(ContractError::RecipientCanNotBeZeroAddress(
+                RecipientCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Ensures the amount is not 0.
+        if amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensure the treasury holds the amount.
+        let balance = self.treasury_balances.get(token);
+        if amount > balance {
+            return Err(ContractError::InsufficientTreasuryBalance(
+                InsufficientTreasuryBalance {},
+            ));
+        }
+
+        // Update treasury balance.
+        self.treasury_balances.insert(token, balance - amount);
+
+        // Emit event.
+        evm::log(TreasuryAction {
+            operation: TreasuryOperation::Withdraw as u8,
+            token,
+            amount,
+            recipient,
+        });
+
+        // Transfer token, or ETH for token 0, to recipient.
+        let transferred = if token == Address::ZERO {
+            call::transfer_eth(recipient, amount).is_ok()
+        } else {
+            Self::push_tokens(token, recipient, amount)?
+        };
+        if !transferred {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Swap treasury fees through a market into the buyback token, then dispose of it.
+    ///
+    /// Callable by the owner. The market fee applies, the protocol fee does not, and markets
+    /// disabling the buying direction are refused like swaps. See `set_treasury_config` for
+    /// where the bought back tokens go.
+    ///
+    /// Return the amount of buyback token bought.
+    pub fn buyback_treasury(
+        &mut self,
+        market_index: U256,
+        amount_in: U256,
+        min_amount_out: U256,
+    ) -> Result<U256, ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensures the amount is not 0.
+        if amount_in.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensure the market accepts swaps.
+        let market_index = Self::market_key(market_index)?;
+        self.ensure_market_active(market_index)?;
+
+        // Store the scheduled rate if it took effect.
+        self.apply_pending_rate(market_index);
+
+        // Get the direction that buys the buyback token, and ensure the market allows it.
+        let buyback_token = self.treasury_buyback_token.get();
+        let market = self.markets.get(market_index);
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
+        let (direction, token_in) = if buyback_token == quote_token {
+            (SwapDirection::BaseForQuote, base_token)
+        } else if buyback_token == base_token {
+            (SwapDirection::QuoteForBase, quote_token)
+        } else {
+            return Err(ContractError::BuybackTokenNotInMarket(
+                BuybackTokenNotInMarket {},
+            ));
+        };
+        if !self.direction_mode(market_index).allows(direction) {
+            return Err(ContractError::DirectionDisabled(DirectionDisabled {
+                direction: direction as u8,
+            }));
+        }
+
+        // Ensure the treasury holds the amount in.
+        let balance = self.treasury_balances.get(token_in);
+        if amount_in > balance {
+            return Err(ContractError::InsufficientTreasuryBalance(
+                InsufficientTreasuryBalance {},
+            ));
+        }
+
+        // Calculate the amount out, net of the market fee.
+        let exchange_rate = self.market_rate(market_index);
+        let (numerator, denominator) = Self::direction_rate(direction, exchange_rate);
+        let amount_out = math::amount_out(
+            amount_in,
+            numerator,
+            denominator,
+            self.market_fee_bps(market_index, false),
+        )
+        .ok_or(ContractError::MultiplicationOverflow(MultiplicationOverflow {}))?;
+
+        // Ensure the amount out is not below the minimum.
+        if amount_out < min_amount_out {
+            return Err(ContractError::InsufficientAmountOut(InsufficientAmountOut {
+                min_amount_out,
+                amount_out,
+            }));
+        }
+
+        // Resync rebasing reserves.
+        self.sync_rebasing_reserves(market_index);
+
+        // Ensure the market holds enough buyback token.
+        let reserve_out = self.reserve_out(market_index, direction);
+        let mut market = self.markets.setter(market_index);
+        let base_reserve = market.base_reserve.get();
+        let quote_reserve = market.quote_reserve.get();
+        if amount_out > reserve_out {
+            return Err(ContractError::InsufficientLiquidity(
+                InsufficientLiquidity {},
+            ));
+        }
+
+        // Update reserves.
+        match direction {
+            SwapDirection::BaseForQuote => {
+                market.base_reserve.set(base_reserve + amount_in);
+                market.quote_reserve.set(quote_reserve - amount_out);
+            }
+            SwapDirection::QuoteForBase => {
+                market.quote_reserve.set(quote_reserve + amount_in);
+                market.base_reserve.set(base_reserve - amount_out);
+            }
+        }
+        self.ensure_swap_invariant(
+            market_index,
+            direction,
+            (base_reserve, quote_reserve),
+            amount_in,
+            amount_out,
+        )?;
+
+        // Update treasury balance and total value locked.
+        self.treasury_balances.insert(token_in, balance - amount_in);
+        self.add_tvl(token_in, amount_in);
+        self.sub_tvl(buyback_token, amount_out);
+        self.commit_rebasing_reserves(market_index);
+
+        // Emit events.
+        match direction {
+            SwapDirection::BaseForQuote => evm::log(SwappedBaseTokenForQuoteToken {
+                base_token,
+                quote_token,
+                amount_in,
+                amount_out,
+            }),
+            SwapDirection::QuoteForBase => evm::log(SwappedQuoteTokenForBaseToken {
+                base_token,
+                quote_token,
+                amount_in,
+                amount_out,
+            }),
+        }
+        evm::log(TreasuryAction {
+            operation: TreasuryOperation::Buyback as u8,
+            token: token_in,
+            amount: amount_in,
+            recipient: address(),
+        });
+
+        // Burn or forward the bought back tokens, or keep them in the treasury.
+        let (operation, recipient) = if self.treasury_burn.get() {
+            (TreasuryOperation::Burn, BURN_ADDRESS)
+        } else {
+            (TreasuryOperation::Forward, self.treasury_recipient.get())
+        };
+        if recipient == Address::ZERO {
+            self.credit_treasury(buyback_token, amount_out);
+            return Ok(amount_out);
+        }
+
+        // Emit event.
+        evm::log(TreasuryAction {
+            operation: operation as u8,
+            token: buyback_token,
+            amount: amount_out,
+            recipient,
+        });
+
+        // Transfer buyback token to recipient.
+        if !Self::push_tokens(buyback_token, recipient, amount_out)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token: buyback_token,
+            }));
+        }
+
+        Ok(amount_out)
+    }
+
+    /// Grant or revoke the guardian role.
+    ///
+    /// Callable by the owner.
+    pub fn set_guardian(&mut self, guardian: Address, enabled: bool) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Set guardian status.
+        let previous_enabled = self.is_guardian.get(guardian);
+        self.is_guardian.insert(guardian, enabled);
+
+        // Emit event.
+        evm::log(GuardianSet {
+            guardian,
+            previous_enabled,
+            new_enabled: enabled,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether an address is a guardian.
+    pub fn fetch_is_guardian(&self, account: Address) -> Result<bool, ContractError> {
+        Ok(self.is_guardian.get(account))
+    }
+
+    /// Set the share of protocol fees set aside for the insurance fund.
+    ///
+    /// Callable by the owner. The rest of the protocol fees go to the treasury.
+    pub fn set_insurance_share(&mut self, share_bps: u16) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensure the share is at most the whole fee.
+        if share_bps > BPS {
+            return Err(ContractError::FeeTooHigh(FeeTooHigh {
+                fee_bps: share_bps,
+                max_fee_bps: BPS,
+            }));
+        }
+
+        // Set insurance share.
+        let previous_share_bps = self.insurance_share_bps.get().to::<u16>();
+        self.insurance_share_bps.set(U16::from(share_bps));
+
+        // Emit event.
+        evm::log(InsuranceShareSet {
+            previous_share_bps,
+            new_share_bps: share_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the share of protocol fees set aside for the insurance fund, in basis points.
+    pub fn fetch_insurance_share(&self) -> Result<u16, ContractError> {
+        Ok(self.insurance_share_bps.get().to::<u16>())
+    }
+
+    /// Fetch the insurance fund balance of a token.
+    pub fn fetch_insurance_balance(&self, token: Address) -> Result<U256, ContractError> {
+        Ok(self.insurance_balances.get(token))
+    }
+
+    /// Set the share of protocol fees accrued to the creator of the market or basket that
+    /// charged them, eg. 5_000 for half.
+    ///
+    /// Callable by the owner. The insurance share applies to the rest, and the treasury takes
+    /// what remains. Creators claim their fees with `claim_creator_fees`.
+    pub fn set_creator_fee_share(&mut self, share_bps: u16) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensure the share is at most the whole fee.
+        if share_bps > BPS {
+            return Err(ContractError::FeeTooHigh(FeeTooHigh {
+                fee_bps: share_bps,
+                max_fee_bps: BPS,
+            }));
+        }
+
+        // Set creator fee share.
+        let previous_share_bps = self.creator_fee_share_bps.get().to::<u16>();
+        self.creator_fee_share_bps.set(U16::from(share_bps));
+
+        // Emit event.
+        evm::log(CreatorFeeShareSet {
+            previous_share_bps,
+            new_share_bps: share_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the share of protocol fees accrued to creators, in basis points.
+    pub fn fetch_creator_fee_share(&self) -> Result<u16, ContractError> {
+        Ok(self.creator_fee_share_bps.get().to::<u16>())
+    }
+
+    /// Fetch the protocol fees a creator may claim in a token.
+    pub fn fetch_creator_fees(
+        &self,
+        creator: Address,
+        token: Address,
+    ) -> Result<U256, ContractError> {
+        Ok(self.creator_fee_balances.get(creator).get(token))
+    }
+
+    /// Claim the protocol fees the caller accrued as a creator in a token.
+    ///
+    /// Return the amount claimed.
+    pub fn claim_creator_fees(&mut self, token: Address) -> Result<U256, ContractError> {
+        self.claim_creator_fees_to(token, msg::sender())
+    }
+
+    /// Claim the protocol fees the caller accrued as a creator in a token to `recipient`.
+    ///
+    /// Return the amount claimed.
+    #[selector(name = "claimCreatorFees")]
+    pub fn claim_creator_fees_to(
+        &mut self,
+        token: Address,
+        recipient: Address,
+    ) -> Result<U256, ContractError> {
+        // Ensures the recipient is not a zero address.
+        if recipient == Address::ZERO {
+            return Err(ContractError::RecipientCanNotBeZeroAddress(
+                RecipientCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Ensure the caller accrued fees.
+        let creator = msg::sender();
+        let amount = self.creator_fee_balances.get(creator).get(token);
+        if amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Update creator fee balances.
+        self.creator_fee_balances
+            .setter(creator)
+            .insert(token, U256::ZERO);
+        let total = self.creator_fee_totals.get(token);
+        self.creator_fee_totals.insert(token, total - amount);
+
+        // Emit event.
+        evm::log(CreatorFeesClaimed {
+            creator,
+            token,
+            recipient,
+            amount,
+        });
+
+        // Transfer token to the recipient.
+        if !Self::push_tokens(token, recipient, amount)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token,
+            }));
+        }
+
+        Ok(amount)
+    }
+
+    /// Top up a market reserve from the insurance fund.
+    ///
+    /// Callable by a guardian, for markets whose reserves were damaged by a token exploit.
+    pub fn cover_shortfall(
+        &mut self,
+        market_index: U256,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "treasury"))?;
+
+        // Ensure the caller is a guardian.
+        self.only_guardian()?;
+
+        // Ensures the amount is not 0.
+        if amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensure the market has not expired or closed.
+        let market_index = Self::market_key(market_index)?;
+        let status = self.market_status(market_index);
+        if status != MarketStatus::Active
+            && status != MarketStatus::Paused
+            && status != MarketStatus::Flagged
+        {
+            return Err(ContractError::MarketNotActive(MarketNotActive {
+                status: status as u8,
+            }));
+        }
+
+        // Ensure the insurance fund holds the amount.
+        let balance = self.insurance_balances.get(token);
+        if amount > balance {
+            return Err(ContractError::InsufficientInsuranceBalance(
+                InsufficientInsuranceBalance {},
+            ));
+        }
+
+        // Resync rebasing reserves.
+        self.sync_rebasing_reserves(market_index);
+
+        // Update the reserve holding the token.
+        let mut market = self.markets.setter(market_index);
+        if token == market.base_token.get() {
+            let base_reserve = market.base_reserve.get();
+            market.base_reserve.set(base_reserve + amount);
+        } else if token == market.quote_token.get() {
+            let quote_reserve = market.quote_reserve.get();
+            market.quote_reserve.set(quote_reserve + amount);
+        } else {
+            return Err(ContractError::TokenNotInMarket(TokenNotInMarket { token }));
+        }
+
+        // Update insurance balance and total value locked.
+        self.insurance_balances.insert(token, balance - amount);
+        self.add_tvl(token, amount);
+        self.commit_rebasing_reserves(market_index);
+
+        // Emit event.
+        evm::log(ShortfallCovered {
+            market_index: U256::from(market_index),
+            token,
+            amount,
+            guardian: msg::sender(),
+        });
+
+        Ok(())
+    }
+
+    /// Nominate a new owner.
+    ///
+    /// The nominee must call `accept_ownership` to complete the transfer.
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensures the new owner is not a zero address.
+        if new_owner == Address::ZERO {
+            return Err(ContractError::OwnerCanNotBeZeroAddress(
+                OwnerCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Set pending owner.
+        self.pending_owner.set(new_owner);
+
+        // Emit event.
+        evm::log(OwnershipTransferStarted {
+            previous_owner: self.owner.get(),
+            new_owner,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a pending ownership transfer.
+    pub fn accept_ownership(&mut self) -> Result<(), ContractError> {
+        // Ensure the caller is the pending owner.
+        let new_owner = msg::sender();
+        if new_owner != self.pending_owner.get() {
+            return Err(ContractError::NotPendingOwner(NotPendingOwner {}));
+        }
+
+        // Set new owner.
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+        self.pending_owner.set(Address::ZERO);
+
+        // Emit event.
+        evm::log(OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+
+        Ok(())
+    }
+
+    /// Decommission the contract for good, disabling market creation and swaps.
+    ///
+    /// Callable by the owner. Withdrawals, refunds, claims, order cancellations and fee
+    /// collection stay open, so no funds are stranded. Can not be undone.
+    pub fn sunset(&mut self) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensure the contract was not decommissioned already.
+        self.ensure_not_sunset()?;
+
+        // Set sunset.
+        self.sunset.set(true);
+
+        // Emit event.
+        evm::log(Sunset {
+            owner: self.owner.get(),
+            sunset_at: chain::timestamp(),
+        });
+
+        Ok(())
+    }
+
+    /// Fetch whether the contract was decommissioned.
+    pub fn fetch_sunset(&self) -> Result<bool, ContractError> {
+        Ok(self.sunset.get())
+    }
+
+    /// Register an admin for owner action confirmations.
+    pub fn add_admin(&mut self, admin: Address) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "multisig"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensures the admin is not a zero address.
+        if admin == Address::ZERO {
+            return Err(ContractError::AdminCanNotBeZeroAddress(
+                AdminCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Ensure the admin is not registered.
+        if self.is_admin.get(admin) {
+            return Err(ContractError::AdminExists(AdminExists {}));
+        }
+
+        // Register admin.
+        self.is_admin.insert(admin, true);
+        self.admins.push(admin);
+
+        // Emit event.
+        evm::log(AdminAdded { admin });
+
+        Ok(())
+    }
+
+    /// Remove a registered admin.
+    ///
+    /// The remaining admins must still be able to reach the threshold.
+    pub fn remove_admin(&mut self, admin: Address) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "multisig"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensure the admin is registered.
+        if !self.is_admin.get(admin) {
+            return Err(ContractError::AdminNotFound(AdminNotFound {}));
+        }
+
+        // Ensure the threshold stays reachable.
+        let remaining_admins = self.admins.len() - 1;
+        if self.admin_threshold.get() > U64::from(remaining_admins) {
+            return Err(ContractError::InvalidAdminThreshold(
+                InvalidAdminThreshold {},
+            ));
+        }
+
+        // Swap the admin with the last admin and remove it.
+        let last_admin = self.admins.get(remaining_admins).unwrap_or_default();
+        for i in 0..remaining_admins {
+            if self.admins.get(i) == Some(admin) {
+                if let Some(mut slot) = self.admins.setter(i) {
+                    slot.set(last_admin);
+                }
+                break;
+            }
+        }
+        self.admins.pop();
+        self.is_admin.insert(admin, false);
+
+        // Emit event.
+        evm::log(AdminRemoved { admin });
+
+        Ok(())
+    }
+
+    /// Set the number of admin confirmations owner actions require.
+    ///
+    /// A threshold of 0 disables the scheme and lets the owner act directly.
+    pub fn set_admin_threshold(&mut self, threshold: u64) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "multisig"))?;
+
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Ensure enough admins are registered to reach the threshold.
+        if threshold > self.admins.len() as u64 {
+            return Err(ContractError::InvalidAdminThreshold(
+                InvalidAdminThreshold {},
+            ));
+        }
+
+        // Set threshold.
+        let previous_threshold = self.admin_threshold.get().to::<u64>();
+        self.admin_threshold.set(U64::from(threshold));
+
+        // Emit event.
+        evm::log(AdminThresholdChanged {
+            previous_threshold,
+            new_threshold: threshold,
+        });
+
+        Ok(())
+    }
+
+    /// Propose an owner action for admin confirmation.
+    ///
+    /// `data` is the calldata of the owner action, eg. `transferOwnership(address)`.
+    /// Proposing counts as the proposer's confirmation.
+    ///
+    /// Return admin action id.
+    pub fn propose_admin_action(&mut self, data: Bytes) -> Result<U256, ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "multisig"))?;
+
+        // Ensure the caller is an admin.
+        self.only_admin()?;
+
+        // Ensure the data holds at least a function selector.
+        if data.len() < 4 {
+            return Err(ContractError::InvalidAdminActionData(
+                InvalidAdminActionData {},
+            ));
+        }
+
+        // Get the new admin action id.
+        let action_id = self.admin_action_count.get() + U256::from(1);
+        self.admin_action_count.set(action_id);
+
+        // Create a new admin action in storage.
+        let proposer = msg::sender();
+        let mut action = self.admin_actions.setter(action_id);
+        action.proposer.set(proposer);
+        action.data.set_bytes(&data.0);
+        action.confirmed.insert(proposer, true);
+
+        // Emit events.
+        evm::log(AdminActionProposed {
+            action_id,
+            proposer,
+            data: data.0.into(),
+        });
+        evm::log(AdminActionConfirmed {
+            action_id,
+            admin: proposer,
+        });
+
+        Ok(action_id)
+    }
+
+    /// Confirm a proposed owner action.
+    pub fn confirm(&mut self, action_id: U256) -> Result<(), ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "multisig"))?;
+
+        // Ensure the caller is an admin.
+        self.only_admin()?;
+
+        // Ensure the action is pending.
+        self.ensure_pending_admin_action(action_id)?;
+
+        // Ensure the caller has not confirmed the action.
+        let admin = msg::sender();
+        let mut action = self.admin_actions.setter(action_id);
+        if action.confirmed.get(admin) {
+            return Err(ContractError::AdminActionAlreadyConfirmed(
+                AdminActionAlreadyConfirmed {},
+            ));
+        }
+
+        // Confirm action.
+        action.confirmed.insert(admin, true);
+
+        // Emit event.
+        evm::log(AdminActionConfirmed { action_id, admin });
+
+        Ok(())
+    }
+
+    /// Execute an owner action confirmed by at least the threshold of current admins.
+    ///
+    /// Return the data returned by the owner action.
+    pub fn execute(&mut self, action_id: U256) -> Result<Bytes, Vec<u8>> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "multisig"))?;
+
+        // Ensure the caller is an admin.
+        self.only_admin()?;
+
+        // Ensure the action is pending.
+        self.ensure_pending_admin_action(action_id)?;
+
+        // Ensure the scheme is enabled and enough admins confirmed the action.
+        let threshold = self.admin_threshold.get().to::<u64>();
+        if threshold == 0 || self.count_admin_confirmations(action_id) < threshold {
+            return Err(ContractError::InsufficientAdminConfirmations(
+                InsufficientAdminConfirmations {},
+            )
+            .into());
+        }
+
+        // Mark action as executed.
+        let mut action = self.admin_actions.setter(action_id);
+        action.executed.set(true);
+        let data = action.data.get_bytes();
+
+        // Route the owner action with owner rights.
+        let selector = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+        self.executing_admin_action.set(true);
+        let result = <Self as Router<Self>>::route(self, selector, &data[4..]);
+        self.executing_admin_action.set(false);
+
+        // Return the owner action result.
+        match result {
+            Some(Ok(output)) => {
+                evm::log(AdminActionExecuted {
+                    action_id,
+                    executor: msg::sender(),
+                });
+                Ok(Bytes(output))
+            }
+            Some(Err(revert_data)) => Err(revert_data),
+            None => Err(ContractError::InvalidAdminActionData(InvalidAdminActionData {}).into()),
+        }
+    }
+
+    /// Set the L1 contract that may send owner actions through `execute_l1_message`, 0 to
+    /// disable the pathway.
+    ///
+    /// Callable by the owner.
+    pub fn set_l1_governor(&mut self, governor: Address) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Set L1 governor.
+        let previous_governor = self.l1_governor.get();
+        self.l1_governor.set(governor);
+
+        // Emit event.
+        evm::log(L1GovernorSet {
+            previous_governor,
+            new_governor: governor,
+        });
+
+        Ok(())
+    }
+
+    /// Fetch the L1 governor, 0 if none.
+    pub fn fetch_l1_governor(&self) -> Result<Address, ContractError> {
+        Ok(self.l1_governor.get())
+    }
+
+    /// Execute an owner action sent by the L1 governor through a retryable ticket.
+    ///
+    /// `data` is the calldata of one of the `L1_GOVERNOR_SELECTORS` owner actions. Return the
+    /// data returned by the owner action.
+    pub fn execute_l1_message(&mut self, data: Bytes) -> Result<Bytes, Vec<u8>> {
+        // Ensure the caller is the aliased L1 governor.
+        let governor = self.l1_governor.get();
+        if governor == Address::ZERO || msg::sender() != chain::apply_l1_alias(governor) {
+            return Err(ContractError::NotL1Governor(NotL1Governor {}).into());
+        }
+
+        // Ensure the message is an owner action the governor may send.
+        if data.len() < 4 {
+            return Err(ContractError::InvalidAdminActionData(InvalidAdminActionData {}).into());
+        }
+        let selector = [data[0], data[1], data[2], data[3]];
+        if !L1_GOVERNOR_SELECTORS.contains(&selector) {
+            return Err(ContractError::L1MessageNotAllowed(L1MessageNotAllowed {
+                selector: FixedBytes(selector),
+            })
+            .into());
+        }
+
+        // Route the owner action with owner rights.
+        self.executing_admin_action.set(true);
+        let result = <Self as Router<Self>>::route(self, u32::from_be_bytes(selector), &data[4..]);
+        self.executing_admin_action.set(false);
+
+        // Return the owner action result.
+        match result {
+            Some(Ok(output)) => {
+                evm::log(L1MessageExecuted {
+                    governor,
+                    selector: FixedBytes(selector),
+                });
+                Ok(Bytes(output))
+            }
+            Some(Err(revert_data)) => Err(revert_data),
+            None => Err(ContractError::InvalidAdminActionData(InvalidAdminActionData {}).into()),
+        }
+    }
+
+    /// Fetch owner.
+    pub fn fetch_owner(&self) -> Result<Address, ContractError> {
+        Ok(self.owner.get())
+    }
+
+    /// Fetch pending owner.
+    pub fn fetch_pending_owner(&self) -> Result<Address, ContractError> {
+        Ok(self.pending_owner.get())
+    }
+
+    /// Fetch registered admins.
+    pub fn fetch_admins(&self) -> Result<Vec<Address>, ContractError> {
+        let mut admins = Vec::with_capacity(self.admins.len());
+        for i in 0..self.admins.len() {
+            admins.push(self.admins.get(i).unwrap_or_default());
+        }
+
+        Ok(admins)
+    }
+
+    /// Fetch admin threshold.
+    pub fn fetch_admin_threshold(&self) -> Result<u64, ContractError> {
+        Ok(self.admin_threshold.get().to::<u64>())
+    }
+
+    /// Fetch admin action by id.
+    ///
+    /// Return admin action (proposer, data, executed, confirmations).
+    pub fn fetch_admin_action(
+        &self,
+        action_id: U256,
+    ) -> Result<(Address, Bytes, bool, u64), ContractError> {
+        // Ensure the action exists.
+        if action_id.is_zero() || action_id > self.admin_action_count.get() {
+            return Err(ContractError::AdminActionNotFound(AdminActionNotFound {}));
+        }
+
+        // Get admin action.
+        let action = self.admin_actions.get(action_id);
+
+        Ok((
+            action.proposer.get(),
+            Bytes(action.data.get_bytes()),
+            action.executed.get(),
+            self.count_admin_confirmations(action_id),
+        ))
+    }
+
+    /// Fetch the function selectors of every public method, see `selectors`.
+    pub fn fetch_selectors() -> Result<Vec<FixedBytes<4>>, ContractError> {
+        Ok(selectors::ALL.iter().copied().map(FixedBytes).collect())
+    }
+}
+
+/// Internal methods of `Contract`.
+impl Contract {
+    /// Narrow an external market index to its storage key.
+    fn market_key(market_index: U256) -> Result<U64, ContractError> {
+        narrow_market_index(market_index).ok_or(ContractError::OutOfBoundIndex(OutOfBoundIndex {}))
+    }
+
+    /// Narrow an external basket id to its storage key.
+    fn basket_key(basket_id: U256) -> Result<U64, ContractError> {
+        narrow_market_index(basket_id).ok_or(ContractError::OutOfBoundIndex(OutOfBoundIndex {}))
+    }
+
+    /// Initialize version 1 of the contract with its owner.
+    fn initialize_internal(&mut self, owner: Address) -> Result<(), ContractError> {
+        // Ensure the contract has not been initialized.
+        self.reinitializer(1)?;
+
+        // Initialize market index.
+        self.market_index.set(U64::from(1));
+
+        // Set owner.
+        self.owner.set(owner);
+
+        // Store build metadata.
+        self.name.set_str(CONTRACT_NAME);
+        self.initialized_chain_id.set(U64::from(block::chainid()));
+        self.initialized_block.set(U64::from(chain::block_number()));
+        self.initialized_at.set(U64::from(chain::timestamp()));
+
+        // Cache the EIP-712 domain separator.
+        self.cached_chain_id.set(U64::from(block::chainid()));
+        self.cached_domain_separator
+            .set(eip712::domain_separator(block::chainid(), address()));
+
+        // Emit event
+        evm::log(OwnershipTransferred {
+            previous_owner: Address::ZERO,
+            new_owner: owner,
+        });
+
+        Ok(())
+    }
+
+    /// Guard a (re)initializer so it runs exactly once.
+    ///
+    /// Each upgrade that needs migration logic calls this with the next version,
+    /// which also records the version string of the running code.
+    fn reinitializer(&mut self, version: u64) -> Result<(), ContractError> {
+        // Ensure the version has not been initialized.
+        if self.current_initialized_version() >= version {
+            return Err(ContractError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+
+        // Set initialized version.
+        self.initialized.set(true);
+        self.initialized_version.set(U64::from(version));
+        self.version.set_str(CONTRACT_VERSION);
+
+        // Emit event.
+        evm::log(Initialized { version });
+
+        Ok(())
+    }
+
+    /// Get the latest initialized version.
+    fn current_initialized_version(&self) -> u64 {
+        let version = self.initialized_version.get().to::<u64>();
+
+        // Deployments initialized before versioning only set the legacy flag.
+        if version == 0 && self.initialized.get() {
+            return 1;
+        }
+
+        version
+    }
+
+    /// Ensure the caller may perform owner actions.
+    ///
+    /// An owner on L1 may call from its L1 alias. Once an admin threshold is set, owner actions
+    /// only run through `execute` or `execute_l1_message`.
+    fn only_owner(&self) -> Result<(), ContractError> {
+        // Routed owner actions were authorized before they were routed.
+        if self.executing_admin_action.get() {
+            return Ok(());
+        }
+
+        // An L1 owner calls through retryable tickets, from its aliased address.
+        let owner = self.owner.get();
+        let authorized = self.admin_threshold.get().is_zero()
+            && (msg::sender() == owner || chain::undo_l1_alias(msg::sender()) == owner);
+
+        if !authorized {
+            return Err(ContractError::NotOwner(NotOwner {}));
+        }
+
+        Ok(())
+    }
+
+    /// Ensure an optional subsystem is compiled in.
+    ///
+    /// Disabled subsystems keep their entry points, so the ABI is the same for every build,
+    /// but their bodies compile out.
+    fn ensure_feature(enabled: bool) -> Result<(), ContractError> {
+        if !enabled {
+            return Err(ContractError::FeatureDisabled(FeatureDisabled {}));
+        }
+
+        Ok(())
+    }
+
+    /// Get the effective status of a market.
+    ///
+    /// Active markets past their expiry report `MarketStatus::Expired`.
+    fn market_status(&self, market_index: U64) -> MarketStatus {
+        let market = self.markets.get(market_index);
+        let status = MarketStatus::from(market.status.get().to::<u8>());
+        let expires_at = market.expires_at.get().to::<u64>();
+
+        if status == MarketStatus::Active && expires_at != 0 && chain::timestamp() >= expires_at {
+            return MarketStatus::Expired;
+        }
+
+        status
+    }
+
+    /// Get the effective rate of a market.
+    ///
+    /// A rate decay sets the rate while configured. Otherwise a scheduled rate applies from its
+    /// timestamp on, before it is stored.
+    fn market_rate(&self, market_index: U64) -> U256 {
+        // Presales trade at the presale rate.
+        let phase = self.sale_phase(market_index);
+        let presale = self.presales.get(market_index);
+        if phase == SalePhase::Presale {
+            return presale.presale_rate.get();
+        }
+
+        // Get the rate decay.
+        let decay = self.rate_decays.get(market_index);
+        let mode = DecayMode::try_from(decay.mode.get().to::<u8>()).unwrap_or(DecayMode::None);
+        if mode != DecayMode::None {
+            let start_rate = decay.start_rate.get();
+            let floor_rate = decay.floor_rate.get();
+            let elapsed = chain::timestamp().saturating_sub(decay.start_time.get().to::<u64>());
+            let duration = decay.duration.get().to::<u64>();
+            return match mode {
+                DecayMode::Linear => math::linear_decay(start_rate, floor_rate, elapsed, duration),
+                _ => math::exponential_decay(start_rate, floor_rate, elapsed, duration),
+            };
+        }
+
+        // The public phase starts at the public rate.
+        let public_rate = presale.public_rate.get();
+        if phase == SalePhase::Public && !presale.public_started.get() && !public_rate.is_zero() {
+            return public_rate;
+        }
+
+        let market = self.markets.get(market_index);
+        let pending_rate_at = market.pending_rate_at.get().to::<u64>();
+
+        if pending_rate_at != 0 && chain::timestamp() >= pending_rate_at {
+            return market.pending_rate.get();
+        }
+
+        market.exchange_rate.get()
+    }
+
+    /// Store the scheduled rate of a market if it took effect.
+    fn apply_pending_rate(&mut self, market_index: U64) {
+        let mut market = self.markets.setter(market_index);
+        let pending_rate_at = market.pending_rate_at.get().to::<u64>();
+        if pending_rate_at == 0 || chain::timestamp() < pending_rate_at {
+            return;
+        }
+
+        // Store the rate and clear the schedule.
+        let previous_rate = market.exchange_rate.get();
+        let new_rate = market.pending_rate.get();
+        market.exchange_rate.set(new_rate);
+        market.pending_rate.set(U256::ZERO);
+        market.pending_rate_at.set(U64::ZERO);
+
+        // Emit events.
+        evm::log(MarketRateUpdated {
+            market_index: U256::from(market_index),
+            previous_rate,
+            new_rate,
+        });
+        self.sync_effective_rate(market_index, RateChangeReason::Schedule);
+    }
+
+    /// Get the directions a market swaps in.
+    fn direction_mode(&self, market_index: U64) -> DirectionMode {
+        let mode = self.direction_modes.get(market_index).to::<u8>();
+        DirectionMode::try_from(mode).unwrap_or(DirectionMode::Both)
+    }
+
+    /// Get the sale phase of a market.
+    fn sale_phase(&self, market_index: U64) -> SalePhase {
+        let ends_at = self.presales.get(market_index).ends_at.get().to::<u64>();
+        if ends_at == 0 {
+            SalePhase::None
+        } else if chain::timestamp() < ends_at {
+            SalePhase::Presale
+        } else {
+            SalePhase::Public
+        }
+    }
+
+    /// Store the public rate of a market whose presale ended.
+    fn apply_sale_phase(&mut self, market_index: U64) {
+        if self.sale_phase(market_index) != SalePhase::Public
+            || self.presales.get(market_index).public_started.get()
+        {
+            return;
+        }
+
+        // Mark the public phase started.
+        let mut presale = self.presales.setter(market_index);
+        presale.public_started.set(true);
+        let public_rate = presale.public_rate.get();
+
+        // Store the public rate.
+        if !public_rate.is_zero() {
+            let mut market = self.markets.setter(market_index);
+            let previous_rate = market.exchange_rate.get();
+            market.exchange_rate.set(public_rate);
+            evm::log(MarketRateUpdated {
+                market_index: U256::from(market_index),
+                previous_rate,
+                new_rate: public_rate,
+            });
+        }
+
+        // Emit events.
+        evm::log(SalePhaseChanged {
+            market_index: U256::from(market_index),
+            previous_phase: SalePhase::Presale as u8,
+            new_phase: SalePhase::Public as u8,
+        });
+        self.sync_effective_rate(market_index, RateChangeReason::Phase);
+    }
+
+    /// Publish the effective rate of a market if it changed since it was last published.
+    fn sync_effective_rate(&mut self, market_index: U64, reason: RateChangeReason) {
+        let rate = self.market_rate(market_index);
+        if rate == self.effective_rates.get(market_index) {
+            return;
+        }
+        self.effective_rates.insert(market_index, rate);
+
+        // Emit event.
+        evm::log(EffectiveRateChanged {
+            market_index: U256::from(market_index),
+            rate,
+            reason: reason as u8,
+        });
+    }
+
+    /// Record a buy from a market's sale, if it has one.
+    fn record_sale_buy(
+        &mut self,
+        market_index: U64,
+        buyer: Address,
+        amount_in: U256,
+        amount_out: U256,
+    ) -> Result<(), ContractError> {
+        let sale = self.sales.get(market_index);
+        let status = SaleStatus::from(sale.status.get().to::<u8>());
+        if status == SaleStatus::None {
+            return Ok(());
+        }
+
+        // Ensure the sale is open.
+        if status != SaleStatus::Open || chain::timestamp() >= sale.ends_at.get().to::<u64>() {
+            return Err(ContractError::SaleNotOpen(SaleNotOpen {
+                status: status as u8,
+            }));
+        }
+
+        // Ensure the buy stays within the hard cap.
+        let raised = sale.raised.get();
+        let hard_cap = sale.hard_cap.get();
+        if !hard_cap.is_zero() && raised + amount_in > hard_cap {
+            return Err(ContractError::HardCapExceeded(HardCapExceeded {
+                remaining: hard_cap.saturating_sub(raised),
+            }));
+        }
+
+        // Record the contribution.
+        let mut sale = self.sales.setter(market_index);
+        let contribution = sale.contributions.get(buyer);
+        let purchased = sale.purchased.get(buyer);
+        sale.raised.set(raised + amount_in);
+        sale.contributions.insert(buyer, contribution + amount_in);
+        sale.purchased.insert(buyer, purchased + amount_out);
+
+        Ok(())
+    }
+
+    /// Get the quote token a market escrows for its open or failed sale.
+    fn sale_escrow(&self, market_index: U64) -> U256 {
+        let sale = self.sales.get(market_index);
+        match SaleStatus::from(sale.status.get().to::<u8>()) {
+            SaleStatus::Open | SaleStatus::Failed => sale.raised.get(),
+            SaleStatus::None | SaleStatus::Succeeded => U256::ZERO,
+        }
+    }
+
+    /// Get the base token a market sets aside for its unclaimed airdrop.
+    fn airdrop_escrow(&self, market_index: U64) -> U256 {
+        let airdrop = self.airdrops.get(market_index);
+        if airdrop.swept.get() {
+            return U256::ZERO;
+        }
+        airdrop.amount.get().saturating_sub(airdrop.claimed.get())
+    }
+
+    /// Take base token leaving a market outside a swap or withdrawal out of its reserve.
+    fn release_base_reserve(&mut self, market_index: U64, amount: U256) -> Address {
+        self.sync_rebasing_reserves(market_index);
+        let mut market = self.markets.setter(market_index);
+        let base_token = market.base_token.get();
+        let base_reserve = market.base_reserve.get();
+        market.base_reserve.set(base_reserve.saturating_sub(amount));
+        self.sub_tvl(base_token, amount);
+        self.commit_rebasing_reserves(market_index);
+        base_token
+    }
+
+    /// Set whether an account may swap during a market's presale.
+    fn set_presale_allowed(&mut self, market_index: U64, account: Address, allowed: bool) {
+        let mut presale = self.presales.setter(market_index);
+        let previous_allowed = presale.allowed.get(account);
+        presale.allowed.insert(account, allowed);
+
+        // Emit event.
+        evm::log(PresaleAllowlistSet {
+            market_index: U256::from(market_index),
+            account,
+            previous_allowed,
+            new_allowed: allowed,
+        });
+    }
+
+    /// Get the rate decay of a market as (mode, start rate, floor rate, start time, duration).
+    fn rate_decay_config(&self, market_index: U64) -> RateDecayConfig {
+        let decay = self.rate_decays.get(market_index);
+        (
+            decay.mode.get().to::<u8>(),
+            decay.start_rate.get(),
+            decay.floor_rate.get(),
+            decay.start_time.get().to::<u64>(),
+            decay.duration.get().to::<u64>(),
+        )
+    }
+
+    /// Build the event of a rate decay replacing the previous one.
+    fn rate_decay_set(
+        market_index: U64,
+        previous: RateDecayConfig,
+        new: RateDecayConfig,
+    ) -> RateDecaySet {
+        RateDecaySet {
+            market_index: U256::from(market_index),
+            previous_mode: previous.0,
+            previous_start_rate: previous.1,
+            previous_floor_rate: previous.2,
+            previous_start_time: previous.3,
+            previous_duration: previous.4,
+            new_mode: new.0,
+            new_start_rate: new.1,
+            new_floor_rate: new.2,
+            new_start_time: new.3,
+            new_duration: new.4,
+        }
+    }
+
+    /// Get the market fee of a market for takers, or for makers filling an order.
+    fn market_fee_bps(&self, market_index: U64, maker: bool) -> u16 {
+        let maker_fee = self.maker_fees.get(market_index);
+        if maker && maker_fee.enabled.get() {
+            return maker_fee.fee_bps.get().to::<u16>();
+        }
+        let tier_id = self.market_fee_tiers.get(market_index);
+        if !tier_id.is_zero() {
+            return self.fee_tiers.get(tier_id).fee_bps.get().to::<u16>();
+        }
+        self.markets.get(market_index).fee_bps.get().to::<u16>()
+    }
+
+    /// Get the fee swaps in a market pay, the market fee plus the protocol fee.
+    fn swap_fee_bps(&self, market_index: U64) -> u16 {
+        self.market_fee_bps(market_index, false) + self.protocol_fee_bps.get().to::<u16>()
+    }
+
+    /// Ensure the max swap age has not passed since a swap was submitted.
+    fn ensure_swap_fresh(&self, submitted_at: u64) -> Result<(), ContractError> {
+        let max_age = self.max_swap_age.get().to::<u64>();
+        let expired_at = submitted_at.saturating_add(max_age);
+        if max_age != 0 && chain::timestamp() > expired_at {
+            return Err(ContractError::SwapTooOld(SwapTooOld { expired_at }));
+        }
+
+        Ok(())
+    }
+
+    /// Ensure a fee tier was set by the owner.
+    fn ensure_fee_tier(&self, tier_id: u64) -> Result<(), ContractError> {
+        if !self.fee_tiers.get(U64::from(tier_id)).enabled.get() {
+            return Err(ContractError::FeeTierNotFound(FeeTierNotFound { tier_id }));
+        }
+
+        Ok(())
+    }
+
+    /// Set the fee tier a market charges.
+    fn set_market_fee_tier_internal(&mut self, market_index: U64, tier_id: u64) {
+        let previous_tier_id = self.market_fee_tiers.get(market_index).to::<u64>();
+        self.market_fee_tiers
+            .insert(market_index, U64::from(tier_id));
+
+        // Emit event.
+        evm::log(MarketFeeTierSet {
+            market_index: U256::from(market_index),
+            previous_tier_id,
+            new_tier_id: tier_id,
+        });
+    }
+
+    /// Ensure an account passes a market's compliance registry, if it has one.
+    ///
+    /// A registry that cannot be read rejects every account.
+    fn ensure_compliant(&self, market_index: U64, account: Address) -> Result<(), ContractError> {
+        let registry = self.compliance_registries.get(market_index);
+        if registry == Address::ZERO {
+            return Ok(());
+        }
+
+        let allowed = IComplianceRegistry::new(registry)
+            .is_allowed(Call::new(), account)
+            .unwrap_or(false);
+        if !allowed {
+            return Err(ContractError::NotCompliant(NotCompliant { account }));
+        }
+
+        Ok(())
+    }
+
+    /// Ensure a trader may trade on a market, EOA-only markets reject traders with code or that
+    /// are not the transaction origin unless the contract is allowed.
+    fn ensure_caller_allowed(
+        &self,
+        market_index: U64,
+        trader: Address,
+    ) -> Result<(), ContractError> {
+        if !self.markets.get(market_index).eoa_only.get()
+            || self.allowed_contracts.get(market_index).get(trader)
+        {
+            return Ok(());
+        }
+
+        if trader.has_code() || trader != tx::origin() {
+            return Err(ContractError::ContractCallersNotAllowed(
+                ContractCallersNotAllowed { account: trader },
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Ensure a swap may pay a recipient, recipient-restricted markets only pay the trader or
+    /// an allowed custodian.
+    fn ensure_recipient_allowed(
+        &self,
+        market_index: U64,
+        trader: Address,
+        recipient: Address,
+    ) -> Result<(), ContractError> {
+        if recipient == trader
+            || !self.recipient_restricted.get(market_index)
+            || self.allowed_custodians.get(market_index).get(recipient)
+        {
+            return Ok(());
+        }
+
+        Err(ContractError::RecipientNotAllowed(RecipientNotAllowed {
+            recipient,
+        }))
+    }
+
+    /// Get the approved hook of a market, if any.
+    fn market_hook(&self, market_index: U64) -> Option<Address> {
+        if !cfg!(feature = "hooks") {
+            return None;
+        }
+
+        let hook = self.market_hooks.get(market_index);
+        (hook != Address::ZERO && self.is_approved_hook.get(hook)).then_some(hook)
+    }
+
+    /// Get the call context for hook calls, capped by `hook_gas_limit`.
+    fn hook_call(&self) -> Call<(), false> {
+        match self.hook_gas_limit.get().to::<u64>() {
+            0 => Call::new(),
+            gas_limit => Call::new().gas(gas_limit),
+        }
+    }
+
+    /// Call the market hook before a swap.
+    ///
+    /// Return the market fee to charge, `market_fee_bps` if it has no hook.
+    fn call_before_swap(
+        &mut self,
+        trader: Address,
+        market_index: U64,
+        direction: SwapDirection,
+        amount_in: U256,
+        market_fee_bps: u16,
+    ) -> Result<u16, ContractError> {
+        let Some(hook) = self.market_hook(market_index) else {
+            return Ok(market_fee_bps);
+        };
+
+        // Ensure the hook accepts the swap.
+        let fee_bps = IMarketHook::new(hook)
+            .before_swap(
+                self.hook_call(),
+                U256::from(market_index),
+                trader,
+                direction as u8,
+                amount_in,
+                market_fee_bps,
+            )
+            .map_err(|_| ContractError::HookRejected(HookRejected { hook }))?;
+
+        // Ensure the fee is within the cap.
+        if fee_bps > MAX_MARKET_FEE_BPS {
+            return Err(ContractError::FeeTooHigh(FeeTooHigh {
+                fee_bps,
+                max_fee_bps: MAX_MARKET_FEE_BPS,
+            }));
+        }
+
+        Ok(fee_bps)
+    }
+
+    /// Call the market hook after a swap.
+    ///
+    /// A revert is raised as `AfterSwapHookRejected` rather than `HookRejected`, as the fill
+    /// was already recorded and routes can not skip the market.
+    fn call_after_swap(
+        &mut self,
+        trader: Address,
+        market_index: U64,
+        direction: SwapDirection,
+        amount_in: U256,
+        amount_out: U256,
+    ) -> Result<(), ContractError> {
+        let Some(hook) = self.market_hook(market_index) else {
+            return Ok(());
+        };
+
+        IMarketHook::new(hook)
+            .after_swap(
+                self.hook_call(),
+                U256::from(market_index),
+                trader,
+                direction as u8,
+                amount_in,
+                amount_out,
+            )
+            .map_err(|_| ContractError::AfterSwapHookRejected(AfterSwapHookRejected { hook }))
+    }
+
+    /// Call the market hook before a deposit or withdrawal by the caller.
+    fn call_before_liquidity(
+        &mut self,
+        market_index: U64,
+        is_deposit: bool,
+        base_amount: U256,
+        quote_amount: U256,
+    ) -> Result<(), ContractError> {
+        let Some(hook) = self.market_hook(market_index) else {
+            return Ok(());
+        };
+
+        IMarketHook::new(hook)
+            .before_liquidity(
+                self.hook_call(),
+                U256::from(market_index),
+                msg::sender(),
+                is_deposit,
+                base_amount,
+                quote_amount,
+            )
+            .map_err(|_| ContractError::HookRejected(HookRejected { hook }))
+    }
+
+    /// Credit protocol fees to the creator, the insurance fund and the treasury.
+    fn accrue_protocol_fee(&mut self, token: Address, creator: Address, mut amount: U256) {
+        if amount.is_zero() {
+            return;
+        }
+
+        // Accrue the creator share, rounded down, the insurance fund and treasury split the rest.
+        if creator != Address::ZERO {
+            let share_bps = U256::from(self.creator_fee_share_bps.get().to::<u16>());
+            let creator_amount =
+                math::mul_div(amount, share_bps, U256::from(BPS), math::Rounding::Down)
+                    .unwrap_or_default();
+            if !creator_amount.is_zero() {
+                self.credit_creator_fees(creator, token, creator_amount);
+            }
+            amount -= creator_amount;
+        }
+
+        // Set aside the insurance share, rounded down, the treasury takes the rest.
+        let share_bps = U256::from(self.insurance_share_bps.get().to::<u16>());
+        let insurance_amount =
+            math::mul_div(amount, share_bps, U256::from(BPS), math::Rounding::Down)
+                .unwrap_or_default();
+        if !insurance_amount.is_zero() {
+            let insurance_balance = self.insurance_balances.get(token);
+            self.insurance_balances
+                .insert(token, insurance_balance + insurance_amount);
+        }
+
+        self.credit_treasury(token, amount - insurance_amount);
+    }
+
+    /// Credit protocol fees a creator may claim.
+    fn credit_creator_fees(&mut self, creator: Address, token: Address, amount: U256) {
+        let mut balances = self.creator_fee_balances.setter(creator);
+        let balance = balances.get(token);
+        balances.insert(token, balance + amount);
+        let total = self.creator_fee_totals.get(token);
+        self.creator_fee_totals.insert(token, total + amount);
+    }
+
+    /// Offer the treasury balance of a token to the fee collector once it reaches the threshold.
+    ///
+    /// A failed push leaves the balance in the treasury for `collect_fees`. The only storage
+    /// written after token calls: what the treasury keeps depends on what the collector pulled.
+    /// Callers run it last, after the swap's own writes and events, and the reentrancy guard
+    /// keeps the collector from acting on the balance before it is updated.
+    fn push_fees(&mut self, token: Address) {
+        let collector = self.fee_collector.get();
+        let amount = self.treasury_balances.get(token);
+        if collector == Address::ZERO
+            || amount.is_zero()
+            || amount < self.fee_push_thresholds.get(token)
+        {
+            return;
+        }
+
+        // Let the collector pull the balance.
+        let erc20 = IErc20::new(token);
+        let offered = erc20
+            .approve(Call::new(), collector, amount)
+            .unwrap_or(false);
+        let collected = offered
+            && IFeeCollector::new(collector)
+                .collect_fees(Call::new(), token, amount)
+                .is_ok();
+
+        // Get the amount pulled, then revoke the unused allowance.
+        let remaining = erc20
+            .allowance(Call::new(), address(), collector)
+            .unwrap_or(amount);
+        if offered && !remaining.is_zero() {
+            let _ = erc20.approve(Call::new(), collector, U256::ZERO);
+        }
+        let pulled = if collected {
+            amount.saturating_sub(remaining)
+        } else {
+            U256::ZERO
+        };
+
+        if pulled.is_zero() {
+            let failed_external_calls = self.failed_external_calls.get();
+            self.failed_external_calls
+                .set(failed_external_calls + U64::from(1));
+            evm::log(FeePushFailed {
+                collector,
+                token,
+                amount,
+            });
+            return;
+        }
+
+        // Update treasury balance.
+        self.treasury_balances.insert(token, amount - pulled);
+
+        // Emit event.
+        evm::log(FeesCollected {
+            collector,
+            token,
+            amount: pulled,
+        });
+    }
+
+    /// Credit tokens to the treasury, token 0 for ETH.
+    fn credit_treasury(&mut self, token: Address, amount: U256) {
+        let balance = self.treasury_balances.get(token);
+        self.treasury_balances.insert(token, balance + amount);
+    }
+
+    /// Charge the caller the listing fee for a new market, crediting it to the treasury.
+    ///
+    /// ETH fees must be attached exactly. Return the token fee the caller then collects with
+    /// `pull_listing_fee`, 0 for ETH fees.
+    fn charge_listing_fee(&mut self, market_index: U64) -> Result<U256, ContractError> {
+        let payer = msg::sender();
+        let token = self.listing_fee_token.get();
+        let fee = if self.listing_fee_waived.get(payer) {
+            U256::ZERO
+        } else {
+            self.listing_fee.get()
+        };
+
+        // Ensure the attached ETH matches the fee, none for token fees.
+        let expected = if token == Address::ZERO { fee } else { U256::ZERO };
+        if msg::value() != expected {
+            return Err(ContractError::IncorrectListingFee(IncorrectListingFee {
+                expected,
+                paid: msg::value(),
+            }));
+        }
+
+        if fee.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        // Credit fee to treasury.
+        self.credit_treasury(token, fee);
+
+        // Emit event.
+        evm::log(ListingFeePaid {
+            market_index: U256::from(market_index),
+            payer,
+            token,
+            amount: fee,
+        });
+
+        if token == Address::ZERO {
+            return Ok(U256::ZERO);
+        }
+
+        Ok(fee)
+    }
+
+    /// Transfer a listing fee charged in tokens from the caller to the contract.
+    fn pull_listing_fee(&self, fee: U256) -> Result<(), ContractError> {
+        let token = self.listing_fee_token.get();
+        if !Self::pull_tokens(token, msg::sender(), fee)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Ensure a market accepts swaps.
+    fn ensure_market_active(&self, market_index: U64) -> Result<(), ContractError> {
+        self.ensure_not_sunset()?;
+
+        let status = self.market_status(market_index);
+        if status != MarketStatus::Active {
+            return Err(ContractError::MarketNotActive(MarketNotActive {
+                status: status as u8,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Get the accounting of one token of a market, see `fetch_accounting`.
+    fn token_accounting(
+        &self,
+        token: Address,
+        reserve: U256,
+        pending_withdrawal: U256,
+        escrowed: U256,
+    ) -> TokenAccounting {
+        let balance = IErc20::new(token)
+            .balance_of(Call::new(), address())
+            .unwrap_or_default();
+        (
+            token,
+            reserve,
+            pending_withdrawal,
+            escrowed,
+            self.treasury_balances.get(token),
+            self.insurance_balances.get(token),
+            self.creator_fee_totals.get(token),
+            self.token_tvl.get(token),
+            balance,
+        )
+    }
+
+    /// Pay the solvency bounty to a caller out of the treasury's ETH, as far as it covers it.
+    ///
+    /// A failed transfer keeps the bounty in the treasury rather than reverting the pause.
+    fn pay_solvency_bounty(&mut self, reporter: Address) {
+        let balance = self.treasury_balances.get(Address::ZERO);
+        let bounty = self.solvency_bounty.get().min(balance);
+        if bounty.is_zero() {
+            return;
+        }
+
+        let remaining = balance - bounty;
+        self.treasury_balances.insert(Address::ZERO, remaining);
+        if call::transfer_eth(reporter, bounty).is_err() {
+            self.treasury_balances.insert(Address::ZERO, balance);
+        }
+    }
+
+    /// Ensure the contract was not decommissioned.
+    fn ensure_not_sunset(&self) -> Result<(), ContractError> {
+        if self.sunset.get() {
+            return Err(ContractError::ContractSunset(ContractSunset {}));
+        }
+
+        Ok(())
+    }
+
+    /// Set the stored status of a market.
+    fn set_market_status(&mut self, market_index: U64, status: MarketStatus) {
+        let previous_status = self.market_status(market_index);
+        let mut market = self.markets.setter(market_index);
+        market.status.set(U8::from(status as u8));
+
+        // Emit event.
+        evm::log(MarketStatusChanged {
+            market_index: U256::from(market_index),
+            previous_status: previous_status as u8,
+            new_status: status as u8,
+        });
+    }
+
+    /// Ensure the caller may create markets under the creation mode.
+    fn only_allowed_creator(&self) -> Result<(), ContractError> {
+        let allowed = match CreationMode::try_from(self.creation_mode.get().to::<u8>())? {
+            CreationMode::Open => true,
+            CreationMode::Allowlisted => {
+                self.is_allowed_creator.get(msg::sender()) || self.only_owner().is_ok()
+            }
+            CreationMode::OwnerOnly => self.only_owner().is_ok(),
+        };
+
+        if !allowed {
+            return Err(ContractError::CreatorNotAllowed(CreatorNotAllowed {}));
+        }
+
+        Ok(())
+    }
+
+    /// Set whether an address may create markets in allowlisted mode.
+    fn set_allowed_creator(&mut self, creator: Address, allowed: bool) -> Result<(), ContractError> {
+        // Ensure the caller may perform owner actions.
+        self.only_owner()?;
+
+        // Set creator permission.
+        let previous_allowed = self.is_allowed_creator.get(creator);
+        self.is_allowed_creator.insert(creator, allowed);
+
+        // Emit event.
+        evm::log(CreatorAllowed {
+            creator,
+            previous_allowed,
+            new_allowed: allowed,
+        });
+
+        Ok(())
+    }
+
+    /// Ensure the caller created the basket market.
+    fn only_basket_creator(&self, basket_id: U64) -> Result<(), ContractError> {
+        if basket_id.is_zero()
+            || basket_id > self.basket_count.get()
+            || self.baskets.get(basket_id).creator.get() != msg::sender()
+        {
+            return Err(ContractError::NotBasketCreator(NotBasketCreator {}));
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the caller created the market.
+    fn only_market_creator(&self, market_index: U64) -> Result<(), ContractError> {
+        if self.market_status(market_index) == MarketStatus::None
+            || self.markets.get(market_index).creator.get() != msg::sender()
+        {
+            return Err(ContractError::NotMarketCreator(NotMarketCreator {}));
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the caller created the market or may perform owner actions.
+    fn only_market_creator_or_owner(&self, market_index: U64) -> Result<(), ContractError> {
+        if self.market_status(market_index) == MarketStatus::None {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        if self.markets.get(market_index).creator.get() == msg::sender() {
+            return Ok(());
+        }
+
+        self.only_owner()
+    }
+
+    /// Ensure the caller is the market's rate operator, created the market, or may perform
+    /// owner actions.
+    fn only_rate_operator(&self, market_index: U64) -> Result<(), ContractError> {
+        if self.market_status(market_index) == MarketStatus::None {
+            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+        }
+
+        let caller = msg::sender();
+        let operator = self.rate_operators.get(market_index);
+        if (operator != Address::ZERO && operator == caller)
+            || self.markets.get(market_index).creator.get() == caller
+        {
+            return Ok(());
+        }
+
+        self.only_owner()
+            .map_err(|_| ContractError::NotRateOperator(NotRateOperator {}))
+    }
+
+    /// Set the rate operator of a market.
+    fn set_rate_operator(&mut self, market_index: U64, operator: Address) {
+        let previous_operator = self.rate_operators.get(market_index);
+        self.rate_operators.insert(market_index, operator);
+
+        // Emit event.
+        evm::log(RateOperatorSet {
+            market_index: U256::from(market_index),
+            previous_operator,
+            new_operator: operator,
+        });
+    }
+
+    /// Fill a swap against a market's reserves without moving tokens, optionally locked to an
+    /// expected rate.
+    ///
+    /// The caller settles the swap with `settle_swap`, `trader` is credited in the user stats.
+    /// Return the amount out, net of the market fee kept in the output reserve and the protocol
+    /// fee moved to the treasury.
+    fn fill_swap(
+        &mut self,
+        trader: Address,
+        market_index: U64,
+        direction: SwapDirection,
+        amount_in: U256,
+        expected_rate: Option<U256>,
+    ) -> Result<U256, ContractError> {
+        self.fill_swap_at(
+            trader,
+            market_index,
+            direction,
+            amount_in,
+            expected_rate,
+            SwapTerms::Taker,
+        )
+    }
+
+    /// Fill a swap like `fill_swap`, at the maker fee of an order or the terms of a voucher.
+    ///
+    /// A voucher rate of 0 keeps the market rate, and the voucher fee only applies below the
+    /// market fee.
+    fn fill_swap_at(
+        &mut self,
+        trader: Address,
+        market_index: U64,
+        direction: SwapDirection,
+        amount_in: U256,
+        expected_rate: Option<U256>,
+        terms: SwapTerms,
+    ) -> Result<U256, ContractError> {
+        // Check and price the swap.
+        let (amount_out, protocol_fee) = self.price_swap(
+            trader,
+            market_index,
+            direction,
+            amount_in,
+            expected_rate,
+            terms,
+        )?;
+
+        // Ensure the market holds enough of the output token.
+        let reserve_out = self.reserve_out(market_index, direction);
+        let mut market = self.markets.setter(market_index);
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
+        let base_reserve = market.base_reserve.get();
+        let quote_reserve = market.quote_reserve.get();
+        let (token_in, token_out) = match direction {
+            SwapDirection::BaseForQuote => (base_token, quote_token),
+            SwapDirection::QuoteForBase => (quote_token, base_token),
+        };
+        if amount_out + protocol_fee > reserve_out {
+            return Err(ContractError::InsufficientLiquidity(
+                InsufficientLiquidity {},
+            ));
+        }
+
+        // Update reserves.
+        match direction {
+            SwapDirection::BaseForQuote => {
+                market.base_reserve.set(base_reserve + amount_in);
+                market
+                    .quote_reserve
+                    .set(quote_reserve - amount_out - protocol_fee);
+            }
+            SwapDirection::QuoteForBase => {
+                market.quote_reserve.set(quote_reserve + amount_in);
+                market
+                    .base_reserve
+                    .set(base_reserve - amount_out - protocol_fee);
+            }
+        }
+        self.ensure_swap_invariant(
+            market_index,
+            direction,
+            (base_reserve, quote_reserve),
+            amount_in,
+            amount_out + protocol_fee,
+        )?;
+
+        // Update total value locked.
+        self.add_tvl(token_in, amount_in);
+        self.sub_tvl(token_out, amount_out + protocol_fee);
+        self.commit_rebasing_reserves(market_index);
+
+        // Move the protocol fee to the creator and the treasury.
+        let creator = self.markets.get(market_index).creator.get();
+        self.accrue_protocol_fee(token_out, creator, protocol_fee);
+
+        // Record the swap in the user's stats.
+        self.record_user_swap(trader, market_index, direction, amount_in, amount_out);
+        self.last_swap_at.set(U64::from(chain::timestamp()));
+
+        // Emit event.
+        match direction {
+            SwapDirection::BaseForQuote => evm::log(SwappedBaseTokenForQuoteToken {
+                base_token,
+                quote_token,
+                amount_in,
+                amount_out,
+            }),
+            SwapDirection::QuoteForBase => evm::log(SwappedQuoteTokenForBaseToken {
+                base_token,
+                quote_token,
+                amount_in,
+                amount_out,
+            }),
+        }
+
+        // Let the hook see the fill, then offer the fees to the collector.
+        self.call_after_swap(trader, market_index, direction, amount_in, amount_out)?;
+        self.push_fees(token_out);
+
+        Ok(amount_out)
+    }
+
+    /// Check a swap like `fill_swap_at` and price it, recording it in the market's sale.
+    ///
+    /// Return (amount out, protocol fee).
+    fn price_swap(
+        &mut self,
+        trader: Address,
+        market_index: U64,
+        direction: SwapDirection,
+        amount_in: U256,
+        expected_rate: Option<U256>,
+        terms: SwapTerms,
+    ) -> Result<(U256, U256), ContractError> {
+        // Ensure the market accepts swaps in the direction.
+        self.ensure_market_active(market_index)?;
+        if !self.direction_mode(market_index).allows(direction) {
+            return Err(ContractError::DirectionDisabled(DirectionDisabled {
+                direction: direction as u8,
+            }));
+        }
+
+        // Ensure the trader passes the market's compliance registry and caller policy, which
+        // escrowed orders passed when placed as keepers fill them.
+        self.ensure_compliant(market_index, trader)?;
+        if terms != SwapTerms::Maker {
+            self.ensure_caller_allowed(market_index, trader)?;
+        }
+
+        // Ensure presale swaps come from allowlisted traders.
+        if self.sale_phase(market_index) == SalePhase::Presale
+            && !self.presales.get(market_index).allowed.get(trader)
+        {
+            return Err(ContractError::PresaleAccessDenied(PresaleAccessDenied {
+                account: trader,
+            }));
+        }
+
+        // Store the scheduled rate and the public rate if they took effect.
+        self.apply_pending_rate(market_index);
+        self.apply_sale_phase(market_index);
+
+        // Publish the effective rate if a decay moved it.
+        self.sync_effective_rate(market_index, RateChangeReason::Decay);
+
+        // Resync rebasing reserves.
+        self.sync_rebasing_reserves(market_index);
+
+        // Get the fees, the protocol fee is charged on top of the market fee set by the hook.
+        let protocol_fee_bps = self.protocol_fee_bps.get().to::<u16>();
+        let market_fee_bps = self.market_fee_bps(market_index, terms == SwapTerms::Maker);
+        let mut market_fee_bps =
+            self.call_before_swap(trader, market_index, direction, amount_in, market_fee_bps)?;
+
+        // Get market rate.
+        let mut exchange_rate = self.market_rate(market_index);
+
+        // Apply the voucher discount.
+        if let SwapTerms::Voucher(voucher_rate, voucher_fee_bps) = terms {
+            market_fee_bps = market_fee_bps.min(voucher_fee_bps);
+            if !voucher_rate.is_zero() {
+                exchange_rate = voucher_rate;
+            }
+        }
+        let fee_bps = market_fee_bps + protocol_fee_bps;
+
+        // Ensure the rate has not changed since the caller was quoted.
+        Self::ensure_expected_rate(expected_rate, exchange_rate)?;
+
+        // Calculate the amount out.
+        let (numerator, denominator) = Self::direction_rate(direction, exchange_rate);
+        let amount_out = math::amount_out(amount_in, numerator, denominator, fee_bps).ok_or(
+            match direction {
+                SwapDirection::BaseForQuote => {
+                    ContractError::MultiplicationOverflow(MultiplicationOverflow {})
+                }
+                SwapDirection::QuoteForBase => {
+                    ContractError::DivisionUnderflow(DivisionUnderflow {})
+                }
+            },
+        )?;
+        let protocol_fee =
+            math::protocol_fee(amount_in, numerator, denominator, protocol_fee_bps)
+                .unwrap_or_default();
+
+        // Ensure the purchase stays within the trader's allocation.
+        if direction == SwapDirection::QuoteForBase {
+            let remaining = self.remaining_allocation(market_index, trader);
+            if amount_out > remaining {
+                return Err(ContractError::PurchaseCapExceeded(PurchaseCapExceeded {
+                    remaining,
+                }));
+            }
+        }
+
+        // Ensure the buy stays within the market's open sale.
+        if direction == SwapDirection::QuoteForBase {
+            self.record_sale_buy(market_index, trader, amount_in, amount_out)?;
+        }
+
+        Ok((amount_out, protocol_fee))
+    }
+
+    /// Ensure a swap moved a market's reserves by exactly (amount in, -amount out).
+    ///
+    /// `reserves` are the (base, quote) reserves before the swap, `amount_out` is everything that
+    /// left the output reserve, protocol fee included.
+    fn ensure_swap_invariant(
+        &self,
+        market_index: U64,
+        direction: SwapDirection,
+        reserves: (U256, U256),
+        amount_in: U256,
+        amount_out: U256,
+    ) -> Result<(), ContractError> {
+        let (base_reserve, quote_reserve) = reserves;
+        let expected = match direction {
+            SwapDirection::BaseForQuote => (
+                base_reserve.checked_add(amount_in),
+                quote_reserve.checked_sub(amount_out),
+            ),
+            SwapDirection::QuoteForBase => (
+                base_reserve.checked_sub(amount_out),
+                quote_reserve.checked_add(amount_in),
+            ),
+        };
+
+        let market = self.markets.get(market_index);
+        let actual = (market.base_reserve.get(), market.quote_reserve.get());
+        if expected != (Some(actual.0), Some(actual.1)) {
+            return Err(ContractError::InvariantViolated(InvariantViolated {}));
+        }
+
+        Ok(())
+    }
+
+    /// Pull the input token of a swap from the caller and pay the output token to it.
+    fn settle_swap(
+        base_token: Address,
+        quote_token: Address,
+        direction: SwapDirection,
+        amount_in: U256,
+        amount_out: U256,
+    ) -> Result<(), ContractError> {
+        let trader = msg::sender();
+        match direction {
+            SwapDirection::BaseForQuote => {
+                // Transfer base token from user.
+                if !Self::pull_tokens(base_token, trader, amount_in)? {
+                    return Err(ContractError::BaseTokenTransferFailed(
+                        BaseTokenTransferFailed {},
+                    ));
+                }
+
+                // Transfer quote token to user.
+                if !Self::push_tokens(quote_token, trader, amount_out)? {
+                    return Err(ContractError::QuoteTokenTransferFailed(
+                        QuoteTokenTransferFailed {},
+                    ));
+                }
+            }
+            SwapDirection::QuoteForBase => {
+                // Transfer quote token to contract.
+                if !Self::pull_tokens(quote_token, trader, amount_in)? {
+                    return Err(ContractError::QuoteTokenTransferFailed(
+                        QuoteTokenTransferFailed {},
+                    ));
+                }
+
+                // Transfer base token to user.
+                if !Self::push_tokens(base_token, trader, amount_out)? {
+                    return Err(ContractError::BaseTokenTransferFailed(
+                        BaseTokenTransferFailed {},
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Swap base token for quote token, optionally locked to an expected rate.
+    fn swap_base_token_for_quote_token_internal(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        base_amount: U256,
+        expected_rate: Option<U256>,
+    ) -> Result<(), ContractError> {
+        // Ensures base amount is not 0.
+        if base_amount == U256::from(0) {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensures base token address is not a zero address.
+        if base_token == Address::ZERO {
+            return Err(ContractError::BaseTokenCanNotBeZeroAddress(
+                BaseTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Ensures quote token address is not a zero address.
+        if quote_token == Address::ZERO {
+            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
+                QuoteTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Resolve the full balance sentinel, capped by the allowance.
+        let base_amount = if base_amount == U256::MAX {
+            Self::spendable_tokens(base_token, msg::sender())
+        } else {
+            base_amount
+        };
+
+        // Ensures the resolved amount is not 0.
+        if base_amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Get market from the base token and quote token.
+        let mut base_token_map = self.indexes.setter(base_token);
+        let quote_token_map = base_token_map.setter(quote_token);
+        let market_index = quote_token_map.get();
+
+        // Fill the swap against the market reserves.
+        let quote_amount = self.fill_swap(
+            msg::sender(),
+            market_index,
+            SwapDirection::BaseForQuote,
+            base_amount,
+            expected_rate,
+        )?;
+
+        // Settle the swap with the caller.
+        Self::settle_swap(
+            base_token,
+            quote_token,
+            SwapDirection::BaseForQuote,
+            base_amount,
+            quote_amount,
+        )
+    }
+
+    /// Swap quote token for base token, optionally locked to an expected rate.
+    fn swap_quote_token_for_base_token_internal(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        quote_amount: U256,
+        expected_rate: Option<U256>,
+    ) -> Result<(), ContractError> {
+        // Ensures amount is not 0.
+        if quote_amount == U256::from(0) {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensures the base token address is not a zero address.
+        if base_token == Address::ZERO {
+            return Err(ContractError::BaseTokenCanNotBeZeroAddress(
+                BaseTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Ensures the quote token address is not a zero address.
+        if quote_token == Address::ZERO {
+            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
+                QuoteTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Resolve the full balance sentinel, capped by the allowance.
+        let quote_amount = if quote_amount == U256::MAX {
+            Self::spendable_tokens(quote_token, msg::sender())
+        } else {
+            quote_amount
+        };
+
+        // Ensures the resolved amount is not 0.
+        if quote_amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Get market from the base token and quote token.
+        let mut base_token_map = self.indexes.setter(base_token);
+        let quote_token_map = base_token_map.setter(quote_token);
+        let market_index = quote_token_map.get();
+
+        // Fill the swap against the market reserves.
+        let base_amount = self.fill_swap(
+            msg::sender(),
+            market_index,
+            SwapDirection::QuoteForBase,
+            quote_amount,
+            expected_rate,
+        )?;
+
+        // Settle the swap with the caller.
+        Self::settle_swap(
+            base_token,
+            quote_token,
+            SwapDirection::QuoteForBase,
+            quote_amount,
+            base_amount,
+        )
+    }
+
+    /// Ensure the market rate matches the rate the caller expects, if any.
+    fn ensure_expected_rate(
+        expected_rate: Option<U256>,
+        exchange_rate: U256,
+    ) -> Result<(), ContractError> {
+        match expected_rate {
+            Some(expected) if expected != exchange_rate => {
+                Err(ContractError::RateChanged(RateChanged {
+                    expected,
+                    actual: exchange_rate,
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Ensure a new rate of a market is within its rate limit of the current rate and of the
+    /// rate its window started at, starting a new window once the last one elapsed.
+    fn ensure_rate_change_allowed(
+        &mut self,
+        market_index: U64,
+        new_rate: U256,
+    ) -> Result<(), ContractError> {
+        let limit = self.rate_limits.get(market_index);
+        let max_change_bps = limit.max_change_bps.get().to::<u16>();
+        let max_window_change_bps = limit.max_window_change_bps.get().to::<u16>();
+        let window = limit.window.get().to::<u64>();
+        let window_end = limit.window_start.get().to::<u64>().saturating_add(window);
+        let exchange_rate = self.market_rate(market_index);
+
+        // Ensure the update stays within the limit.
+        Self::ensure_rate_change_within(exchange_rate, new_rate, max_change_bps)?;
+        if max_window_change_bps == 0 || window == 0 {
+            return Ok(());
+        }
+
+        // Start a new window once the last one elapsed.
+        let mut limit = self.rate_limits.setter(market_index);
+        if chain::timestamp() >= window_end {
+            limit.window_start.set(U64::from(chain::timestamp()));
+            limit.window_start_rate.set(exchange_rate);
+        }
+
+        // Ensure the updates of the window stay within the limit.
+        let window_start_rate = limit.window_start_rate.get();
+        Self::ensure_rate_change_within(window_start_rate, new_rate, max_window_change_bps)
+    }
+
+    /// Ensure a rate moved at most `max_change_bps` away from a reference rate, 0 if unlimited.
+    fn ensure_rate_change_within(
+        reference_rate: U256,
+        new_rate: U256,
+        max_change_bps: u16,
+    ) -> Result<(), ContractError> {
+        if max_change_bps == 0 {
+            return Ok(());
+        }
+
+        let max_change = math::mul_div(
+            reference_rate,
+            U256::from(max_change_bps),
+            U256::from(BPS),
+            math::Rounding::Down,
+        )
+        .unwrap_or(U256::MAX);
+        if new_rate.abs_diff(reference_rate) > max_change {
+            return Err(ContractError::RateChangeTooLarge(RateChangeTooLarge {
+                reference_rate,
+                new_rate,
+                max_change_bps,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Ensure a rate lies within a market's rate band, a `max_rate` of 0 if unbounded above.
+    fn ensure_rate_in_bounds(
+        min_rate: U256,
+        max_rate: U256,
+        rate: U256,
+    ) -> Result<(), ContractError> {
+        if rate < min_rate || (!max_rate.is_zero() && rate > max_rate) {
+            return Err(ContractError::RateOutOfBounds(RateOutOfBounds {
+                min_rate,
+                max_rate,
+                attempted_rate: rate,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Close a triggered conditional order of a market and price its fill for `settle_orders`.
+    ///
+    /// Return (maker, direction, amount in, amount out, protocol fee, keeper tip), the amount in
+    /// net of the keeper tip.
+    fn price_conditional_order(
+        &mut self,
+        order_id: U256,
+        market_index: U64,
+    ) -> Result<(Address, SwapDirection, U256, U256, U256, U256), ContractError> {
+        // Ensure the order is open and on the market.
+        let order = self.conditional_orders.get(order_id);
+        let amount_in = order.amount_in.get();
+        if amount_in.is_zero() {
+            return Err(ContractError::OrderNotOpen(OrderNotOpen { order_id }));
+        }
+        if order.market_index.get() != market_index {
+            return Err(ContractError::OrderMarketMismatch(OrderMarketMismatch {
+                order_id,
+            }));
+        }
+        let maker = order.maker.get();
+        let direction = SwapDirection::try_from(order.direction.get().to::<u8>())?;
+        let condition = TriggerCondition::try_from(order.condition.get().to::<u8>())?;
+        let trigger_rate = order.trigger_rate.get();
+        let min_amount_out = order.min_amount_out.get();
+
+        // Ensure the order has not expired.
+        if self.conditional_order_expired(order_id) {
+            return Err(ContractError::OrderExpired(OrderExpired { order_id }));
+        }
+
+        // Ensure the market rate crossed the trigger.
+        let rate = self.market_rate(market_index);
+        if !condition.is_reached(rate, trigger_rate) {
+            return Err(ContractError::TriggerNotReached(TriggerNotReached { rate }));
+        }
+
+        // Close order.
+        self.close_conditional_order(order_id);
+
+        // Take the keeper tip out of the escrow.
+        let keeper_tip = self.keeper_tip(market_index, amount_in);
+        let amount_in = amount_in - keeper_tip;
+
+        // Price the fill for the maker.
+        let terms = SwapTerms::Maker;
+        let (amount_out, protocol_fee) =
+            self.price_swap(maker, market_index, direction, amount_in, None, terms)?;
+
+        // Ensure the amount out is not below the minimum.
+        if amount_out < min_amount_out {
+            return Err(ContractError::InsufficientAmountOut(
+                InsufficientAmountOut {
+                    min_amount_out,
+                    amount_out,
+                },
+            ));
+        }
+
+        // Record the fill in the maker's stats.
+        self.record_user_swap(maker, market_index, direction, amount_in, amount_out);
+
+        Ok((
+            maker,
+            direction,
+            amount_in,
+            amount_out,
+            protocol_fee,
+            keeper_tip,
+        ))
+    }
+
+    /// Fill a triggered conditional order, whole or `amount_in` of it.
+    fn fill_conditional_order_internal(
+        &mut self,
+        order_id: U256,
+        amount_in: Option<U256>,
+    ) -> Result<U256, ContractError> {
+        // Ensure the subsystem is compiled in.
+        Self::ensure_feature(cfg!(feature = "orders"))?;
+
+        // Ensure the order is open.
+        let order = self.conditional_orders.get(order_id);
+        let escrow = order.amount_in.get();
+        if escrow.is_zero() {
+            return Err(ContractError::OrderNotOpen(OrderNotOpen { order_id }));
+        }
+        let maker = order.maker.get();
+        let market_index = order.market_index.get();
+        let direction = SwapDirection::try_from(order.direction.get().to::<u8>())?;
+        let condition = TriggerCondition::try_from(order.condition.get().to::<u8>())?;
+        let trigger_rate = order.trigger_rate.get();
+        let min_amount_out = order.min_amount_out.get();
+        let min_fill = order.min_fill.get();
+
+        // Ensure the order has not expired.
+        if self.conditional_order_expired(order_id) {
+            return Err(ContractError::OrderExpired(OrderExpired { order_id }));
+        }
+
+        // Ensure a partial fill is allowed and at least the minimum fill.
+        let amount_in = amount_in.unwrap_or(escrow);
+        if amount_in.is_zero()
+            || amount_in > escrow
+            || (amount_in < escrow && (min_fill.is_zero() || amount_in < min_fill))
+        {
+            return Err(ContractError::InvalidFillAmount(InvalidFillAmount {
+                min_fill,
+                remaining: escrow,
+            }));
+        }
+
+        // Ensure the market rate crossed the trigger.
+        let rate = self.market_rate(market_index);
+        if !condition.is_reached(rate, trigger_rate) {
+            return Err(ContractError::TriggerNotReached(TriggerNotReached { rate }));
+        }
+
+        // Ensure the market accepts direct swaps.
+        let market = self.markets.get(market_index);
+        if market.commit_reveal.get() {
+            return Err(ContractError::CommitRevealRequired(CommitRevealRequired {}));
+        }
+        let (token_in, token_out) = match direction {
+            SwapDirection::BaseForQuote => (market.base_token.get(), market.quote_token.get()),
+            SwapDirection::QuoteForBase => (market.quote_token.get(), market.base_token.get()),
+        };
+
+        // Close the order, or keep the rest open with its share of the minimum out.
+        let remaining = escrow - amount_in;
+        let fill_min_amount_out = if remaining.is_zero() {
+            self.close_conditional_order(order_id);
+            min_amount_out
+        } else {
+            let fill_min_amount_out =
+                math::mul_div(min_amount_out, amount_in, escrow, math::Rounding::Up)
+                    .unwrap_or(min_amount_out);
+            let mut order = self.conditional_orders.setter(order_id);
+            order.amount_in.set(remaining);
+            order
+                .min_amount_out
+                .set(min_amount_out - fill_min_amount_out);
+            fill_min_amount_out
+        };
+
+        // Take the keeper tip out of the fill.
+        let keeper = msg::sender();
+        let keeper_tip = self.keeper_tip(market_index, amount_in);
+        let swap_amount = amount_in - keeper_tip;
+
+        // Fill the swap for the maker, moving the rest of the fill into the reserves.
+        self.sub_tvl(token_in, amount_in);
+        let terms = SwapTerms::Maker;
+        let amount_out =
+            self.fill_swap_at(maker, market_index, direction, swap_amount, None, terms)?;
+
+        // Ensure the amount out is not below the minimum.
+        if amount_out < fill_min_amount_out {
+            return Err(ContractError::InsufficientAmountOut(
+                InsufficientAmountOut {
+                    min_amount_out: fill_min_amount_out,
+                    amount_out,
+                },
+            ));
+        }
+
+        // Emit events.
+        evm::log(ConditionalOrderFilled {
+            order_id,
+            keeper,
+            amount_in: swap_amount,
+            amount_out,
+        });
+        if !remaining.is_zero() {
+            evm::log(OrderPartiallyFilled {
+                order_id,
+                filled: amount_in,
+                remaining,
+            });
+        }
+
+        // Transfer the output token to the maker and the tip to the keeper.
+        if !Self::push_tokens(token_out, maker, amount_out)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token: token_out,
+            }));
+        }
+        Self::pay_keeper_tip(keeper, token_in, keeper_tip)?;
+
+        Ok(amount_out)
+    }
+
+    /// Close a conditional order, releasing its escrow and removing it from the market's open
+    /// orders.
+    fn close_conditional_order(&mut self, order_id: U256) {
+        let order = self.conditional_orders.get(order_id);
+        let market_index = order.market_index.get();
+        let position = order.open_position.get().to::<usize>();
+        let mut order = self.conditional_orders.setter(order_id);
+        order.amount_in.set(U256::ZERO);
+        order.open_position.set(U64::ZERO);
+        if position == 0 {
+            return;
+        }
+
+        // Move the last open order into the closed order's place.
+        let mut open_orders = self.open_orders.setter(market_index);
+        let Some(last_order_id) = open_orders.pop() else {
+            return;
+        };
+        if last_order_id != order_id {
+            if let Some(mut slot) = open_orders.setter(position - 1) {
+                slot.set(last_order_id);
+            }
+            self.conditional_orders
+                .setter(last_order_id)
+                .open_position
+                .set(U64::from(position));
+        }
+    }
+
+    /// Whether a conditional order has an expiry that has passed.
+    fn conditional_order_expired(&self, order_id: U256) -> bool {
+        let expires_at = self.conditional_orders.get(order_id).expires_at.get();
+        !expires_at.is_zero() && chain::timestamp() >= expires_at.to::<u64>()
+    }
+
+    /// Get the tip a keeper earns executing an order of a market that moves `amount`.
+    fn keeper_tip(&self, market_index: U64, amount: U256) -> U256 {
+        let bps_of = |bps: U256| {
+            let tip = math::mul_div(amount, bps, U256::from(BPS), math::Rounding::Down);
+            tip.unwrap_or(amount)
+        };
+        let keeper_tip = self.keeper_tips.get(market_index);
+        let tip = match KeeperTipMode::try_from(keeper_tip.mode.get().to::<u8>()) {
+            Ok(KeeperTipMode::Flat) => keeper_tip.amount.get(),
+            Ok(KeeperTipMode::Bps) => bps_of(keeper_tip.amount.get()),
+            Err(_) => U256::ZERO,
+        };
+
+        // Clamp the tip to the bounds.
+        let min_tip = bps_of(U256::from(self.min_keeper_tip_bps.get().to::<u16>()));
+        let max_tip = bps_of(U256::from(self.max_keeper_tip_bps.get().to::<u16>()));
+        tip.clamp(min_tip, max_tip)
+    }
+
+    /// Pay a keeper its tip in a token, if any.
+    fn pay_keeper_tip(keeper: Address, token: Address, amount: U256) -> Result<(), ContractError> {
+        if amount.is_zero() {
+            return Ok(());
+        }
+
+        // Emit event.
+        evm::log(KeeperTipped {
+            keeper,
+            token,
+            amount,
+        });
+
+        // Transfer the tip to the keeper.
+        if !Self::push_tokens(token, keeper, amount)? {
+            return Err(ContractError::TokenTransferFailed(TokenTransferFailed {
+                token,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Get the token a conditional order escrows.
+    fn conditional_order_token_in(&self, order_id: U256) -> Result<Address, ContractError> {
+        let order = self.conditional_orders.get(order_id);
+        let market = self.markets.get(order.market_index.get());
+        match SwapDirection::try_from(order.direction.get().to::<u8>())? {
+            SwapDirection::BaseForQuote => Ok(market.base_token.get()),
+            SwapDirection::QuoteForBase => Ok(market.quote_token.get()),
+        }
+    }
+
+    /// Ensure the market of a token pair accepts swaps outside commit-reveal.
+    fn ensure_direct_swap_allowed(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+    ) -> Result<(), ContractError> {
+        let market_index = self.indexes.get(base_token).get(quote_token);
+        if self.markets.get(market_index).commit_reveal.get() {
+            return Err(ContractError::CommitRevealRequired(CommitRevealRequired {}));
+        }
+
+        Ok(())
+    }
+
+    /// Derive a swap commitment from `keccak256(committer, market_index, direction, amount, salt)`.
+    fn derive_swap_commitment(
+        committer: Address,
+        market_index: U64,
+        direction: u8,
+        amount: U256,
+        salt: B256,
+    ) -> B256 {
+        let mut encoded = [0u8; 93];
+        encoded[..20].copy_from_slice(committer.as_slice());
+        encoded[20..28].copy_from_slice(&market_index.to_be_bytes::<8>());
+        encoded[28] = direction;
+        encoded[29..61].copy_from_slice(&amount.to_be_bytes::<32>());
+        encoded[61..93].copy_from_slice(salt.as_slice());
+
+        crypto::keccak(encoded)
+    }
+
+    /// Create a new market in storage.
+    ///
+    /// Return the index of the created market.
+    fn create_market_internal(
+        &mut self,
+        base_token: Address,
+        quote_token: Address,
+        exchange_rate: U256,
+    ) -> Result<U64, ContractError> {
+        // Ensure the market can be created.
+        self.ensure_market_creatable(base_token, quote_token, exchange_rate)?;
+
+        // Get the current market index.
+        let current_market_index = self.market_index.get();
+
+        // Create a new market in storage.
+        let mut market = self.markets.setter(current_market_index);
+        market.base_token.set(base_token);
+        market.quote_token.set(quote_token);
+        market.exchange_rate.set(exchange_rate);
+        market.status.set(U8::from(MarketStatus::Active as u8));
+        market.creator.set(msg::sender());
+        market.base_decimals.set(Self::token_decimals(base_token));
+        market.quote_decimals.set(Self::token_decimals(quote_token));
+        self.decimals_cached_at
+            .insert(current_market_index, U64::from(chain::timestamp()));
+        self.base_symbols
+            .insert(current_market_index, Self::token_symbol(base_token));
+        self.quote_symbols
+            .insert(current_market_index, Self::token_symbol(quote_token));
+
+        // Map market_id => market_index if deterministic ids are enabled.
+        if self.deterministic_market_ids.get() {
+            let market_id =
+                Self::derive_market_id(base_token, quote_token, self.market_id_salt.get());
+            market.market_id.set(market_id);
+            self.deterministic_indexes.insert(market_id, current_market_index);
+        }
+
+        // Map (base_token_address, quote_token_address) => market_index, unless the pair
+        // already has an open market.
+        let primary_market_index = self.indexes.get(base_token).get(quote_token);
+        if primary_market_index.is_zero()
+            || self.market_status(primary_market_index) == MarketStatus::Closed
+        {
+            let mut base_token_map = self.indexes.setter(base_token);
+            let mut quote_token_map = base_token_map.setter(quote_token);
+            quote_token_map.set(current_market_index);
+        }
+
+        // Record the market in the pair history.
+        let mut base_token_history = self.pair_history.setter(base_token);
+        let mut quote_token_history = base_token_history.setter(quote_token);
+        quote_token_history.push(current_market_index);
+
+        // List the tokens for total value locked.
+        self.list_tvl_token(base_token);
+        self.list_tvl_token(quote_token);
+
+        // Lock the minimum liquidity.
+        self.locked_liquidity
+            .insert(current_market_index, self.min_locked_liquidity.get());
+
+        // Set new market index.
+        self.market_index.set(current_market_index + U64::from(1));
+
+        // Emit events.
+        evm::log(MarketCreated {
+            base_token,
+            quote_token,
+            exchange_rate,
+            market_index: U256::from(current_market_index),
+            creator: msg::sender(),
+        });
+        self.sync_effective_rate(current_market_index, RateChangeReason::Manual);
+
+        Ok(current_market_index)
+    }
+
+    /// Ensure a market can be created for a token pair and exchange rate.
+    fn ensure_market_creatable(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+        exchange_rate: U256,
+    ) -> Result<(), ContractError> {
+        // Ensure the caller may create markets.
+        self.only_allowed_creator()?;
+        self.ensure_not_sunset()?;
+
+        // Ensures rate is not 0.
+        if exchange_rate == U256::from(0) {
+            return Err(ContractError::ExchangeRateCanNotBeZero(
+                ExchangeRateCanNotBeZero {},
+            ));
+        }
+
+        // Ensures the base token address is not a zero address.
         if base_token == Address::ZERO {
             return Err(ContractError::BaseTokenCanNotBeZeroAddress(
                 BaseTokenCanNotBeZeroAddress {},
             ));
         }
 
-        // Ensures quote token address is not a zero address.
-        if quote_token == Address::ZERO {
-            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
-                QuoteTokenCanNotBeZeroAddress {},
+        // Ensures the quote token address is not a zero address.
+        if quote_token == Address::ZERO {
+            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
+                QuoteTokenCanNotBeZeroAddress {},
+            ));
+        }
+
+        // Ensure the market id is free, deterministic ids are derived from the pair so a
+        // pair with an open market can not be listed again while they are enabled.
+        let market_index = self.indexes.get(base_token).get(quote_token);
+        if self.deterministic_market_ids.get()
+            && !market_index.is_zero()
+            && self.market_status(market_index) != MarketStatus::Closed
+        {
+            return Err(ContractError::MarketExists(MarketExists {}));
+        }
+
+        Ok(())
+    }
+
+    /// Get the oldest market of a pair that is not closed, 0 if none.
+    fn first_open_market(&self, base_token: Address, quote_token: Address) -> U64 {
+        let base_token_history = self.pair_history.getter(base_token);
+        let quote_token_history = base_token_history.getter(quote_token);
+
+        for i in 0..quote_token_history.len() {
+            let market_index = quote_token_history.get(i).unwrap_or_default();
+            if self.market_status(market_index) != MarketStatus::Closed {
+                return market_index;
+            }
+        }
+
+        U64::ZERO
+    }
+
+    /// Get the active market of a pair paying the most per unit in a direction, net of fees.
+    ///
+    /// Markets in `skip`, with an empty output reserve or requiring commit-reveal are skipped,
+    /// ties go to the oldest market. Return 0 if none.
+    fn best_market(
+        &self,
+        base_token: Address,
+        quote_token: Address,
+        direction: SwapDirection,
+        skip: &[U64],
+    ) -> U64 {
+        let base_token_history = self.pair_history.getter(base_token);
+        let quote_token_history = base_token_history.getter(quote_token);
+
+        let mut best_market_index = U64::ZERO;
+        let mut best_rate = U256::ZERO;
+        let mut best_net_bps = U256::ZERO;
+        for i in 0..quote_token_history.len() {
+            let market_index = quote_token_history.get(i).unwrap_or_default();
+            if self.market_status(market_index) != MarketStatus::Active
+                || skip.contains(&market_index)
+            {
+                continue;
+            }
+
+            // Skip markets that can not fill a direct swap.
+            let reserve_out = self.reserve_out(market_index, direction);
+            if reserve_out.is_zero()
+                || self.markets.get(market_index).commit_reveal.get()
+                || !self.direction_mode(market_index).allows(direction)
+            {
+                continue;
+            }
+
+            // Compare rate * net or net / rate against the best market so far.
+            let rate = self.market_rate(market_index);
+            let net_bps = U256::from(BPS.saturating_sub(self.swap_fee_bps(market_index)));
+            let better = best_market_index.is_zero()
+                || match direction {
+                    SwapDirection::BaseForQuote => {
+                        rate.saturating_mul(net_bps) > best_rate.saturating_mul(best_net_bps)
+                    }
+                    SwapDirection::QuoteForBase => {
+                        net_bps.saturating_mul(best_rate) > best_net_bps.saturating_mul(rate)
+                    }
+                };
+            if better {
+                best_market_index = market_index;
+                best_rate = rate;
+                best_net_bps = net_bps;
+            }
+        }
+
+        best_market_index
+    }
+
+    /// Whether a swap error means the market turned the trader away in `price_swap`, before
+    /// any write, for routes that can fill elsewhere.
+    ///
+    /// Errors raised once the fill is recorded, eg. `AfterSwapHookRejected`, must revert.
+    fn rejects_trader(error: &ContractError) -> bool {
+        matches!(
+            error,
+            ContractError::NotCompliant(_)
+                | ContractError::ContractCallersNotAllowed(_)
+                | ContractError::PresaleAccessDenied(_)
+                | ContractError::PurchaseCapExceeded(_)
+                | ContractError::SaleNotOpen(_)
+                | ContractError::HardCapExceeded(_)
+                | ContractError::HookRejected(_)
+        )
+    }
+
+    /// Close a market and return its reserves to the creator.
+    fn close_market_internal(&mut self, market_index: U64) -> Result<(), ContractError> {
+        // Ensure the market is not closed.
+        if self.market_status(market_index) == MarketStatus::Closed {
+            return Err(ContractError::MarketAlreadyClosed(MarketAlreadyClosed {}));
+        }
+
+        // Ensure the contributions to an open or failed sale stay in the market for refunds.
+        let escrow = self.sale_escrow(market_index);
+        if !escrow.is_zero() {
+            return Err(ContractError::LiquidityLocked(LiquidityLocked {
+                locked: escrow,
+            }));
+        }
+
+        // Set market status.
+        self.set_market_status(market_index, MarketStatus::Closed);
+
+        // Resync rebasing reserves.
+        self.sync_rebasing_reserves(market_index);
+
+        // Clear reserves.
+        let mut market = self.markets.setter(market_index);
+        let creator = market.creator.get();
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
+        let base_amount = market.base_reserve.get();
+        let quote_amount = market.quote_reserve.get();
+        market.base_reserve.set(U256::ZERO);
+        market.quote_reserve.set(U256::ZERO);
+
+        // Update total value locked.
+        self.sub_tvl(base_token, base_amount);
+        self.sub_tvl(quote_token, quote_amount);
+        self.commit_rebasing_reserves(market_index);
+
+        // Map (base_token_address, quote_token_address) to the next open market of the pair.
+        if self.indexes.get(base_token).get(quote_token) == market_index {
+            let next_market_index = self.first_open_market(base_token, quote_token);
+            let mut base_token_map = self.indexes.setter(base_token);
+            let mut quote_token_map = base_token_map.setter(quote_token);
+            quote_token_map.set(next_market_index);
+        }
+
+        // Emit event.
+        evm::log(MarketClosed {
+            market_index: U256::from(market_index),
+            creator,
+            base_amount,
+            quote_amount,
+        });
+
+        // Transfer base token to creator.
+        if !Self::push_tokens(base_token, creator, base_amount)? {
+            return Err(ContractError::BaseTokenTransferFailed(
+                BaseTokenTransferFailed {},
+            ));
+        }
+
+        // Transfer quote token to creator.
+        if !Self::push_tokens(quote_token, creator, quote_amount)? {
+            return Err(ContractError::QuoteTokenTransferFailed(
+                QuoteTokenTransferFailed {},
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Get the time a market's pending creator withdrawal executes at.
+    fn pending_withdrawal_at(&self, market_index: U64) -> Result<u64, ContractError> {
+        let executable_at = self
+            .withdrawal_queues
+            .get(market_index)
+            .executable_at
+            .get()
+            .to::<u64>();
+        if executable_at == 0 {
+            return Err(ContractError::NoPendingWithdrawal(NoPendingWithdrawal {}));
+        }
+
+        Ok(executable_at)
+    }
+
+    /// Clear a market's pending creator withdrawal, resuming the market if the withdrawal
+    /// paused it.
+    ///
+    /// Return the amounts the withdrawal was for.
+    fn clear_pending_withdrawal(&mut self, market_index: U64) -> (U256, U256) {
+        let mut queue = self.withdrawal_queues.setter(market_index);
+        let base_amount = queue.base_amount.get();
+        let quote_amount = queue.quote_amount.get();
+        let paused = queue.paused.get();
+        queue.base_amount.set(U256::ZERO);
+        queue.quote_amount.set(U256::ZERO);
+        queue.executable_at.set(U64::ZERO);
+        queue.paused.set(false);
+
+        if paused && self.market_status(market_index) == MarketStatus::Paused {
+            self.set_market_status(market_index, MarketStatus::Active);
+        }
+
+        (base_amount, quote_amount)
+    }
+
+    /// Withdraw liquidity from a market to the caller.
+    fn withdraw_liquidity_internal(
+        &mut self,
+        market_index: U64,
+        base_amount: U256,
+        quote_amount: U256,
+        recipient: Address,
+    ) -> Result<(), ContractError> {
+        // Ensures the amounts are not both 0.
+        if base_amount.is_zero() && quote_amount.is_zero() {
+            return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
+        }
+
+        // Ensures the recipient is not a zero address.
+        if recipient == Address::ZERO {
+            return Err(ContractError::RecipientCanNotBeZeroAddress(
+                RecipientCanNotBeZeroAddress {},
             ));
         }
 
-        // Get market from the base token and quote token.
-        let mut base_token_map = self.indexes.setter(base_token);
-        let quote_token_map = base_token_map.setter(quote_token);
-        let market_index = quote_token_map.get();
+        // Let the hook veto the withdrawal.
+        self.call_before_liquidity(market_index, false, base_amount, quote_amount)?;
 
-        // Get market.
-        let market = self.markets.get(market_index);
+        // Resync rebasing reserves.
+        self.sync_rebasing_reserves(market_index);
 
-        // Get market rate.
-        let exchange_rate = market.exchange_rate.get();
+        // Ensure the market holds enough liquidity.
+        self.ensure_liquidity_withdrawable(market_index, base_amount, quote_amount)?;
 
-        // Calculate the quote token amount.
-        let quote_amount = base_amount.checked_mul(exchange_rate);
+        // Update reserves.
+        let mut market = self.markets.setter(market_index);
+        let base_reserve = market.base_reserve.get();
+        let quote_reserve = market.quote_reserve.get();
+        market.base_reserve.set(base_reserve - base_amount);
+        market.quote_reserve.set(quote_reserve - quote_amount);
 
-        // Return overflow error.
-        if quote_amount.is_none() {
-            return Err(ContractError::MultiplicationOverflow(
-                MultiplicationOverflow {},
+        // Update total value locked.
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
+        self.sub_tvl(base_token, base_amount);
+        self.sub_tvl(quote_token, quote_amount);
+        self.commit_rebasing_reserves(market_index);
+
+        // Emit event.
+        let provider = msg::sender();
+        evm::log(LiquidityWithdrawn {
+            market_index: U256::from(market_index),
+            provider,
+            base_amount,
+            quote_amount,
+        });
+
+        // Transfer base token to recipient.
+        if !Self::push_tokens(base_token, recipient, base_amount)? {
+            return Err(ContractError::BaseTokenTransferFailed(
+                BaseTokenTransferFailed {},
             ));
         }
 
-        // Safely unwrap the quote amount.
-        let quote_amount = quote_amount.unwrap();
+        // Transfer quote token to recipient.
+        if !Self::push_tokens(quote_token, recipient, quote_amount)? {
+            return Err(ContractError::QuoteTokenTransferFailed(
+                QuoteTokenTransferFailed {},
+            ));
+        }
 
-        // Transfer base token from user.
-        let base_token_contract = IErc20::new(market.base_token.get());
-        let _ =
-            base_token_contract.transfer_from(Call::new(), msg::sender(), address(), base_amount);
+        Ok(())
+    }
 
-        // Transfer quote token transfer to user.
-        let quote_token_contract = IErc20::new(market.quote_token.get());
-        let _ =
-            quote_token_contract.transfer(Call::new(), msg::sender(), quote_amount);
+    /// Ensure a market's reserves cover a withdrawal without touching the locked and airdropped
+    /// base token or the escrowed sale proceeds.
+    fn ensure_liquidity_withdrawable(
+        &self,
+        market_index: U64,
+        base_amount: U256,
+        quote_amount: U256,
+    ) -> Result<(), ContractError> {
+        let locked = self.locked_liquidity.get(market_index) + self.airdrop_escrow(market_index);
+        let escrow = self.sale_escrow(market_index);
+        let market = self.markets.get(market_index);
+        let base_reserve = market.base_reserve.get();
+        let quote_reserve = market.quote_reserve.get();
+        if base_amount > base_reserve || quote_amount > quote_reserve {
+            return Err(ContractError::InsufficientLiquidity(
+                InsufficientLiquidity {},
+            ));
+        }
 
-        // Emit event.
-        evm::log(SwappedBaseTokenForQuoteToken {
-            base_token,
-            quote_token,
-            amount_in: base_amount,
-            amount_out: quote_amount,
-        });
+        // Ensure the locked and airdropped base token stays in the market.
+        if !base_amount.is_zero() && base_reserve - base_amount < locked {
+            return Err(ContractError::LiquidityLocked(LiquidityLocked { locked }));
+        }
+
+        // Ensure the escrowed sale proceeds stay in the market.
+        if !quote_amount.is_zero() && quote_reserve - quote_amount < escrow {
+            return Err(ContractError::LiquidityLocked(LiquidityLocked {
+                locked: escrow,
+            }));
+        }
 
         Ok(())
     }
 
-    /// Swap base token for quote token.
-    pub fn swap_quote_token_for_base_token(
+    /// Split an amount of a token into the (base, quote) amounts of a market listing it.
+    fn reserve_amounts(
+        &self,
+        market_index: U64,
+        token: Address,
+        amount: U256,
+    ) -> Result<(U256, U256), ContractError> {
+        let market = self.markets.get(market_index);
+        if token == market.base_token.get() {
+            Ok((amount, U256::ZERO))
+        } else if token == market.quote_token.get() {
+            Ok((U256::ZERO, amount))
+        } else {
+            Err(ContractError::TokenNotInMarket(TokenNotInMarket { token }))
+        }
+    }
+
+    /// Get the reserve a swap in a direction pays out of, excluding locked base token, escrowed
+    /// sale proceeds and unclaimed airdrops.
+    fn reserve_out(&self, market_index: U64, direction: SwapDirection) -> U256 {
+        let market = self.markets.get(market_index);
+        match direction {
+            SwapDirection::BaseForQuote => market
+                .quote_reserve
+                .get()
+                .saturating_sub(self.sale_escrow(market_index)),
+            SwapDirection::QuoteForBase => market
+                .base_reserve
+                .get()
+                .saturating_sub(self.locked_liquidity.get(market_index))
+                .saturating_sub(self.airdrop_escrow(market_index)),
+        }
+    }
+
+    /// Pull liquidity from the caller into a market.
+    fn deposit_liquidity_internal(
         &mut self,
-        base_token: Address,
-        quote_token: Address,
+        market_index: U64,
+        base_amount: U256,
         quote_amount: U256,
     ) -> Result<(), ContractError> {
-        // Ensures amount is not 0.
-        if quote_amount == U256::from(0) {
+        // Ensures the amounts are not both 0.
+        if base_amount.is_zero() && quote_amount.is_zero() {
             return Err(ContractError::AmountCanNotBeZero(AmountCanNotBeZero {}));
         }
 
-        // Ensures the base token address is not a zero address.
-        if base_token == Address::ZERO {
-            return Err(ContractError::BaseTokenCanNotBeZeroAddress(
-                BaseTokenCanNotBeZeroAddress {},
+        // Ensure the market is not expired or closed.
+        let status = self.market_status(market_index);
+        if status != MarketStatus::Active && status != MarketStatus::Paused {
+            return Err(ContractError::MarketNotActive(MarketNotActive {
+                status: status as u8,
+            }));
+        }
+
+        // Ensure a two-sided deposit matches the market rate.
+        self.ensure_deposit_ratio(market_index, base_amount, quote_amount)?;
+
+        // Let the hook veto the deposit.
+        self.call_before_liquidity(market_index, true, base_amount, quote_amount)?;
+
+        // Resync rebasing reserves.
+        self.sync_rebasing_reserves(market_index);
+
+        // Update reserves.
+        let mut market = self.markets.setter(market_index);
+        let base_reserve = market.base_reserve.get();
+        let quote_reserve = market.quote_reserve.get();
+        market.base_reserve.set(base_reserve + base_amount);
+        market.quote_reserve.set(quote_reserve + quote_amount);
+
+        // Update total value locked.
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
+        self.add_tvl(base_token, base_amount);
+        self.add_tvl(quote_token, quote_amount);
+        self.commit_rebasing_reserves(market_index);
+
+        // Emit event.
+        let provider = msg::sender();
+        evm::log(LiquidityDeposited {
+            market_index: U256::from(market_index),
+            provider,
+            base_amount,
+            quote_amount,
+        });
+
+        // Transfer base token from provider.
+        if !Self::pull_tokens(base_token, provider, base_amount)? {
+            return Err(ContractError::BaseTokenTransferFailed(
+                BaseTokenTransferFailed {},
             ));
         }
 
-        // Ensures the quote token address is not a zero address.
-        if quote_token == Address::ZERO {
-            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
-                QuoteTokenCanNotBeZeroAddress {},
+        // Transfer quote token from provider.
+        if !Self::pull_tokens(quote_token, provider, quote_amount)? {
+            return Err(ContractError::QuoteTokenTransferFailed(
+                QuoteTokenTransferFailed {},
             ));
         }
 
-        // Get market from the base token and quote token.
-        let mut base_token_map = self.indexes.setter(base_token);
-        let quote_token_map = base_token_map.setter(quote_token);
-        let market_index = quote_token_map.get();
+        Ok(())
+    }
 
-        // Get market.
+    /// Ensure the quote amount of a two-sided deposit is within the market's tolerance of the
+    /// base amount priced at the market rate.
+    fn ensure_deposit_ratio(
+        &self,
+        market_index: U64,
+        base_amount: U256,
+        quote_amount: U256,
+    ) -> Result<(), ContractError> {
+        let tolerance = self.deposit_tolerances.get(market_index);
+        if !tolerance.enabled.get() || base_amount.is_zero() || quote_amount.is_zero() {
+            return Ok(());
+        }
+
+        // Price the base amount at the market rate.
+        let rate = self.market_rate(market_index);
+        let Some(expected_quote_amount) = base_amount.checked_mul(rate) else {
+            return Err(ContractError::MultiplicationOverflow(
+                MultiplicationOverflow {},
+            ));
+        };
+
+        // Can not overflow, the tolerance is at most 100%.
+        let max_deviation = math::mul_div(
+            expected_quote_amount,
+            U256::from(tolerance.tolerance_bps.get()),
+            U256::from(BPS),
+            math::Rounding::Down,
+        )
+        .unwrap_or(expected_quote_amount);
+
+        if quote_amount.abs_diff(expected_quote_amount) > max_deviation {
+            return Err(ContractError::DepositRatioMismatch(DepositRatioMismatch {
+                expected_quote_amount,
+                quote_amount,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// List a token in `tvl_tokens` the first time a market uses it.
+    fn list_tvl_token(&mut self, token: Address) {
+        if !self.is_tvl_token.get(token) {
+            self.is_tvl_token.insert(token, true);
+            self.tvl_tokens.push(token);
+        }
+    }
+
+    /// Resync the reserves of a rebasing market from its shares of the contract's balances.
+    ///
+    /// No-op for markets that track raw amounts.
+    fn sync_rebasing_reserves(&mut self, market_index: U64) {
         let market = self.markets.get(market_index);
+        if !market.rebasing.get() {
+            return;
+        }
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
 
-        // Get market rate.
-        let exchange_rate = market.exchange_rate.get();
+        // Sync the balances held for rebasing markets.
+        self.sync_rebasing_pool(base_token);
+        self.sync_rebasing_pool(quote_token);
+
+        // Update reserves from the market's shares.
+        let shares = self.rebasing_shares.get(market_index);
+        let base_reserve = self.rebasing_reserve(base_token, shares.base_shares.get());
+        let quote_reserve = self.rebasing_reserve(quote_token, shares.quote_shares.get());
+        let mut market = self.markets.setter(market_index);
+        market.base_reserve.set(base_reserve);
+        market.quote_reserve.set(quote_reserve);
 
-        // Calculate the base token amount.
-        let base_amount = quote_amount.checked_div(exchange_rate);
+        // Emit event.
+        evm::log(MarketReservesSynced {
+            market_index: U256::from(market_index),
+            base_reserve,
+            quote_reserve,
+        });
+    }
 
-        // Return overflow error.
-        if base_amount.is_none() {
-            return Err(ContractError::DivisionUnderflow(DivisionUnderflow {}));
+    /// Reissue the shares of a rebasing market after its reserves changed.
+    ///
+    /// No-op for markets that track raw amounts.
+    fn commit_rebasing_reserves(&mut self, market_index: U64) {
+        let market = self.markets.get(market_index);
+        if !market.rebasing.get() {
+            return;
         }
+        let base_token = market.base_token.get();
+        let quote_token = market.quote_token.get();
+        let base_reserve = market.base_reserve.get();
+        let quote_reserve = market.quote_reserve.get();
 
-        // Safely unwrap the quote amount.
-        let base_amount = base_amount.unwrap();
+        // Reissue shares for each side.
+        let shares = self.rebasing_shares.get(market_index);
+        let base_shares = shares.base_shares.get();
+        let quote_shares = shares.quote_shares.get();
+        let base_shares = self.reissue_rebasing_shares(base_token, base_shares, base_reserve);
+        let quote_shares = self.reissue_rebasing_shares(quote_token, quote_shares, quote_reserve);
 
-        // Transfer quote token to contract.
-        let quote_token_contract = IErc20::new(market.quote_token.get());
-        let _ =
-            quote_token_contract.transfer_from(Call::new(), msg::sender(), address(), quote_amount);
+        // Update shares.
+        let mut shares = self.rebasing_shares.setter(market_index);
+        shares.base_shares.set(base_shares);
+        shares.quote_shares.set(quote_shares);
+    }
 
-        // Transfer base token to user.
-        let base_token_contract = IErc20::new(market.base_token.get());
-        let _ =
-            base_token_contract.transfer(Call::new(), msg::sender(), base_amount);
+    /// Sync the balance of a token held for rebasing markets with the contract's balance.
+    ///
+    /// Everything the contract holds beyond raw reserves, treasury, insurance, and creator fee
+    /// balances is
+    /// attributed to rebasing markets, and the rebase is reflected in the total value locked.
+    fn sync_rebasing_pool(&mut self, token: Address) {
+        let pool = self.rebasing_pools.get(token);
+        let tvl = self.token_tvl.get(token);
+        let held = tvl.saturating_sub(pool)
+            + self.treasury_balances.get(token)
+            + self.insurance_balances.get(token)
+            + self.creator_fee_totals.get(token);
 
-        Ok(())
+        // Keep the last synced balance if the token cannot be read.
+        let Ok(balance) = IErc20::new(token).balance_of(Call::new(), address()) else {
+            return;
+        };
+        let synced = balance.saturating_sub(held);
+        if synced != pool {
+            self.token_tvl.insert(token, tvl.saturating_sub(pool) + synced);
+            self.rebasing_pools.insert(token, synced);
+        }
     }
 
-    /// Fetch initialization status.
-    pub fn fetch_initialization_status(&self) -> Result<bool, ContractError> {
-        Ok(self.initialized.get())
+    /// Fetch the amount of a token a rebasing market's shares are worth as of the last sync.
+    fn rebasing_reserve(&self, token: Address, shares: U256) -> U256 {
+        let total_shares = self.rebasing_total_shares.get(token);
+        if total_shares.is_zero() {
+            return U256::ZERO;
+        }
+
+        math::mul_div(
+            shares,
+            self.rebasing_pools.get(token),
+            total_shares,
+            math::Rounding::Down,
+        )
+        .unwrap_or_default()
     }
 
-    /// Fetch current market index.
-    pub fn fetch_current_market_index(&self) -> Result<U256, ContractError> {
-        Ok(U256::from(self.market_index.get()))
+    /// Reissue a rebasing market's shares of a token for its new reserve.
+    ///
+    /// Return the market's new shares.
+    fn reissue_rebasing_shares(&mut self, token: Address, shares: U256, reserve: U256) -> U256 {
+        // Get the balance and shares of the other rebasing markets.
+        let total_shares = self.rebasing_total_shares.get(token);
+        let other_amount = self
+            .rebasing_pools
+            .get(token)
+            .saturating_sub(self.rebasing_reserve(token, shares));
+        let other_shares = total_shares.saturating_sub(shares);
+
+        // Issue shares at the other markets' share price, 1:1 if there are none.
+        let new_shares = if other_amount.is_zero() || other_shares.is_zero() {
+            reserve
+        } else {
+            math::mul_div(reserve, other_shares, other_amount, math::Rounding::Down)
+                .unwrap_or(reserve)
+        };
+
+        // Update shares and pool.
+        self.rebasing_total_shares.insert(token, other_shares + new_shares);
+        self.rebasing_pools.insert(token, other_amount + reserve);
+
+        new_shares
     }
 
-    /// Fetch exchange rate.
-    pub fn fetch_exchange_rate(
-        &self,
-        base_token: Address,
-        quote_token: Address,
-    ) -> Result<U256, ContractError> {
-        // Ensures base token address is not a zero address.
-        if base_token == Address::ZERO {
-            return Err(ContractError::BaseTokenCanNotBeZeroAddress(
-                BaseTokenCanNotBeZeroAddress {},
-            ));
+    /// Add reserves entering a market to the total value locked of a token.
+    fn add_tvl(&mut self, token: Address, amount: U256) {
+        let tvl = self.token_tvl.get(token);
+        self.token_tvl.insert(token, tvl + amount);
+    }
+
+    /// Remove reserves leaving a market from the total value locked of a token.
+    fn sub_tvl(&mut self, token: Address, amount: U256) {
+        let tvl = self.token_tvl.get(token);
+        self.token_tvl.insert(token, tvl.saturating_sub(amount));
+    }
+
+    /// Add a swap by a trader to their stats and the volume of a market.
+    fn record_user_swap(
+        &mut self,
+        trader: Address,
+        market_index: U64,
+        direction: SwapDirection,
+        amount_in: U256,
+        amount_out: U256,
+    ) {
+        let mut user_market_stats = self.user_stats.setter(trader);
+        let mut stats = user_market_stats.setter(market_index);
+
+        let swap_count = stats.swap_count.get();
+        stats.swap_count.set(swap_count + U64::from(1));
+
+        let (base_amount, quote_amount) = match direction {
+            SwapDirection::BaseForQuote => {
+                let base_in = stats.base_in.get();
+                let quote_out = stats.quote_out.get();
+                stats.base_in.set(base_in + amount_in);
+                stats.quote_out.set(quote_out + amount_out);
+                (amount_in, amount_out)
+            }
+            SwapDirection::QuoteForBase => {
+                let quote_in = stats.quote_in.get();
+                let base_out = stats.base_out.get();
+                stats.quote_in.set(quote_in + amount_in);
+                stats.base_out.set(base_out + amount_out);
+                (amount_out, amount_in)
+            }
+        };
+
+        // Add the swap to the market volume.
+        let mut volume = self.market_volumes.setter(market_index);
+        let base_volume = volume.base_volume.get();
+        let quote_volume = volume.quote_volume.get();
+        volume.base_volume.set(base_volume + base_amount);
+        volume.quote_volume.set(quote_volume + quote_amount);
+    }
+
+    /// Get the base token a user may still buy from a market, `U256::MAX` if uncapped.
+    fn remaining_allocation(&self, market_index: U64, user: Address) -> U256 {
+        let cap = self.purchase_caps.get(market_index);
+        if cap.is_zero() {
+            return U256::MAX;
         }
 
-        // Ensures quote token address is not a zero address.
-        if quote_token == Address::ZERO {
-            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
-                QuoteTokenCanNotBeZeroAddress {},
-            ));
+        let user_market_stats = self.user_stats.get(user);
+        let purchased = user_market_stats.get(market_index).base_out.get();
+        cap.saturating_sub(purchased)
+    }
+
+    /// Transfer tokens from an account to the contract.
+    ///
+    /// Return whether the token reported success, zero amounts are skipped. A reverted call is
+    /// re-raised as `ExternalCallFailed`.
+    fn pull_tokens(token: Address, from: Address, amount: U256) -> Result<bool, ContractError> {
+        if amount.is_zero() {
+            return Ok(true);
         }
 
-        // Get market from the base token and quote token.
-        let base_token_map = self.indexes.getter(base_token);
-        let quote_token_map = base_token_map.getter(quote_token);
-        let market_index = quote_token_map.get();
+        IErc20::new(token)
+            .transfer_from(Call::new(), from, address(), amount)
+            .map_err(|error| Self::external_call_failed(token, error))
+    }
 
-        // Get market.
-        let market = self.markets.get(market_index);
+    /// Transfer tokens from the contract to an account.
+    ///
+    /// Return whether the token reported success, zero amounts are skipped. A reverted call is
+    /// re-raised as `ExternalCallFailed`.
+    fn push_tokens(token: Address, to: Address, amount: U256) -> Result<bool, ContractError> {
+        if amount.is_zero() {
+            return Ok(true);
+        }
 
-        // Get the exchange rate.
-        let exchange_rate = market.exchange_rate.get();
+        IErc20::new(token)
+            .transfer(Call::new(), to, amount)
+            .map_err(|error| Self::external_call_failed(token, error))
+    }
 
-        Ok(exchange_rate)
+    /// Wrap a failed external call with its target and revert data.
+    ///
+    /// The revert data is empty if the call returned data that does not decode.
+    fn external_call_failed(target: Address, error: call::Error) -> ContractError {
+        let revert_data = match error {
+            call::Error::Revert(data) => data,
+            call::Error::AbiDecodingFailed(_) => Vec::new(),
+        };
+        ContractError::ExternalCallFailed(ExternalCallFailed {
+            target,
+            revert_data: revert_data.into(),
+        })
     }
 
-    /// Fetch market index.
-    pub fn fetch_market_id(
-        &self,
-        base_token: Address,
-        quote_token: Address,
-    ) -> Result<U256, ContractError> {
-        // Ensures the base token address is not a zero address.
-        if base_token == Address::ZERO {
-            return Err(ContractError::BaseTokenCanNotBeZeroAddress(
-                BaseTokenCanNotBeZeroAddress {},
-            ));
+    /// Get the rate of a swap direction as (numerator, denominator).
+    fn direction_rate(direction: SwapDirection, exchange_rate: U256) -> (U256, U256) {
+        match direction {
+            SwapDirection::BaseForQuote => (exchange_rate, U256::from(1)),
+            SwapDirection::QuoteForBase => (U256::from(1), exchange_rate),
         }
+    }
 
-        // Ensures the quote token address is not a zero address.
-        if quote_token == Address::ZERO {
-            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
-                QuoteTokenCanNotBeZeroAddress {},
-            ));
+    /// Fetch the decimals of a token, 18 if the token does not report them.
+    fn token_decimals(token: Address) -> U8 {
+        U8::from(IErc20::new(token).decimals(Call::new()).unwrap_or(18))
+    }
+
+    /// Fetch the symbol of a token truncated to 32 bytes, zero if the token does not report one.
+    ///
+    /// Tolerates tokens returning a non-standard `bytes32` symbol instead of a `string`.
+    fn token_symbol(token: Address) -> B256 {
+        let Ok(data) = call::static_call(Call::new(), token, &function_selector!("symbol")) else {
+            return B256::ZERO;
+        };
+
+        // Non-standard tokens return the symbol as `bytes32`.
+        if data.len() == 32 {
+            return B256::from_slice(&data);
         }
 
-        // Get market from the base token and quote token.
-        let base_token_map = self.indexes.getter(base_token);
-        let quote_token_map = base_token_map.getter(quote_token);
-        let market_index = quote_token_map.get();
+        let Ok(symbol) = <sol_data::String as SolType>::abi_decode(&data, false) else {
+            return B256::ZERO;
+        };
+        let mut packed = B256::ZERO;
+        let len = symbol.len().min(32);
+        packed[..len].copy_from_slice(&symbol.as_bytes()[..len]);
+        packed
+    }
 
-        Ok(U256::from(market_index))
+    /// Pull underlying assets from an account and deposit them into a vault for the contract.
+    ///
+    /// Return the shares minted, `None` if a call fails.
+    fn deposit_to_vault(vault: Address, from: Address, assets: U256) -> Option<U256> {
+        let erc4626 = IErc4626::new(vault);
+        let asset = erc4626.asset(Call::new()).ok()?;
+
+        // Transfer underlying assets from the account and let the vault pull them.
+        if !Self::pull_tokens(asset, from, assets).ok()?
+            || !IErc20::new(asset)
+                .approve(Call::new(), vault, assets)
+                .unwrap_or(false)
+        {
+            return None;
+        }
+
+        erc4626.deposit(Call::new(), assets, address()).ok()
     }
 
-    /// Fetch market by tokens.
-    pub fn fetch_market_by_tokens(
-        &self,
-        base_token: Address,
-        quote_token: Address,
-    ) -> Result<(Address, Address, U256), ContractError> {
-        // Ensures the base token address is not a zero address.
-        if base_token == Address::ZERO {
-            return Err(ContractError::BaseTokenCanNotBeZeroAddress(
-                BaseTokenCanNotBeZeroAddress {},
-            ));
+    /// Fetch the amount of tokens the contract can pull from an account.
+    ///
+    /// Return the smaller of its balance and allowance, 0 if either call fails.
+    fn spendable_tokens(token: Address, owner: Address) -> U256 {
+        let erc20 = IErc20::new(token);
+        let balance = erc20.balance_of(Call::new(), owner).unwrap_or_default();
+        let allowance = erc20
+            .allowance(Call::new(), owner, address())
+            .unwrap_or_default();
+
+        balance.min(allowance)
+    }
+
+    /// Derive the deterministic market id of a token pair.
+    fn derive_market_id(base_token: Address, quote_token: Address, salt: B256) -> U256 {
+        let mut packed = [0u8; 72];
+        packed[..20].copy_from_slice(base_token.as_slice());
+        packed[20..40].copy_from_slice(quote_token.as_slice());
+        packed[40..].copy_from_slice(salt.as_slice());
+
+        U256::from_be_bytes(crypto::keccak(packed).0)
+    }
+
+    /// Derive the id of a market created with a salt.
+    fn derive_salted_market_id(creator: Address, salt: B256) -> U256 {
+        let mut packed = [0u8; 52];
+        packed[..20].copy_from_slice(creator.as_slice());
+        packed[20..].copy_from_slice(salt.as_slice());
+
+        U256::from_be_bytes(crypto::keccak(packed).0)
+    }
+
+    /// Get the EIP-712 domain separator for the current chain.
+    fn current_domain_separator(&self) -> B256 {
+        let chain_id = block::chainid();
+        if U64::from(chain_id) == self.cached_chain_id.get() {
+            return self.cached_domain_separator.get();
         }
 
-        // Ensures the quote token address is not a zero address.
-        if quote_token == Address::ZERO {
-            return Err(ContractError::QuoteTokenCanNotBeZeroAddress(
-                QuoteTokenCanNotBeZeroAddress {},
-            ));
+        eip712::domain_separator(chain_id, address())
+    }
+
+    /// Ensure the caller is a guardian.
+    fn only_guardian(&self) -> Result<(), ContractError> {
+        if !self.is_guardian.get(msg::sender()) {
+            return Err(ContractError::NotGuardian(NotGuardian {}));
         }
 
-        // Get market from the base token and quote token.
-        let base_token_map = self.indexes.getter(base_token);
-        let quote_token_map = base_token_map.getter(quote_token);
-        let market_index = quote_token_map.get();
+        Ok(())
+    }
 
-        // Get market.
-        let market = self.markets.get(U64::from(market_index));
+    /// Ensure the caller is a registered admin.
+    fn only_admin(&self) -> Result<(), ContractError> {
+        if !self.is_admin.get(msg::sender()) {
+            return Err(ContractError::NotAdmin(NotAdmin {}));
+        }
 
-        Ok((
-            market.base_token.get(),
-            market.quote_token.get(),
-            market.exchange_rate.get(),
-        ))
+        Ok(())
     }
 
-    /// Fetch market by id.
-    /// Useful for pagination.
-    ///
-    /// Return market (base_token, quote_token, exchange_rate).
-    pub fn fetch_market_by_id(
-        &self,
-        market_index: u64,
-    ) -> Result<(Address, Address, U256), ContractError> {
-        // Ensure the index is valid.
-        if U64::from(market_index).ge(&self.market_index.get())
-            || U64::from(market_index).eq(&U64::from(0))
-        {
-            return Err(ContractError::OutOfBoundIndex(OutOfBoundIndex {}));
+    /// Ensure the admin action exists and has not been executed.
+    fn ensure_pending_admin_action(&self, action_id: U256) -> Result<(), ContractError> {
+        if action_id.is_zero() || action_id > self.admin_action_count.get() {
+            return Err(ContractError::AdminActionNotFound(AdminActionNotFound {}));
         }
 
-        // Get market.
-        let market = self.markets.get(U64::from(market_index));
+        if self.admin_actions.get(action_id).executed.get() {
+            return Err(ContractError::AdminActionAlreadyExecuted(
+                AdminActionAlreadyExecuted {},
+            ));
+        }
 
-        Ok((
-            market.base_token.get(),
-            market.quote_token.get(),
-            market.exchange_rate.get(),
-        ))
+        Ok(())
+    }
+
+    /// Count the confirmations of an admin action by current admins.
+    fn count_admin_confirmations(&self, action_id: U256) -> u64 {
+        let action = self.admin_actions.get(action_id);
+        let mut confirmations = 0;
+        for i in 0..self.admins.len() {
+            let admin = self.admins.get(i).unwrap_or_default();
+            if action.confirmed.get(admin) {
+                confirmations += 1;
+            }
+        }
+
+        confirmations
     }
 }
@@ -0,0 +1,194 @@
+//!
+//! Fixed-point helpers for swap and fee math.
+//!
+//! Products are computed in 512 bits, so `a * b / denominator` only fails
+//! when the final result does not fit in 256 bits.
+//!
+//! Rounding policy: every swap conversion rounds in favor of the reserves.
+//! - Exact-in swaps round the amount out down.
+//! - Exact-out quotes round the amount in up.
+//! - Fees round up.
+//!
+
+use stylus_sdk::alloy_primitives::{U256, U512};
+
+use crate::BPS;
+
+/// Fixed-point scale of inverse rates, 1e18.
+pub const WAD: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+/// Direction to round a division in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round towards zero.
+    Down,
+    /// Round away from zero.
+    Up,
+}
+
+/// Compute `a * b / denominator` with a 512-bit intermediate product.
+///
+/// Return `None` if `denominator` is 0 or the result does not fit in 256 bits.
+pub fn mul_div(a: U256, b: U256, denominator: U256, rounding: Rounding) -> Option<U256> {
+    if denominator.is_zero() {
+        return None;
+    }
+
+    let product: U512 = a.widening_mul(b);
+    let (quotient, remainder) = product.div_rem(U512::from(denominator));
+
+    // Ensure the quotient fits in 256 bits.
+    let limbs = quotient.as_limbs();
+    if limbs[4..].iter().any(|limb| *limb != 0) {
+        return None;
+    }
+    let quotient = U256::from_limbs_slice(&limbs[..4]);
+
+    match rounding {
+        Rounding::Up if !remainder.is_zero() => quotient.checked_add(U256::from(1)),
+        _ => Some(quotient),
+    }
+}
+
+/// Compute the amount out of an exact-in swap at `rate_numerator / rate_denominator`.
+///
+/// The fee is taken from the amount out. Return `None` on overflow or a fee above 100%.
+pub fn amount_out(
+    amount_in: U256,
+    rate_numerator: U256,
+    rate_denominator: U256,
+    fee_bps: u16,
+) -> Option<U256> {
+    if fee_bps > BPS {
+        return None;
+    }
+
+    let gross_amount_out = mul_div(amount_in, rate_numerator, rate_denominator, Rounding::Down)?;
+    let fee = mul_div(
+        gross_amount_out,
+        U256::from(fee_bps),
+        U256::from(BPS),
+        Rounding::Up,
+    )?;
+
+    Some(gross_amount_out - fee)
+}
+
+/// Compute the protocol's share of the fee on an exact-in swap at `rate_numerator / rate_denominator`.
+///
+/// The share is `protocol_fee_bps` of the amount out before fees, rounded down, so it is never
+/// more than the fee `amount_out` takes at a fee including `protocol_fee_bps`.
+pub fn protocol_fee(
+    amount_in: U256,
+    rate_numerator: U256,
+    rate_denominator: U256,
+    protocol_fee_bps: u16,
+) -> Option<U256> {
+    let gross_amount_out = mul_div(amount_in, rate_numerator, rate_denominator, Rounding::Down)?;
+    mul_div(
+        gross_amount_out,
+        U256::from(protocol_fee_bps),
+        U256::from(BPS),
+        Rounding::Down,
+    )
+}
+
+/// Compute the amount in an exact-out swap needs at `rate_numerator / rate_denominator`.
+///
+/// `amount_out(amount_in(x))` is never below `x`. Return `None` on overflow or a fee of 100% or more.
+pub fn amount_in(
+    amount_out: U256,
+    rate_numerator: U256,
+    rate_denominator: U256,
+    fee_bps: u16,
+) -> Option<U256> {
+    if fee_bps >= BPS {
+        return None;
+    }
+
+    let gross_amount_out = mul_div(
+        amount_out,
+        U256::from(BPS),
+        U256::from(BPS - fee_bps),
+        Rounding::Up,
+    )?;
+
+    mul_div(
+        gross_amount_out,
+        rate_denominator,
+        rate_numerator,
+        Rounding::Up,
+    )
+}
+
+/// Compute the base token one quote token buys at `exchange_rate`, scaled by `WAD`.
+///
+/// Rounded down, as swaps are. Return `None` for a rate of 0.
+pub fn inverse_rate(exchange_rate: U256) -> Option<U256> {
+    mul_div(WAD, U256::from(1), exchange_rate, Rounding::Down)
+}
+
+/// Times the gap above the floor halves over an exponential decay.
+pub const DECAY_HALVINGS: u64 = 8;
+
+/// Compute a rate decaying linearly from `start_rate` to `floor_rate` over `duration` seconds.
+///
+/// Rounds the decayed amount down, so the rate never drops below the line.
+pub fn linear_decay(start_rate: U256, floor_rate: U256, elapsed: u64, duration: u64) -> U256 {
+    if elapsed >= duration {
+        return floor_rate;
+    }
+
+    let gap = start_rate.saturating_sub(floor_rate);
+    let decayed = mul_div(
+        gap,
+        U256::from(elapsed),
+        U256::from(duration),
+        Rounding::Down,
+    )
+    .unwrap_or_default();
+    start_rate - decayed
+}
+
+/// Compute a rate decaying exponentially from `start_rate` to `floor_rate` over `duration`
+/// seconds.
+///
+/// The gap above the floor halves `DECAY_HALVINGS` times, interpolated linearly within each
+/// halving. The gap left after the last halving decays linearly, so the rate reaches the
+/// floor at `duration`.
+pub fn exponential_decay(start_rate: U256, floor_rate: U256, elapsed: u64, duration: u64) -> U256 {
+    if elapsed >= duration {
+        return floor_rate;
+    }
+
+    let gap = start_rate.saturating_sub(floor_rate);
+
+    // Find the current halving and how far into it the decay is, out of `duration`.
+    let position = u128::from(elapsed) * u128::from(DECAY_HALVINGS);
+    let halving = (position / u128::from(duration)) as usize;
+    let progress = position % u128::from(duration);
+
+    // Interpolate between the gaps at the start and end of the halving.
+    let high = gap >> halving;
+    let low = gap >> (halving + 1);
+    let curve = high
+        - mul_div(
+            high - low,
+            U256::from(progress),
+            U256::from(duration),
+            Rounding::Down,
+        )
+        .unwrap_or_default();
+
+    // Decay the gap left after the last halving linearly.
+    let residual = gap >> DECAY_HALVINGS as usize;
+    let correction = mul_div(
+        residual,
+        U256::from(elapsed),
+        U256::from(duration),
+        Rounding::Down,
+    )
+    .unwrap_or_default();
+
+    floor_rate + curve - correction
+}
@@ -0,0 +1,45 @@
+//!
+//! Merkle proofs for allowlists and claims.
+//!
+//! Trees follow OpenZeppelin's `StandardMerkleTree`: leaves are the double
+//! keccak256 of their ABI-encoded values, and pairs are hashed sorted, so
+//! proofs from its JS library verify here unchanged.
+//!
+
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    crypto::keccak,
+};
+
+/// Leaf of an `address` tree, eg. a presale allowlist.
+pub fn address_leaf(account: Address) -> B256 {
+    let mut encoded = [0u8; 32];
+    encoded[12..].copy_from_slice(account.as_slice());
+    keccak(keccak(encoded))
+}
+
+/// Leaf of an `(address, uint256)` tree, eg. an airdrop.
+pub fn amount_leaf(account: Address, amount: U256) -> B256 {
+    let mut encoded = [0u8; 64];
+    encoded[12..32].copy_from_slice(account.as_slice());
+    encoded[32..].copy_from_slice(&amount.to_be_bytes::<32>());
+    keccak(keccak(encoded))
+}
+
+/// Hash a pair of nodes in sorted order.
+pub fn hash_pair(a: B256, b: B256) -> B256 {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut encoded = [0u8; 64];
+    encoded[..32].copy_from_slice(left.as_slice());
+    encoded[32..].copy_from_slice(right.as_slice());
+    keccak(encoded)
+}
+
+/// Check that a proof links a leaf to a root.
+pub fn verify(proof: &[B256], root: B256, leaf: B256) -> bool {
+    let mut node = leaf;
+    for sibling in proof {
+        node = hash_pair(node, *sibling);
+    }
+    node == root
+}
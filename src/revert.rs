@@ -0,0 +1,209 @@
+//!
+//! Revert data decoding for off-chain callers.
+//!
+//! Maps the bytes a failed call returns back to `ContractError`, so clients can
+//! report `RateChanged { expected: 3, actual: 4 }` instead of raw hex. Only
+//! built for the host, the program itself never decodes its own errors.
+//!
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::{Panic, Revert as RevertReason, SolError};
+use core::fmt;
+use stylus_sdk::alloy_primitives::{Address, U256};
+
+use crate::*;
+
+/// Reason a call reverted.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Revert {
+    /// An error declared by the contract.
+    Contract(ContractError),
+    /// A Solidity `Error(string)`, eg. from a token.
+    Reason(String),
+    /// A Solidity `Panic(uint256)`.
+    Panic(U256),
+    /// Revert data that matches none of the above.
+    Unknown(Vec<u8>),
+}
+
+impl Revert {
+    /// Decode revert data.
+    pub fn decode(data: &[u8]) -> Self {
+        if let Some(error) = decode_contract_error(data) {
+            return Self::Contract(error);
+        }
+        if let Ok(reason) = RevertReason::abi_decode(data, true) {
+            return Self::Reason(reason.reason);
+        }
+        if let Ok(panic) = Panic::abi_decode(data, true) {
+            return Self::Panic(panic.code);
+        }
+        Self::Unknown(data.to_vec())
+    }
+
+    /// Decode the revert data a failed token call left in `ExternalCallFailed`.
+    ///
+    /// Return the call target and its revert, `None` for reverts raised by the contract itself.
+    pub fn external_call(&self) -> Option<(Address, Revert)> {
+        match self {
+            Self::Contract(ContractError::ExternalCallFailed(failure)) => {
+                Some((failure.target, Self::decode(&failure.revert_data)))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Revert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Contract(error) => write!(f, "{error:?}"),
+            Self::Reason(reason) => write!(f, "Error({reason:?})"),
+            Self::Panic(code) => write!(f, "Panic({code:#x})"),
+            Self::Unknown(data) => write!(f, "0x{}", hex::encode(data)),
+        }
+    }
+}
+
+/// Decode a declared contract error, matching the variants of `ContractError`.
+fn decode_contract_error(data: &[u8]) -> Option<ContractError> {
+    macro_rules! decode {
+        ($($error:ident),* $(,)?) => {
+            match data.get(..4)? {
+                $(selector if selector == $error::SELECTOR => {
+                    $error::abi_decode(data, true).ok().map(ContractError::$error)
+                })*
+                _ => None,
+            }
+        };
+    }
+
+    decode!(
+        AlreadyInitialized,
+        MarketExists,
+        BaseTokenCanNotBeZeroAddress,
+        QuoteTokenCanNotBeZeroAddress,
+        ExchangeRateCanNotBeZero,
+        AmountCanNotBeZero,
+        IncorrectBaseAmount,
+        IncorrectQuoteAmount,
+        DivisionUnderflow,
+        MultiplicationOverflow,
+        OutOfBoundIndex,
+        NotOwner,
+        NotPendingOwner,
+        OwnerCanNotBeZeroAddress,
+        NotAdmin,
+        AdminCanNotBeZeroAddress,
+        AdminExists,
+        AdminNotFound,
+        InvalidAdminThreshold,
+        AdminActionNotFound,
+        AdminActionAlreadyConfirmed,
+        AdminActionAlreadyExecuted,
+        InsufficientAdminConfirmations,
+        InvalidAdminActionData,
+        MarketNotFound,
+        MarketNotActive,
+        MarketNotPaused,
+        NotMarketCreator,
+        MarketAlreadyClosed,
+        InsufficientLiquidity,
+        BaseTokenTransferFailed,
+        QuoteTokenTransferFailed,
+        RateChanged,
+        CommitRevealRequired,
+        SwapCommitmentExists,
+        SwapCommitmentNotFound,
+        RevealTooEarly,
+        InvalidSwapDirection,
+        DepositRefundFailed,
+        FeeTooHigh,
+        FeatureDisabled,
+        InsufficientTreasuryBalance,
+        RecipientCanNotBeZeroAddress,
+        BuybackTokenNotInMarket,
+        InsufficientAmountOut,
+        TokenTransferFailed,
+        NotGuardian,
+        TokenNotInMarket,
+        InsufficientInsuranceBalance,
+        MarketNotFlagged,
+        CreatorNotAllowed,
+        InvalidCreationMode,
+        IncorrectListingFee,
+        RateOutOfBounds,
+        InvalidRateBounds,
+        EffectiveTimeNotInFuture,
+        InvalidDecayMode,
+        InvalidRateDecay,
+        SplitLegPairMismatch,
+        InvalidBasket,
+        QuoteTokenNotAccepted,
+        BasketSoldCapReached,
+        NotBasketCreator,
+        VaultCallFailed,
+        MarketHasReserves,
+        InvalidSwapData,
+        SwapCallbackFailed,
+        HookNotApproved,
+        HookRejected,
+        NotFeeCollector,
+        NotCompliant,
+        ContractCallersNotAllowed,
+        ExternalCallFailed,
+        InvariantViolated,
+        NotRateOperator,
+        NotL1Governor,
+        L1MessageNotAllowed,
+        WithdrawalDelayed,
+        NoPendingWithdrawal,
+        WithdrawalNotReady,
+        LiquidityLocked,
+        InvalidVoucher,
+        VoucherExpired,
+        VoucherUsed,
+        VoucherAmountExceeded,
+        PurchaseCapExceeded,
+        InvalidMerkleProof,
+        PresaleAccessDenied,
+        SaleNotOpen,
+        SaleStillOpen,
+        SaleNotFailed,
+        InvalidSaleCaps,
+        HardCapExceeded,
+        SaleNotSucceeded,
+        AirdropInProgress,
+        AirdropClaimsClosed,
+        AirdropClaimsOpen,
+        AirdropAlreadyClaimed,
+        InvalidTriggerCondition,
+        TriggerAlreadyReached,
+        TriggerNotReached,
+        OrderNotOpen,
+        NotOrderMaker,
+        OrderMarketMismatch,
+        InvalidKeeperTipMode,
+        InvalidKeeperTipBounds,
+        RecipientNotAllowed,
+        InvalidDepositTolerance,
+        DepositRatioMismatch,
+        NotPendingCreator,
+        CreatorCanNotBeZeroAddress,
+        FeeTierNotFound,
+        MarketFeeTiered,
+        SwapTooOld,
+        RateChangeTooLarge,
+        ContractSunset,
+        InvalidSolvencyTolerance,
+        SameMarket,
+        InvalidDirectionMode,
+        DirectionDisabled,
+        CommitmentNotExpired,
+        AfterSwapHookRejected,
+        RebasingMarketNotSupported,
+        InvalidOrderExpiry,
+        OrderExpired,
+        InvalidFillAmount,
+    )
+}
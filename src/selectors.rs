@@ -0,0 +1,797 @@
+//!
+//! Function selectors of every public method.
+//!
+//! Routers, proxies and access-control lists can reference these instead of
+//! hard-coding 4-byte values. `fetch_selectors()` returns `ALL` on-chain.
+//!
+
+use alloc::vec::Vec;
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, B256, U256},
+    function_selector,
+};
+
+/// `initialize()`.
+pub const INITIALIZE: [u8; 4] = function_selector!("initialize");
+/// `initialize(address,address,uint16,uint8)`.
+pub const INITIALIZE_WITH_CONFIG: [u8; 4] =
+    function_selector!("initialize", Address, Address, u16, u8);
+/// `createMarket(address,address,uint256)`.
+pub const CREATE_MARKET: [u8; 4] = function_selector!("createMarket", Address, Address, U256);
+/// `createMarketWithFeeTier(address,address,uint256,uint64)`.
+pub const CREATE_MARKET_WITH_FEE_TIER: [u8; 4] =
+    function_selector!("createMarketWithFeeTier", Address, Address, U256, u64);
+/// `createMarketWithSalt(address,address,uint256,bytes32)`.
+pub const CREATE_MARKET_WITH_SALT: [u8; 4] =
+    function_selector!("createMarketWithSalt", Address, Address, U256, B256);
+/// `createAndFund(address,address,uint256,uint256,uint256)`.
+pub const CREATE_AND_FUND: [u8; 4] =
+    function_selector!("createAndFund", Address, Address, U256, U256, U256);
+/// `previewCreateMarket(address,address,uint256,uint256,uint256)`.
+pub const PREVIEW_CREATE_MARKET: [u8; 4] =
+    function_selector!("previewCreateMarket", Address, Address, U256, U256, U256);
+/// `depositLiquidity(uint256,uint256,uint256)`.
+pub const DEPOSIT_LIQUIDITY: [u8; 4] = function_selector!("depositLiquidity", U256, U256, U256);
+/// `withdrawLiquidity(uint256,uint256,uint256)`.
+pub const WITHDRAW_LIQUIDITY: [u8; 4] = function_selector!("withdrawLiquidity", U256, U256, U256);
+/// `withdrawLiquidity(uint256,uint256,uint256,address)`.
+pub const WITHDRAW_LIQUIDITY_TO: [u8; 4] =
+    function_selector!("withdrawLiquidity", U256, U256, U256, Address);
+/// `transferReserves(uint256,uint256,address,uint256)`.
+pub const TRANSFER_RESERVES: [u8; 4] =
+    function_selector!("transferReserves", U256, U256, Address, U256);
+/// `setWithdrawalDelay(uint256,uint64,bool)`.
+pub const SET_WITHDRAWAL_DELAY: [u8; 4] = function_selector!("setWithdrawalDelay", U256, u64, bool);
+/// `fetchWithdrawalDelay(uint256)`.
+pub const FETCH_WITHDRAWAL_DELAY: [u8; 4] = function_selector!("fetchWithdrawalDelay", U256);
+/// `requestWithdrawal(uint256,uint256,uint256)`.
+pub const REQUEST_WITHDRAWAL: [u8; 4] = function_selector!("requestWithdrawal", U256, U256, U256);
+/// `executeWithdrawal(uint256)`.
+pub const EXECUTE_WITHDRAWAL: [u8; 4] = function_selector!("executeWithdrawal", U256);
+/// `executeWithdrawal(uint256,address)`.
+pub const EXECUTE_WITHDRAWAL_TO: [u8; 4] = function_selector!("executeWithdrawal", U256, Address);
+/// `cancelWithdrawal(uint256)`.
+pub const CANCEL_WITHDRAWAL: [u8; 4] = function_selector!("cancelWithdrawal", U256);
+/// `fetchPendingWithdrawal(uint256)`.
+pub const FETCH_PENDING_WITHDRAWAL: [u8; 4] = function_selector!("fetchPendingWithdrawal", U256);
+/// `setMinLockedLiquidity(uint256)`.
+pub const SET_MIN_LOCKED_LIQUIDITY: [u8; 4] = function_selector!("setMinLockedLiquidity", U256);
+/// `fetchMinLockedLiquidity()`.
+pub const FETCH_MIN_LOCKED_LIQUIDITY: [u8; 4] = function_selector!("fetchMinLockedLiquidity");
+/// `lockLiquidity(uint256,uint256)`.
+pub const LOCK_LIQUIDITY: [u8; 4] = function_selector!("lockLiquidity", U256, U256);
+/// `fetchLockedLiquidity(uint256)`.
+pub const FETCH_LOCKED_LIQUIDITY: [u8; 4] = function_selector!("fetchLockedLiquidity", U256);
+/// `swapBaseTokenForQuoteToken(address,address,uint256)`.
+pub const SWAP_BASE_TOKEN_FOR_QUOTE_TOKEN: [u8; 4] =
+    function_selector!("swapBaseTokenForQuoteToken", Address, Address, U256);
+/// `swapBaseTokenForQuoteToken(address,address,uint256,uint256)`.
+pub const SWAP_BASE_TOKEN_FOR_QUOTE_TOKEN_AT_RATE: [u8; 4] =
+    function_selector!("swapBaseTokenForQuoteToken", Address, Address, U256, U256);
+/// `swapQuoteTokenForBaseToken(address,address,uint256)`.
+pub const SWAP_QUOTE_TOKEN_FOR_BASE_TOKEN: [u8; 4] =
+    function_selector!("swapQuoteTokenForBaseToken", Address, Address, U256);
+/// `swapQuoteTokenForBaseToken(address,address,uint256,uint256)`.
+pub const SWAP_QUOTE_TOKEN_FOR_BASE_TOKEN_AT_RATE: [u8; 4] =
+    function_selector!("swapQuoteTokenForBaseToken", Address, Address, U256, U256);
+/// `swapBaseTokenForQuoteTokenSubmittedAt(address,address,uint256,uint64)`.
+pub const SWAP_BASE_TOKEN_FOR_QUOTE_TOKEN_SUBMITTED_AT: [u8; 4] = function_selector!(
+    "swapBaseTokenForQuoteTokenSubmittedAt",
+    Address,
+    Address,
+    U256,
+    u64
+);
+/// `swapQuoteTokenForBaseTokenSubmittedAt(address,address,uint256,uint64)`.
+pub const SWAP_QUOTE_TOKEN_FOR_BASE_TOKEN_SUBMITTED_AT: [u8; 4] = function_selector!(
+    "swapQuoteTokenForBaseTokenSubmittedAt",
+    Address,
+    Address,
+    U256,
+    u64
+);
+/// `setMaxSwapAge(uint64)`.
+pub const SET_MAX_SWAP_AGE: [u8; 4] = function_selector!("setMaxSwapAge", u64);
+/// `fetchMaxSwapAge()`.
+pub const FETCH_MAX_SWAP_AGE: [u8; 4] = function_selector!("fetchMaxSwapAge");
+/// `swapBest(address,address,uint8,uint256,uint256)`.
+pub const SWAP_BEST: [u8; 4] = function_selector!("swapBest", Address, Address, u8, U256, U256);
+/// `swapSplit(uint8,(uint256,uint256)[],uint256)`.
+pub const SWAP_SPLIT: [u8; 4] = function_selector!("swapSplit", u8, Vec<(U256, U256)>, U256);
+/// `swapWithVoucher((uint256,address,uint256,uint16,uint256,uint256,uint64),uint8,uint256,uint256,bytes)`.
+pub const SWAP_WITH_VOUCHER: [u8; 4] = function_selector!(
+    "swapWithVoucher",
+    (U256, Address, U256, u16, U256, U256, u64),
+    u8,
+    U256,
+    U256,
+    Bytes
+);
+/// `fetchVoucherUsed(address,uint256)`.
+pub const FETCH_VOUCHER_USED: [u8; 4] = function_selector!("fetchVoucherUsed", Address, U256);
+/// `swapViaUnderlying(uint256,uint8,uint256,uint256)`.
+pub const SWAP_VIA_UNDERLYING: [u8; 4] =
+    function_selector!("swapViaUnderlying", U256, u8, U256, U256);
+/// `swapAndCall(uint256,uint8,uint256,uint256,address,bytes)`.
+pub const SWAP_AND_CALL: [u8; 4] =
+    function_selector!("swapAndCall", U256, u8, U256, U256, Address, Bytes);
+/// `onTransferReceived(address,address,uint256,bytes)`.
+pub const ON_TRANSFER_RECEIVED: [u8; 4] =
+    function_selector!("onTransferReceived", Address, Address, U256, Bytes);
+/// `commitSwap(bytes32)`.
+pub const COMMIT_SWAP: [u8; 4] = function_selector!("commitSwap", B256);
+/// `revealAndExecute(uint256,uint8,uint256,bytes32)`.
+pub const REVEAL_AND_EXECUTE: [u8; 4] =
+    function_selector!("revealAndExecute", U256, u8, U256, B256);
+/// `cancelSwapCommitment(bytes32)`.
+pub const CANCEL_SWAP_COMMITMENT: [u8; 4] = function_selector!("cancelSwapCommitment", B256);
+/// `computeSwapCommitment(address,uint256,uint8,uint256,bytes32)`.
+pub const COMPUTE_SWAP_COMMITMENT: [u8; 4] =
+    function_selector!("computeSwapCommitment", Address, U256, u8, U256, B256);
+/// `fetchSwapCommitment(bytes32)`.
+pub const FETCH_SWAP_COMMITMENT: [u8; 4] = function_selector!("fetchSwapCommitment", B256);
+/// `placeConditionalOrder(uint256,uint8,uint8,uint256,uint256,uint256,uint64,uint256)`.
+pub const PLACE_CONDITIONAL_ORDER: [u8; 4] = function_selector!(
+    "placeConditionalOrder",
+    U256,
+    u8,
+    u8,
+    U256,
+    U256,
+    U256,
+    u64,
+    U256
+);
+/// `cancelConditionalOrder(uint256)`.
+pub const CANCEL_CONDITIONAL_ORDER: [u8; 4] = function_selector!("cancelConditionalOrder", U256);
+/// `fillConditionalOrder(uint256)`.
+pub const FILL_CONDITIONAL_ORDER: [u8; 4] = function_selector!("fillConditionalOrder", U256);
+/// `fillConditionalOrderPartial(uint256,uint256)`.
+pub const FILL_CONDITIONAL_ORDER_PARTIAL: [u8; 4] =
+    function_selector!("fillConditionalOrderPartial", U256, U256);
+/// `settleOrders(uint256[])`.
+pub const SETTLE_ORDERS: [u8; 4] = function_selector!("settleOrders", Vec<U256>);
+/// `fetchConditionalOrder(uint256)`.
+pub const FETCH_CONDITIONAL_ORDER: [u8; 4] = function_selector!("fetchConditionalOrder", U256);
+/// `fetchConditionalOrderTriggered(uint256)`.
+pub const FETCH_CONDITIONAL_ORDER_TRIGGERED: [u8; 4] =
+    function_selector!("fetchConditionalOrderTriggered", U256);
+/// `fetchConditionalOrderCount()`.
+pub const FETCH_CONDITIONAL_ORDER_COUNT: [u8; 4] = function_selector!("fetchConditionalOrderCount");
+/// `fetchOpenOrders(uint256,uint64,uint64)`.
+pub const FETCH_OPEN_ORDERS: [u8; 4] = function_selector!("fetchOpenOrders", U256, u64, u64);
+/// `cancelExpiredOrders(uint256[])`.
+pub const CANCEL_EXPIRED_ORDERS: [u8; 4] = function_selector!("cancelExpiredOrders", Vec<U256>);
+/// `setKeeperTipBounds(uint16,uint16)`.
+pub const SET_KEEPER_TIP_BOUNDS: [u8; 4] = function_selector!("setKeeperTipBounds", u16, u16);
+/// `fetchKeeperTipBounds()`.
+pub const FETCH_KEEPER_TIP_BOUNDS: [u8; 4] = function_selector!("fetchKeeperTipBounds");
+/// `setMarketKeeperTip(uint256,uint8,uint256)`.
+pub const SET_MARKET_KEEPER_TIP: [u8; 4] = function_selector!("setMarketKeeperTip", U256, u8, U256);
+/// `fetchMarketKeeperTip(uint256)`.
+pub const FETCH_MARKET_KEEPER_TIP: [u8; 4] = function_selector!("fetchMarketKeeperTip", U256);
+/// `fetchConditionalOrderTip(uint256)`.
+pub const FETCH_CONDITIONAL_ORDER_TIP: [u8; 4] =
+    function_selector!("fetchConditionalOrderTip", U256);
+/// `quoteExactIn(uint256,uint8,uint256)`.
+pub const QUOTE_EXACT_IN: [u8; 4] = function_selector!("quoteExactIn", U256, u8, U256);
+/// `quoteBatch((uint256,uint8,uint256)[])`.
+pub const QUOTE_BATCH: [u8; 4] = function_selector!("quoteBatch", Vec<(U256, u8, U256)>);
+/// `quoteExactOut(uint256,uint8,uint256)`.
+pub const QUOTE_EXACT_OUT: [u8; 4] = function_selector!("quoteExactOut", U256, u8, U256);
+/// `checkSwapPreconditions(address,uint256,uint8,uint256)`.
+pub const CHECK_SWAP_PRECONDITIONS: [u8; 4] =
+    function_selector!("checkSwapPreconditions", Address, U256, u8, U256);
+/// `quoteBothDirections(uint256,uint256)`.
+pub const QUOTE_BOTH_DIRECTIONS: [u8; 4] = function_selector!("quoteBothDirections", U256, U256);
+/// `fetchInitializationStatus()`.
+pub const FETCH_INITIALIZATION_STATUS: [u8; 4] = function_selector!("fetchInitializationStatus");
+/// `initializedVersion()`.
+pub const INITIALIZED_VERSION: [u8; 4] = function_selector!("initializedVersion");
+/// `name()`.
+pub const NAME: [u8; 4] = function_selector!("name");
+/// `version()`.
+pub const VERSION: [u8; 4] = function_selector!("version");
+/// `implementationInfo()`.
+pub const IMPLEMENTATION_INFO: [u8; 4] = function_selector!("implementationInfo");
+/// `fetchConfig()`.
+pub const FETCH_CONFIG: [u8; 4] = function_selector!("fetchConfig");
+/// `DOMAIN_SEPARATOR()`.
+pub const DOMAIN_SEPARATOR: [u8; 4] = function_selector!("DOMAIN_SEPARATOR");
+/// `nonces(address)`.
+pub const NONCES: [u8; 4] = function_selector!("nonces", Address);
+/// `fetchCurrentMarketIndex()`.
+pub const FETCH_CURRENT_MARKET_INDEX: [u8; 4] = function_selector!("fetchCurrentMarketIndex");
+/// `fetchExchangeRate(address,address)`.
+pub const FETCH_EXCHANGE_RATE: [u8; 4] = function_selector!("fetchExchangeRate", Address, Address);
+/// `fetchMarketId(address,address)`.
+pub const FETCH_MARKET_ID: [u8; 4] = function_selector!("fetchMarketId", Address, Address);
+/// `fetchMarketByTokens(address,address)`.
+pub const FETCH_MARKET_BY_TOKENS: [u8; 4] =
+    function_selector!("fetchMarketByTokens", Address, Address);
+/// `fetchMarketById(uint256)`.
+pub const FETCH_MARKET_BY_ID: [u8; 4] = function_selector!("fetchMarketById", U256);
+/// `fetchMarketsEnriched(uint64,uint64)`.
+pub const FETCH_MARKETS_ENRICHED: [u8; 4] = function_selector!("fetchMarketsEnriched", u64, u64);
+/// `fetchMarketStatus(uint256)`.
+pub const FETCH_MARKET_STATUS: [u8; 4] = function_selector!("fetchMarketStatus", U256);
+/// `fetchMarketHealth(uint256)`.
+pub const FETCH_MARKET_HEALTH: [u8; 4] = function_selector!("fetchMarketHealth", U256);
+/// `fetchHealth()`.
+pub const FETCH_HEALTH: [u8; 4] = function_selector!("fetchHealth");
+/// `pauseMarket(uint256)`.
+pub const PAUSE_MARKET: [u8; 4] = function_selector!("pauseMarket", U256);
+/// `unpauseMarket(uint256)`.
+pub const UNPAUSE_MARKET: [u8; 4] = function_selector!("unpauseMarket", U256);
+/// `flagMarket(uint256,bytes32)`.
+pub const FLAG_MARKET: [u8; 4] = function_selector!("flagMarket", U256, B256);
+/// `resolveFlag(uint256,bool)`.
+pub const RESOLVE_FLAG: [u8; 4] = function_selector!("resolveFlag", U256, bool);
+/// `fetchMarketFlag(uint256)`.
+pub const FETCH_MARKET_FLAG: [u8; 4] = function_selector!("fetchMarketFlag", U256);
+/// `setMarketExpiry(uint256,uint64)`.
+pub const SET_MARKET_EXPIRY: [u8; 4] = function_selector!("setMarketExpiry", U256, u64);
+/// `setMarketFee(uint256,uint16)`.
+pub const SET_MARKET_FEE: [u8; 4] = function_selector!("setMarketFee", U256, u16);
+/// `fetchMarketFee(uint256)`.
+pub const FETCH_MARKET_FEE: [u8; 4] = function_selector!("fetchMarketFee", U256);
+/// `setFeeTier(uint64,uint16)`.
+pub const SET_FEE_TIER: [u8; 4] = function_selector!("setFeeTier", u64, u16);
+/// `fetchFeeTier(uint64)`.
+pub const FETCH_FEE_TIER: [u8; 4] = function_selector!("fetchFeeTier", u64);
+/// `setMarketFeeTier(uint256,uint64)`.
+pub const SET_MARKET_FEE_TIER: [u8; 4] = function_selector!("setMarketFeeTier", U256, u64);
+/// `fetchMarketFeeTier(uint256)`.
+pub const FETCH_MARKET_FEE_TIER: [u8; 4] = function_selector!("fetchMarketFeeTier", U256);
+/// `setMarketMakerFee(uint256,uint16)`.
+pub const SET_MARKET_MAKER_FEE: [u8; 4] = function_selector!("setMarketMakerFee", U256, u16);
+/// `setMarketDepositTolerance(uint256,bool,uint16)`.
+pub const SET_MARKET_DEPOSIT_TOLERANCE: [u8; 4] =
+    function_selector!("setMarketDepositTolerance", U256, bool, u16);
+/// `fetchMarketDepositTolerance(uint256)`.
+pub const FETCH_MARKET_DEPOSIT_TOLERANCE: [u8; 4] =
+    function_selector!("fetchMarketDepositTolerance", U256);
+/// `fetchMarketFeeSchedule(uint256)`.
+pub const FETCH_MARKET_FEE_SCHEDULE: [u8; 4] = function_selector!("fetchMarketFeeSchedule", U256);
+/// `setHookApproval(address,bool)`.
+pub const SET_HOOK_APPROVAL: [u8; 4] = function_selector!("setHookApproval", Address, bool);
+/// `fetchIsApprovedHook(address)`.
+pub const FETCH_IS_APPROVED_HOOK: [u8; 4] = function_selector!("fetchIsApprovedHook", Address);
+/// `setHookGasLimit(uint64)`.
+pub const SET_HOOK_GAS_LIMIT: [u8; 4] = function_selector!("setHookGasLimit", u64);
+/// `fetchHookGasLimit()`.
+pub const FETCH_HOOK_GAS_LIMIT: [u8; 4] = function_selector!("fetchHookGasLimit");
+/// `setMarketHook(uint256,address)`.
+pub const SET_MARKET_HOOK: [u8; 4] = function_selector!("setMarketHook", U256, Address);
+/// `fetchMarketHook(uint256)`.
+pub const FETCH_MARKET_HOOK: [u8; 4] = function_selector!("fetchMarketHook", U256);
+/// `setMarketComplianceRegistry(uint256,address)`.
+pub const SET_MARKET_COMPLIANCE_REGISTRY: [u8; 4] =
+    function_selector!("setMarketComplianceRegistry", U256, Address);
+/// `fetchMarketComplianceRegistry(uint256)`.
+pub const FETCH_MARKET_COMPLIANCE_REGISTRY: [u8; 4] =
+    function_selector!("fetchMarketComplianceRegistry", U256);
+/// `setMarketEoaOnly(uint256,bool)`.
+pub const SET_MARKET_EOA_ONLY: [u8; 4] = function_selector!("setMarketEoaOnly", U256, bool);
+/// `fetchMarketEoaOnly(uint256)`.
+pub const FETCH_MARKET_EOA_ONLY: [u8; 4] = function_selector!("fetchMarketEoaOnly", U256);
+/// `setMarketDirectionMode(uint256,uint8)`.
+pub const SET_MARKET_DIRECTION_MODE: [u8; 4] =
+    function_selector!("setMarketDirectionMode", U256, u8);
+/// `fetchMarketDirectionMode(uint256)`.
+pub const FETCH_MARKET_DIRECTION_MODE: [u8; 4] =
+    function_selector!("fetchMarketDirectionMode", U256);
+/// `setMarketContractAllowed(uint256,address,bool)`.
+pub const SET_MARKET_CONTRACT_ALLOWED: [u8; 4] =
+    function_selector!("setMarketContractAllowed", U256, Address, bool);
+/// `fetchMarketContractAllowed(uint256,address)`.
+pub const FETCH_MARKET_CONTRACT_ALLOWED: [u8; 4] =
+    function_selector!("fetchMarketContractAllowed", U256, Address);
+/// `setMarketRecipientRestricted(uint256,bool)`.
+pub const SET_MARKET_RECIPIENT_RESTRICTED: [u8; 4] =
+    function_selector!("setMarketRecipientRestricted", U256, bool);
+/// `fetchMarketRecipientRestricted(uint256)`.
+pub const FETCH_MARKET_RECIPIENT_RESTRICTED: [u8; 4] =
+    function_selector!("fetchMarketRecipientRestricted", U256);
+/// `setMarketCustodianAllowed(uint256,address,bool)`.
+pub const SET_MARKET_CUSTODIAN_ALLOWED: [u8; 4] =
+    function_selector!("setMarketCustodianAllowed", U256, Address, bool);
+/// `fetchMarketCustodianAllowed(uint256,address)`.
+pub const FETCH_MARKET_CUSTODIAN_ALLOWED: [u8; 4] =
+    function_selector!("fetchMarketCustodianAllowed", U256, Address);
+/// `setMarketRateBounds(uint256,uint256,uint256)`.
+pub const SET_MARKET_RATE_BOUNDS: [u8; 4] =
+    function_selector!("setMarketRateBounds", U256, U256, U256);
+/// `fetchMarketRateBounds(uint256)`.
+pub const FETCH_MARKET_RATE_BOUNDS: [u8; 4] = function_selector!("fetchMarketRateBounds", U256);
+/// `setMarketRateLimit(uint256,uint16,uint16,uint64)`.
+pub const SET_MARKET_RATE_LIMIT: [u8; 4] =
+    function_selector!("setMarketRateLimit", U256, u16, u16, u64);
+/// `fetchMarketRateLimit(uint256)`.
+pub const FETCH_MARKET_RATE_LIMIT: [u8; 4] = function_selector!("fetchMarketRateLimit", U256);
+/// `grantRateOperator(uint256,address)`.
+pub const GRANT_RATE_OPERATOR: [u8; 4] = function_selector!("grantRateOperator", U256, Address);
+/// `revokeRateOperator(uint256)`.
+pub const REVOKE_RATE_OPERATOR: [u8; 4] = function_selector!("revokeRateOperator", U256);
+/// `fetchRateOperator(uint256)`.
+pub const FETCH_RATE_OPERATOR: [u8; 4] = function_selector!("fetchRateOperator", U256);
+/// `transferMarketCreator(uint256,address)`.
+pub const TRANSFER_MARKET_CREATOR: [u8; 4] =
+    function_selector!("transferMarketCreator", U256, Address);
+/// `acceptMarketCreator(uint256)`.
+pub const ACCEPT_MARKET_CREATOR: [u8; 4] = function_selector!("acceptMarketCreator", U256);
+/// `fetchPendingMarketCreator(uint256)`.
+pub const FETCH_PENDING_MARKET_CREATOR: [u8; 4] =
+    function_selector!("fetchPendingMarketCreator", U256);
+/// `setPresale(uint256,uint64,uint256,uint256,bytes32)`.
+pub const SET_PRESALE: [u8; 4] = function_selector!("setPresale", U256, u64, U256, U256, B256);
+/// `setPresaleAllowlist(uint256,address[],bool)`.
+pub const SET_PRESALE_ALLOWLIST: [u8; 4] =
+    function_selector!("setPresaleAllowlist", U256, Vec<Address>, bool);
+/// `provePresaleAccess(uint256,bytes32[])`.
+pub const PROVE_PRESALE_ACCESS: [u8; 4] = function_selector!("provePresaleAccess", U256, Vec<B256>);
+/// `fetchPresaleAllowed(uint256,address)`.
+pub const FETCH_PRESALE_ALLOWED: [u8; 4] = function_selector!("fetchPresaleAllowed", U256, Address);
+/// `fetchPhase(uint256)`.
+pub const FETCH_PHASE: [u8; 4] = function_selector!("fetchPhase", U256);
+/// `setSale(uint256,uint256,uint256,uint64)`.
+pub const SET_SALE: [u8; 4] = function_selector!("setSale", U256, U256, U256, u64);
+/// `finalizeSale(uint256)`.
+pub const FINALIZE_SALE: [u8; 4] = function_selector!("finalizeSale", U256);
+/// `claimRefund(uint256)`.
+pub const CLAIM_REFUND: [u8; 4] = function_selector!("claimRefund", U256);
+/// `fetchSale(uint256)`.
+pub const FETCH_SALE: [u8; 4] = function_selector!("fetchSale", U256);
+/// `fetchContribution(uint256,address)`.
+pub const FETCH_CONTRIBUTION: [u8; 4] = function_selector!("fetchContribution", U256, Address);
+/// `setAirdropRoot(uint256,bytes32,uint256,uint64)`.
+pub const SET_AIRDROP_ROOT: [u8; 4] = function_selector!("setAirdropRoot", U256, B256, U256, u64);
+/// `claimAirdrop(uint256,uint256,bytes32[])`.
+pub const CLAIM_AIRDROP: [u8; 4] = function_selector!("claimAirdrop", U256, U256, Vec<B256>);
+/// `sweepAirdrop(uint256)`.
+pub const SWEEP_AIRDROP: [u8; 4] = function_selector!("sweepAirdrop", U256);
+/// `fetchAirdrop(uint256)`.
+pub const FETCH_AIRDROP: [u8; 4] = function_selector!("fetchAirdrop", U256);
+/// `fetchAirdropClaimed(uint256,address)`.
+pub const FETCH_AIRDROP_CLAIMED: [u8; 4] = function_selector!("fetchAirdropClaimed", U256, Address);
+/// `scheduleRateUpdate(uint256,uint256,uint64)`.
+pub const SCHEDULE_RATE_UPDATE: [u8; 4] = function_selector!("scheduleRateUpdate", U256, U256, u64);
+/// `fetchPendingRate(uint256)`.
+pub const FETCH_PENDING_RATE: [u8; 4] = function_selector!("fetchPendingRate", U256);
+/// `setRateDecay(uint256,uint8,uint256,uint256,uint64,uint64)`.
+pub const SET_RATE_DECAY: [u8; 4] =
+    function_selector!("setRateDecay", U256, u8, U256, U256, u64, u64);
+/// `fetchRateDecay(uint256)`.
+pub const FETCH_RATE_DECAY: [u8; 4] = function_selector!("fetchRateDecay", U256);
+/// `fetchCurrentRate(uint256)`.
+pub const FETCH_CURRENT_RATE: [u8; 4] = function_selector!("fetchCurrentRate", U256);
+/// `fetchMarketDecimals(uint256)`.
+pub const FETCH_MARKET_DECIMALS: [u8; 4] = function_selector!("fetchMarketDecimals", U256);
+/// `refreshTokenMetadata(uint256)`.
+pub const REFRESH_TOKEN_METADATA: [u8; 4] = function_selector!("refreshTokenMetadata", U256);
+/// `fetchMarketDecimalsCachedAt(uint256)`.
+pub const FETCH_MARKET_DECIMALS_CACHED_AT: [u8; 4] =
+    function_selector!("fetchMarketDecimalsCachedAt", U256);
+/// `setMarketCommitReveal(uint256,bool)`.
+pub const SET_MARKET_COMMIT_REVEAL: [u8; 4] =
+    function_selector!("setMarketCommitReveal", U256, bool);
+/// `fetchMarketCommitReveal(uint256)`.
+pub const FETCH_MARKET_COMMIT_REVEAL: [u8; 4] = function_selector!("fetchMarketCommitReveal", U256);
+/// `setMarketVaults(uint256,bool,bool)`.
+pub const SET_MARKET_VAULTS: [u8; 4] = function_selector!("setMarketVaults", U256, bool, bool);
+/// `fetchMarketVaults(uint256)`.
+pub const FETCH_MARKET_VAULTS: [u8; 4] = function_selector!("fetchMarketVaults", U256);
+/// `setMarketRebasing(uint256,bool)`.
+pub const SET_MARKET_REBASING: [u8; 4] = function_selector!("setMarketRebasing", U256, bool);
+/// `fetchMarketRebasing(uint256)`.
+pub const FETCH_MARKET_REBASING: [u8; 4] = function_selector!("fetchMarketRebasing", U256);
+/// `syncMarketReserves(uint256)`.
+pub const SYNC_MARKET_RESERVES: [u8; 4] = function_selector!("syncMarketReserves", U256);
+/// `fetchPairHistory(address,address)`.
+pub const FETCH_PAIR_HISTORY: [u8; 4] = function_selector!("fetchPairHistory", Address, Address);
+/// `fetchBestMarket(address,address,uint8)`.
+pub const FETCH_BEST_MARKET: [u8; 4] = function_selector!("fetchBestMarket", Address, Address, u8);
+/// `fetchMarketReserves(uint256)`.
+pub const FETCH_MARKET_RESERVES: [u8; 4] = function_selector!("fetchMarketReserves", U256);
+/// `fetchTvl(address)`.
+pub const FETCH_TVL: [u8; 4] = function_selector!("fetchTvl", Address);
+/// `fetchTvlAll(uint64,uint64)`.
+pub const FETCH_TVL_ALL: [u8; 4] = function_selector!("fetchTvlAll", u64, u64);
+/// `fetchAccounting(uint256)`.
+pub const FETCH_ACCOUNTING: [u8; 4] = function_selector!("fetchAccounting", U256);
+/// `verifySolvency(uint256)`.
+pub const VERIFY_SOLVENCY: [u8; 4] = function_selector!("verifySolvency", U256);
+/// `setSolvencyConfig(uint16,uint256)`.
+pub const SET_SOLVENCY_CONFIG: [u8; 4] = function_selector!("setSolvencyConfig", u16, U256);
+/// `fetchSolvencyConfig()`.
+pub const FETCH_SOLVENCY_CONFIG: [u8; 4] = function_selector!("fetchSolvencyConfig");
+/// `fetchUserStats(address,uint256)`.
+pub const FETCH_USER_STATS: [u8; 4] = function_selector!("fetchUserStats", Address, U256);
+/// `setPurchaseCap(uint256,uint256)`.
+pub const SET_PURCHASE_CAP: [u8; 4] = function_selector!("setPurchaseCap", U256, U256);
+/// `fetchPurchaseCap(uint256)`.
+pub const FETCH_PURCHASE_CAP: [u8; 4] = function_selector!("fetchPurchaseCap", U256);
+/// `fetchPurchased(uint256,address)`.
+pub const FETCH_PURCHASED: [u8; 4] = function_selector!("fetchPurchased", U256, Address);
+/// `fetchRemainingAllocation(uint256,address)`.
+pub const FETCH_REMAINING_ALLOCATION: [u8; 4] =
+    function_selector!("fetchRemainingAllocation", U256, Address);
+/// `closeMarket(uint256)`.
+pub const CLOSE_MARKET: [u8; 4] = function_selector!("closeMarket", U256);
+/// `createBasket(address,address[],uint256[],uint256)`.
+pub const CREATE_BASKET: [u8; 4] =
+    function_selector!("createBasket", Address, Vec<Address>, Vec<U256>, U256);
+/// `depositBasket(uint256,uint256)`.
+pub const DEPOSIT_BASKET: [u8; 4] = function_selector!("depositBasket", U256, U256);
+/// `withdrawBasket(uint256,address,uint256)`.
+pub const WITHDRAW_BASKET: [u8; 4] = function_selector!("withdrawBasket", U256, Address, U256);
+/// `buyFromBasket(uint256,address,uint256,uint256)`.
+pub const BUY_FROM_BASKET: [u8; 4] = function_selector!("buyFromBasket", U256, Address, U256, U256);
+/// `fetchBasket(uint256)`.
+pub const FETCH_BASKET: [u8; 4] = function_selector!("fetchBasket", U256);
+/// `fetchBasketQuote(uint256,address)`.
+pub const FETCH_BASKET_QUOTE: [u8; 4] = function_selector!("fetchBasketQuote", U256, Address);
+/// `fetchMarketByMarketId(uint256)`.
+pub const FETCH_MARKET_BY_MARKET_ID: [u8; 4] = function_selector!("fetchMarketByMarketId", U256);
+/// `computeMarketId(address,address,bytes32)`.
+pub const COMPUTE_MARKET_ID: [u8; 4] =
+    function_selector!("computeMarketId", Address, Address, B256);
+/// `computeSaltedMarketId(address,bytes32)`.
+pub const COMPUTE_SALTED_MARKET_ID: [u8; 4] =
+    function_selector!("computeSaltedMarketId", Address, B256);
+/// `fetchMarketBySalt(address,bytes32)`.
+pub const FETCH_MARKET_BY_SALT: [u8; 4] = function_selector!("fetchMarketBySalt", Address, B256);
+/// `fetchDeterministicMarketIds()`.
+pub const FETCH_DETERMINISTIC_MARKET_IDS: [u8; 4] =
+    function_selector!("fetchDeterministicMarketIds");
+/// `setDeterministicMarketIds(bool,bytes32)`.
+pub const SET_DETERMINISTIC_MARKET_IDS: [u8; 4] =
+    function_selector!("setDeterministicMarketIds", bool, B256);
+/// `fetchCreationMode()`.
+pub const FETCH_CREATION_MODE: [u8; 4] = function_selector!("fetchCreationMode");
+/// `setCreationMode(uint8)`.
+pub const SET_CREATION_MODE: [u8; 4] = function_selector!("setCreationMode", u8);
+/// `fetchIsAllowedCreator(address)`.
+pub const FETCH_IS_ALLOWED_CREATOR: [u8; 4] = function_selector!("fetchIsAllowedCreator", Address);
+/// `grantCreator(address)`.
+pub const GRANT_CREATOR: [u8; 4] = function_selector!("grantCreator", Address);
+/// `revokeCreator(address)`.
+pub const REVOKE_CREATOR: [u8; 4] = function_selector!("revokeCreator", Address);
+/// `fetchListingFee()`.
+pub const FETCH_LISTING_FEE: [u8; 4] = function_selector!("fetchListingFee");
+/// `setListingFee(address,uint256)`.
+pub const SET_LISTING_FEE: [u8; 4] = function_selector!("setListingFee", Address, U256);
+/// `fetchListingFeeWaived(address)`.
+pub const FETCH_LISTING_FEE_WAIVED: [u8; 4] = function_selector!("fetchListingFeeWaived", Address);
+/// `setListingFeeWaiver(address,bool)`.
+pub const SET_LISTING_FEE_WAIVER: [u8; 4] =
+    function_selector!("setListingFeeWaiver", Address, bool);
+/// `setProtocolFee(uint16)`.
+pub const SET_PROTOCOL_FEE: [u8; 4] = function_selector!("setProtocolFee", u16);
+/// `fetchProtocolFee()`.
+pub const FETCH_PROTOCOL_FEE: [u8; 4] = function_selector!("fetchProtocolFee");
+/// `MAX_PROTOCOL_FEE_BPS()`.
+pub const MAX_PROTOCOL_FEE_BPS: [u8; 4] = function_selector!("MAX_PROTOCOL_FEE_BPS");
+/// `setTreasuryConfig(address,bool,address)`.
+pub const SET_TREASURY_CONFIG: [u8; 4] =
+    function_selector!("setTreasuryConfig", Address, bool, Address);
+/// `fetchTreasuryConfig()`.
+pub const FETCH_TREASURY_CONFIG: [u8; 4] = function_selector!("fetchTreasuryConfig");
+/// `fetchTreasuryBalance(address)`.
+pub const FETCH_TREASURY_BALANCE: [u8; 4] = function_selector!("fetchTreasuryBalance", Address);
+/// `setFeeCollector(address)`.
+pub const SET_FEE_COLLECTOR: [u8; 4] = function_selector!("setFeeCollector", Address);
+/// `fetchFeeCollector()`.
+pub const FETCH_FEE_COLLECTOR: [u8; 4] = function_selector!("fetchFeeCollector");
+/// `setFeePushThreshold(address,uint256)`.
+pub const SET_FEE_PUSH_THRESHOLD: [u8; 4] =
+    function_selector!("setFeePushThreshold", Address, U256);
+/// `fetchFeePushThreshold(address)`.
+pub const FETCH_FEE_PUSH_THRESHOLD: [u8; 4] = function_selector!("fetchFeePushThreshold", Address);
+/// `collectFees(address)`.
+pub const COLLECT_FEES: [u8; 4] = function_selector!("collectFees", Address);
+/// `collectFees(address,address)`.
+pub const COLLECT_FEES_TO: [u8; 4] = function_selector!("collectFees", Address, Address);
+/// `withdrawTreasury(address,address,uint256)`.
+pub const WITHDRAW_TREASURY: [u8; 4] =
+    function_selector!("withdrawTreasury", Address, Address, U256);
+/// `buybackTreasury(uint256,uint256,uint256)`.
+pub const BUYBACK_TREASURY: [u8; 4] = function_selector!("buybackTreasury", U256, U256, U256);
+/// `setGuardian(address,bool)`.
+pub const SET_GUARDIAN: [u8; 4] = function_selector!("setGuardian", Address, bool);
+/// `fetchIsGuardian(address)`.
+pub const FETCH_IS_GUARDIAN: [u8; 4] = function_selector!("fetchIsGuardian", Address);
+/// `setInsuranceShare(uint16)`.
+pub const SET_INSURANCE_SHARE: [u8; 4] = function_selector!("setInsuranceShare", u16);
+/// `fetchInsuranceShare()`.
+pub const FETCH_INSURANCE_SHARE: [u8; 4] = function_selector!("fetchInsuranceShare");
+/// `fetchInsuranceBalance(address)`.
+pub const FETCH_INSURANCE_BALANCE: [u8; 4] = function_selector!("fetchInsuranceBalance", Address);
+/// `setCreatorFeeShare(uint16)`.
+pub const SET_CREATOR_FEE_SHARE: [u8; 4] = function_selector!("setCreatorFeeShare", u16);
+/// `fetchCreatorFeeShare()`.
+pub const FETCH_CREATOR_FEE_SHARE: [u8; 4] = function_selector!("fetchCreatorFeeShare");
+/// `fetchCreatorFees(address,address)`.
+pub const FETCH_CREATOR_FEES: [u8; 4] = function_selector!("fetchCreatorFees", Address, Address);
+/// `claimCreatorFees(address)`.
+pub const CLAIM_CREATOR_FEES: [u8; 4] = function_selector!("claimCreatorFees", Address);
+/// `claimCreatorFees(address,address)`.
+pub const CLAIM_CREATOR_FEES_TO: [u8; 4] = function_selector!("claimCreatorFees", Address, Address);
+/// `coverShortfall(uint256,address,uint256)`.
+pub const COVER_SHORTFALL: [u8; 4] = function_selector!("coverShortfall", U256, Address, U256);
+/// `transferOwnership(address)`.
+pub const TRANSFER_OWNERSHIP: [u8; 4] = function_selector!("transferOwnership", Address);
+/// `acceptOwnership()`.
+pub const ACCEPT_OWNERSHIP: [u8; 4] = function_selector!("acceptOwnership");
+/// `sunset()`.
+pub const SUNSET: [u8; 4] = function_selector!("sunset");
+/// `fetchSunset()`.
+pub const FETCH_SUNSET: [u8; 4] = function_selector!("fetchSunset");
+/// `addAdmin(address)`.
+pub const ADD_ADMIN: [u8; 4] = function_selector!("addAdmin", Address);
+/// `removeAdmin(address)`.
+pub const REMOVE_ADMIN: [u8; 4] = function_selector!("removeAdmin", Address);
+/// `setAdminThreshold(uint64)`.
+pub const SET_ADMIN_THRESHOLD: [u8; 4] = function_selector!("setAdminThreshold", u64);
+/// `proposeAdminAction(bytes)`.
+pub const PROPOSE_ADMIN_ACTION: [u8; 4] = function_selector!("proposeAdminAction", Bytes);
+/// `confirm(uint256)`.
+pub const CONFIRM: [u8; 4] = function_selector!("confirm", U256);
+/// `execute(uint256)`.
+pub const EXECUTE: [u8; 4] = function_selector!("execute", U256);
+/// `setL1Governor(address)`.
+pub const SET_L1_GOVERNOR: [u8; 4] = function_selector!("setL1Governor", Address);
+/// `fetchL1Governor()`.
+pub const FETCH_L1_GOVERNOR: [u8; 4] = function_selector!("fetchL1Governor");
+/// `executeL1Message(bytes)`.
+pub const EXECUTE_L1_MESSAGE: [u8; 4] = function_selector!("executeL1Message", Bytes);
+/// `fetchOwner()`.
+pub const FETCH_OWNER: [u8; 4] = function_selector!("fetchOwner");
+/// `fetchPendingOwner()`.
+pub const FETCH_PENDING_OWNER: [u8; 4] = function_selector!("fetchPendingOwner");
+/// `fetchAdmins()`.
+pub const FETCH_ADMINS: [u8; 4] = function_selector!("fetchAdmins");
+/// `fetchAdminThreshold()`.
+pub const FETCH_ADMIN_THRESHOLD: [u8; 4] = function_selector!("fetchAdminThreshold");
+/// `fetchAdminAction(uint256)`.
+pub const FETCH_ADMIN_ACTION: [u8; 4] = function_selector!("fetchAdminAction", U256);
+/// `fetchSelectors()`.
+pub const FETCH_SELECTORS: [u8; 4] = function_selector!("fetchSelectors");
+
+/// Selectors of every public method, in ABI order.
+pub const ALL: [[u8; 4]; 233] = [
+    INITIALIZE,
+    INITIALIZE_WITH_CONFIG,
+    CREATE_MARKET,
+    CREATE_MARKET_WITH_FEE_TIER,
+    CREATE_MARKET_WITH_SALT,
+    CREATE_AND_FUND,
+    PREVIEW_CREATE_MARKET,
+    DEPOSIT_LIQUIDITY,
+    WITHDRAW_LIQUIDITY,
+    WITHDRAW_LIQUIDITY_TO,
+    TRANSFER_RESERVES,
+    SET_WITHDRAWAL_DELAY,
+    FETCH_WITHDRAWAL_DELAY,
+    REQUEST_WITHDRAWAL,
+    EXECUTE_WITHDRAWAL,
+    EXECUTE_WITHDRAWAL_TO,
+    CANCEL_WITHDRAWAL,
+    FETCH_PENDING_WITHDRAWAL,
+    SET_MIN_LOCKED_LIQUIDITY,
+    FETCH_MIN_LOCKED_LIQUIDITY,
+    LOCK_LIQUIDITY,
+    FETCH_LOCKED_LIQUIDITY,
+    SWAP_BASE_TOKEN_FOR_QUOTE_TOKEN,
+    SWAP_BASE_TOKEN_FOR_QUOTE_TOKEN_AT_RATE,
+    SWAP_QUOTE_TOKEN_FOR_BASE_TOKEN,
+    SWAP_QUOTE_TOKEN_FOR_BASE_TOKEN_AT_RATE,
+    SWAP_BASE_TOKEN_FOR_QUOTE_TOKEN_SUBMITTED_AT,
+    SWAP_QUOTE_TOKEN_FOR_BASE_TOKEN_SUBMITTED_AT,
+    SET_MAX_SWAP_AGE,
+    FETCH_MAX_SWAP_AGE,
+    SWAP_BEST,
+    SWAP_SPLIT,
+    SWAP_WITH_VOUCHER,
+    FETCH_VOUCHER_USED,
+    SWAP_VIA_UNDERLYING,
+    SWAP_AND_CALL,
+    ON_TRANSFER_RECEIVED,
+    COMMIT_SWAP,
+    REVEAL_AND_EXECUTE,
+    CANCEL_SWAP_COMMITMENT,
+    COMPUTE_SWAP_COMMITMENT,
+    FETCH_SWAP_COMMITMENT,
+    PLACE_CONDITIONAL_ORDER,
+    CANCEL_CONDITIONAL_ORDER,
+    FILL_CONDITIONAL_ORDER,
+    FILL_CONDITIONAL_ORDER_PARTIAL,
+    SETTLE_ORDERS,
+    FETCH_CONDITIONAL_ORDER,
+    FETCH_CONDITIONAL_ORDER_TRIGGERED,
+    FETCH_CONDITIONAL_ORDER_COUNT,
+    FETCH_OPEN_ORDERS,
+    CANCEL_EXPIRED_ORDERS,
+    SET_KEEPER_TIP_BOUNDS,
+    FETCH_KEEPER_TIP_BOUNDS,
+    SET_MARKET_KEEPER_TIP,
+    FETCH_MARKET_KEEPER_TIP,
+    FETCH_CONDITIONAL_ORDER_TIP,
+    QUOTE_EXACT_IN,
+    QUOTE_BATCH,
+    QUOTE_EXACT_OUT,
+    CHECK_SWAP_PRECONDITIONS,
+    QUOTE_BOTH_DIRECTIONS,
+    FETCH_INITIALIZATION_STATUS,
+    INITIALIZED_VERSION,
+    NAME,
+    VERSION,
+    IMPLEMENTATION_INFO,
+    FETCH_CONFIG,
+    DOMAIN_SEPARATOR,
+    NONCES,
+    FETCH_CURRENT_MARKET_INDEX,
+    FETCH_EXCHANGE_RATE,
+    FETCH_MARKET_ID,
+    FETCH_MARKET_BY_TOKENS,
+    FETCH_MARKET_BY_ID,
+    FETCH_MARKETS_ENRICHED,
+    FETCH_MARKET_STATUS,
+    FETCH_MARKET_HEALTH,
+    FETCH_HEALTH,
+    PAUSE_MARKET,
+    UNPAUSE_MARKET,
+    FLAG_MARKET,
+    RESOLVE_FLAG,
+    FETCH_MARKET_FLAG,
+    SET_MARKET_EXPIRY,
+    SET_MARKET_FEE,
+    FETCH_MARKET_FEE,
+    SET_FEE_TIER,
+    FETCH_FEE_TIER,
+    SET_MARKET_FEE_TIER,
+    FETCH_MARKET_FEE_TIER,
+    SET_MARKET_MAKER_FEE,
+    SET_MARKET_DEPOSIT_TOLERANCE,
+    FETCH_MARKET_DEPOSIT_TOLERANCE,
+    FETCH_MARKET_FEE_SCHEDULE,
+    SET_HOOK_APPROVAL,
+    FETCH_IS_APPROVED_HOOK,
+    SET_HOOK_GAS_LIMIT,
+    FETCH_HOOK_GAS_LIMIT,
+    SET_MARKET_HOOK,
+    FETCH_MARKET_HOOK,
+    SET_MARKET_COMPLIANCE_REGISTRY,
+    FETCH_MARKET_COMPLIANCE_REGISTRY,
+    SET_MARKET_EOA_ONLY,
+    FETCH_MARKET_EOA_ONLY,
+    SET_MARKET_DIRECTION_MODE,
+    FETCH_MARKET_DIRECTION_MODE,
+    SET_MARKET_CONTRACT_ALLOWED,
+    FETCH_MARKET_CONTRACT_ALLOWED,
+    SET_MARKET_RECIPIENT_RESTRICTED,
+    FETCH_MARKET_RECIPIENT_RESTRICTED,
+    SET_MARKET_CUSTODIAN_ALLOWED,
+    FETCH_MARKET_CUSTODIAN_ALLOWED,
+    SET_MARKET_RATE_BOUNDS,
+    FETCH_MARKET_RATE_BOUNDS,
+    SET_MARKET_RATE_LIMIT,
+    FETCH_MARKET_RATE_LIMIT,
+    GRANT_RATE_OPERATOR,
+    REVOKE_RATE_OPERATOR,
+    FETCH_RATE_OPERATOR,
+    TRANSFER_MARKET_CREATOR,
+    ACCEPT_MARKET_CREATOR,
+    FETCH_PENDING_MARKET_CREATOR,
+    SET_PRESALE,
+    SET_PRESALE_ALLOWLIST,
+    PROVE_PRESALE_ACCESS,
+    FETCH_PRESALE_ALLOWED,
+    FETCH_PHASE,
+    SET_SALE,
+    FINALIZE_SALE,
+    CLAIM_REFUND,
+    FETCH_SALE,
+    FETCH_CONTRIBUTION,
+    SET_AIRDROP_ROOT,
+    CLAIM_AIRDROP,
+    SWEEP_AIRDROP,
+    FETCH_AIRDROP,
+    FETCH_AIRDROP_CLAIMED,
+    SCHEDULE_RATE_UPDATE,
+    FETCH_PENDING_RATE,
+    SET_RATE_DECAY,
+    FETCH_RATE_DECAY,
+    FETCH_CURRENT_RATE,
+    FETCH_MARKET_DECIMALS,
+    REFRESH_TOKEN_METADATA,
+    FETCH_MARKET_DECIMALS_CACHED_AT,
+    SET_MARKET_COMMIT_REVEAL,
+    FETCH_MARKET_COMMIT_REVEAL,
+    SET_MARKET_VAULTS,
+    FETCH_MARKET_VAULTS,
+    SET_MARKET_REBASING,
+    FETCH_MARKET_REBASING,
+    SYNC_MARKET_RESERVES,
+    FETCH_PAIR_HISTORY,
+    FETCH_BEST_MARKET,
+    FETCH_MARKET_RESERVES,
+    FETCH_TVL,
+    FETCH_TVL_ALL,
+    FETCH_ACCOUNTING,
+    VERIFY_SOLVENCY,
+    SET_SOLVENCY_CONFIG,
+    FETCH_SOLVENCY_CONFIG,
+    FETCH_USER_STATS,
+    SET_PURCHASE_CAP,
+    FETCH_PURCHASE_CAP,
+    FETCH_PURCHASED,
+    FETCH_REMAINING_ALLOCATION,
+    CLOSE_MARKET,
+    CREATE_BASKET,
+    DEPOSIT_BASKET,
+    WITHDRAW_BASKET,
+    BUY_FROM_BASKET,
+    FETCH_BASKET,
+    FETCH_BASKET_QUOTE,
+    FETCH_MARKET_BY_MARKET_ID,
+    COMPUTE_MARKET_ID,
+    COMPUTE_SALTED_MARKET_ID,
+    FETCH_MARKET_BY_SALT,
+    FETCH_DETERMINISTIC_MARKET_IDS,
+    SET_DETERMINISTIC_MARKET_IDS,
+    FETCH_CREATION_MODE,
+    SET_CREATION_MODE,
+    FETCH_IS_ALLOWED_CREATOR,
+    GRANT_CREATOR,
+    REVOKE_CREATOR,
+    FETCH_LISTING_FEE,
+    SET_LISTING_FEE,
+    FETCH_LISTING_FEE_WAIVED,
+    SET_LISTING_FEE_WAIVER,
+    SET_PROTOCOL_FEE,
+    FETCH_PROTOCOL_FEE,
+    MAX_PROTOCOL_FEE_BPS,
+    SET_TREASURY_CONFIG,
+    FETCH_TREASURY_CONFIG,
+    FETCH_TREASURY_BALANCE,
+    SET_FEE_COLLECTOR,
+    FETCH_FEE_COLLECTOR,
+    SET_FEE_PUSH_THRESHOLD,
+    FETCH_FEE_PUSH_THRESHOLD,
+    COLLECT_FEES,
+    COLLECT_FEES_TO,
+    WITHDRAW_TREASURY,
+    BUYBACK_TREASURY,
+    SET_GUARDIAN,
+    FETCH_IS_GUARDIAN,
+    SET_INSURANCE_SHARE,
+    FETCH_INSURANCE_SHARE,
+    FETCH_INSURANCE_BALANCE,
+    SET_CREATOR_FEE_SHARE,
+    FETCH_CREATOR_FEE_SHARE,
+    FETCH_CREATOR_FEES,
+    CLAIM_CREATOR_FEES,
+    CLAIM_CREATOR_FEES_TO,
+    COVER_SHORTFALL,
+    TRANSFER_OWNERSHIP,
+    ACCEPT_OWNERSHIP,
+    SUNSET,
+    FETCH_SUNSET,
+    ADD_ADMIN,
+    REMOVE_ADMIN,
+    SET_ADMIN_THRESHOLD,
+    PROPOSE_ADMIN_ACTION,
+    CONFIRM,
+    EXECUTE,
+    SET_L1_GOVERNOR,
+    FETCH_L1_GOVERNOR,
+    EXECUTE_L1_MESSAGE,
+    FETCH_OWNER,
+    FETCH_PENDING_OWNER,
+    FETCH_ADMINS,
+    FETCH_ADMIN_THRESHOLD,
+    FETCH_ADMIN_ACTION,
+    FETCH_SELECTORS,
+];
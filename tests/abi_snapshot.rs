@@ -0,0 +1,50 @@
+//!
+//! ABI snapshot test.
+//!
+//! Compares the exported Solidity interface with `tests/snapshots/IContract.sol`, so signature
+//! and selector changes must be made deliberately. Requires the `export-abi` feature:
+//!
+//! ```bash
+//! cargo test --features export-abi --test abi_snapshot
+//! ```
+//!
+//! Set `UPDATE_ABI_SNAPSHOT=1` to rewrite the snapshot after an intended change.
+//!
+
+use core::fmt;
+use stylus_dorg_task::Contract;
+use stylus_sdk::abi::export::GenerateAbi;
+
+/// Snapshot file, relative to the crate root.
+const SNAPSHOT_PATH: &str = "tests/snapshots/IContract.sol";
+
+/// Env var that rewrites the snapshot instead of comparing against it.
+const UPDATE_ABI_SNAPSHOT: &str = "UPDATE_ABI_SNAPSHOT";
+
+/// Same license and pragma as `main.rs` passes to `print_abi`.
+const HEADER: &str = "// SPDX-License-Identifier: MIT-OR-APACHE-2.0\npragma solidity ^0.8.23;\n\n";
+
+struct Abi;
+
+impl fmt::Display for Abi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Contract::fmt_abi(f)
+    }
+}
+
+#[test]
+fn exported_abi_matches_snapshot() {
+    let abi = format!("{HEADER}{Abi}");
+    let path = format!("{}/{SNAPSHOT_PATH}", env!("CARGO_MANIFEST_DIR"));
+
+    if std::env::var(UPDATE_ABI_SNAPSHOT).is_ok() {
+        std::fs::write(&path, &abi).expect("failed to write ABI snapshot");
+        return;
+    }
+
+    let snapshot = std::fs::read_to_string(&path).expect("failed to read ABI snapshot");
+    assert!(
+        abi == snapshot,
+        "exported ABI differs from {SNAPSHOT_PATH}, rerun with {UPDATE_ABI_SNAPSHOT}=1 if the change is intended\n\n{abi}"
+    );
+}
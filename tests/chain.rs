@@ -0,0 +1,32 @@
+//!
+//! Tests for the L1 address aliasing helpers.
+//!
+
+use proptest::prelude::*;
+use stylus_dorg_task::chain::{apply_l1_alias, undo_l1_alias};
+use stylus_sdk::alloy_primitives::{address, Address};
+
+#[test]
+fn aliases_match_arbitrum() {
+    assert_eq!(
+        apply_l1_alias(Address::ZERO),
+        address!("1111000000000000000000000000000000001111")
+    );
+    assert_eq!(
+        apply_l1_alias(address!("ffffffffffffffffffffffffffffffffffffffff")),
+        address!("1111000000000000000000000000000000001110")
+    );
+    assert_eq!(
+        undo_l1_alias(address!("1111000000000000000000000000000000001111")),
+        Address::ZERO
+    );
+}
+
+proptest! {
+    #[test]
+    fn aliasing_round_trips(bytes in any::<[u8; 20]>()) {
+        let l1_address = Address::from(bytes);
+        prop_assert_eq!(undo_l1_alias(apply_l1_alias(l1_address)), l1_address);
+        prop_assert_ne!(apply_l1_alias(l1_address), l1_address);
+    }
+}
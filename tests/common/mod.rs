@@ -0,0 +1,17 @@
+//!
+//! Helpers shared by the host tests.
+//!
+
+use stylus_sdk::keccak_const::Keccak256;
+
+/// Host stand-in for the `native_keccak256` hostio that `keccak` links against on-chain.
+///
+/// # Safety
+///
+/// `bytes` must point to `len` readable bytes and `output` to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn native_keccak256(bytes: *const u8, len: usize, output: *mut u8) {
+    let input = core::slice::from_raw_parts(bytes, len);
+    let hash = Keccak256::new().update(input).finalize();
+    core::ptr::copy_nonoverlapping(hash.as_ptr(), output, 32);
+}
@@ -0,0 +1,318 @@
+//!
+//! Differential tests against the Solidity reference in `tests/reference/FixedRateReference.sol`.
+//!
+//! Both contracts must be freshly deployed to the same devnode. The test creates markets, sets
+//! fees and quotes randomized amounts on both, and checks outputs, revert data and events match.
+//!
+//! Ignored by default, run with:
+//!
+//! ```bash
+//! RPC_URL=http://localhost:8547 PRIVATE_KEY_PATH=... \
+//! STYLUS_CONTRACT_ADDRESS=0x... REFERENCE_CONTRACT_ADDRESS=0x... \
+//! cargo test --test differential -- --ignored
+//! ```
+//!
+//! `DIFFERENTIAL_CASES` (default 32) and `DIFFERENTIAL_SEED` tune the randomized inputs.
+//!
+
+use dotenv::dotenv;
+use ethers::{
+    contract::ContractError,
+    middleware::SignerMiddleware,
+    prelude::abigen,
+    providers::{Http, Middleware, Provider},
+    signers::{LocalWallet, Signer},
+    types::{Address, Bytes, H256, U256},
+    utils::keccak256,
+};
+use eyre::eyre;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Your private key file path.
+const PRIVATE_KEY_PATH: &str = "PRIVATE_KEY_PATH";
+
+/// Stylus RPC endpoint url.
+const RPC_URL: &str = "RPC_URL";
+
+/// Deployed program addresses.
+const STYLUS_CONTRACT_ADDRESS: &str = "STYLUS_CONTRACT_ADDRESS";
+const REFERENCE_CONTRACT_ADDRESS: &str = "REFERENCE_CONTRACT_ADDRESS";
+
+/// Randomized input settings.
+const DIFFERENTIAL_CASES: &str = "DIFFERENTIAL_CASES";
+const DIFFERENTIAL_SEED: &str = "DIFFERENTIAL_SEED";
+
+// ABI shared by the Stylus contract and the reference.
+abigen!(
+    FixedRate,
+    r#"[
+        function createMarket(address base_token, address quote_token, uint256 exchange_rate) external returns (uint256)
+        function setMarketFee(uint256 market_index, uint16 fee_bps) external
+        function fetchCurrentMarketIndex() external view returns (uint256)
+        function quoteExactIn(uint256 market_index, uint8 direction, uint256 amount_in) external view returns (uint256)
+        function quoteExactOut(uint256 market_index, uint8 direction, uint256 amount_out) external view returns (uint256)
+    ]"#
+);
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// Outcome of a call, either its return value or its revert data.
+type Outcome<T> = Result<T, Option<Bytes>>;
+
+#[tokio::test]
+#[ignore = "requires a devnode with both contracts deployed"]
+async fn stylus_matches_solidity_reference() -> eyre::Result<()> {
+    // Load environment variables
+    dotenv().ok();
+
+    let private_key_path = std::env::var(PRIVATE_KEY_PATH)
+        .map_err(|_| eyre!("No {} env var set", PRIVATE_KEY_PATH))?;
+    let private_key = read_secret_from_file(&private_key_path)?;
+    let rpc_url = std::env::var(RPC_URL).map_err(|_| eyre!("No {} env var set", RPC_URL))?;
+    let stylus_address: Address = std::env::var(STYLUS_CONTRACT_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", STYLUS_CONTRACT_ADDRESS))?
+        .parse()?;
+    let reference_address: Address = std::env::var(REFERENCE_CONTRACT_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", REFERENCE_CONTRACT_ADDRESS))?
+        .parse()?;
+    let cases: usize = std::env::var(DIFFERENTIAL_CASES)
+        .map(|cases| cases.parse())
+        .unwrap_or(Ok(32))?;
+    let seed: u64 = std::env::var(DIFFERENTIAL_SEED)
+        .map(|seed| seed.parse())
+        .unwrap_or(Ok(0x5eed))?;
+
+    // Set up rpc client.
+    let wallet = LocalWallet::from_str(&private_key)?;
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let client = Arc::new(SignerMiddleware::new(
+        provider,
+        wallet.with_chain_id(chain_id),
+    ));
+
+    let stylus = FixedRate::new(stylus_address, client.clone());
+    let reference = FixedRate::new(reference_address, client);
+
+    // Market indexes are only comparable on fresh deployments.
+    let stylus_index = stylus.fetch_current_market_index().call().await?;
+    let reference_index = reference.fetch_current_market_index().call().await?;
+    if stylus_index != reference_index {
+        return Err(eyre!(
+            "market indexes differ ({stylus_index} != {reference_index}), redeploy both contracts"
+        ));
+    }
+
+    let mut rng = XorShift(seed | 1);
+    for case in 0..cases {
+        println!("case {case}");
+
+        // Create a market for a fresh pair, occasionally with invalid inputs.
+        let base_token = rng.address(case);
+        let quote_token = rng.address(case);
+        let exchange_rate = rng.u256();
+        let created =
+            compare_create_market(&stylus, &reference, base_token, quote_token, exchange_rate)
+                .await?;
+
+        // Creating the same pair again lists a second market, or fails the same way.
+        compare_create_market(&stylus, &reference, base_token, quote_token, exchange_rate).await?;
+
+        // Set a fee, occasionally above the cap.
+        let market_index = stylus
+            .fetch_current_market_index()
+            .call()
+            .await?
+            .saturating_sub(U256::one());
+        let fee_bps = (rng.next() % 1_100) as u16;
+        compare_set_market_fee(&stylus, &reference, market_index, fee_bps).await?;
+
+        // Quote randomized amounts in every direction, including an invalid one and a missing market.
+        let quoted_index = if created {
+            market_index
+        } else {
+            market_index + 1
+        };
+        for direction in 0..3u8 {
+            let amount = rng.u256();
+            let stylus_out = outcome(
+                stylus
+                    .quote_exact_in(quoted_index, direction, amount)
+                    .call()
+                    .await,
+            );
+            let reference_out = outcome(
+                reference
+                    .quote_exact_in(quoted_index, direction, amount)
+                    .call()
+                    .await,
+            );
+            assert_eq!(
+                stylus_out, reference_out,
+                "quoteExactIn({quoted_index}, {direction}, {amount})"
+            );
+
+            let stylus_in = outcome(
+                stylus
+                    .quote_exact_out(quoted_index, direction, amount)
+                    .call()
+                    .await,
+            );
+            let reference_in = outcome(
+                reference
+                    .quote_exact_out(quoted_index, direction, amount)
+                    .call()
+                    .await,
+            );
+            assert_eq!(
+                stylus_in, reference_in,
+                "quoteExactOut({quoted_index}, {direction}, {amount})"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Create a market on both contracts and compare return values, reverts and events.
+///
+/// Return whether the market was created.
+async fn compare_create_market(
+    stylus: &FixedRate<Client>,
+    reference: &FixedRate<Client>,
+    base_token: Address,
+    quote_token: Address,
+    exchange_rate: U256,
+) -> eyre::Result<bool> {
+    let stylus_call = stylus.create_market(base_token, quote_token, exchange_rate);
+    let reference_call = reference.create_market(base_token, quote_token, exchange_rate);
+
+    let stylus_result = outcome(stylus_call.call().await);
+    let reference_result = outcome(reference_call.call().await);
+    assert_eq!(
+        stylus_result, reference_result,
+        "createMarket({base_token:?}, {quote_token:?}, {exchange_rate})"
+    );
+    if stylus_result.is_err() {
+        return Ok(false);
+    }
+
+    let stylus_logs = collect_logs(stylus_call.send().await?.await?);
+    let reference_logs = collect_logs(reference_call.send().await?.await?);
+    assert_eq!(stylus_logs, reference_logs, "createMarket events");
+
+    // The returned index is the created market's, and its event carries it.
+    let created_index = stylus.fetch_current_market_index().call().await? - 1;
+    assert_eq!(
+        stylus_result,
+        Ok(created_index),
+        "createMarket return value"
+    );
+    let market_created = H256::from(keccak256(
+        "MarketCreated(address,address,uint256,uint256,address)",
+    ));
+    let (topics, _) = stylus_logs
+        .iter()
+        .find(|(topics, _)| topics.first() == Some(&market_created))
+        .ok_or_else(|| eyre!("no MarketCreated event"))?;
+    assert_eq!(
+        U256::from_big_endian(topics[3].as_bytes()),
+        created_index,
+        "MarketCreated market index"
+    );
+
+    Ok(true)
+}
+
+/// Set a market fee on both contracts and compare reverts and events.
+async fn compare_set_market_fee(
+    stylus: &FixedRate<Client>,
+    reference: &FixedRate<Client>,
+    market_index: U256,
+    fee_bps: u16,
+) -> eyre::Result<()> {
+    let stylus_call = stylus.set_market_fee(market_index, fee_bps);
+    let reference_call = reference.set_market_fee(market_index, fee_bps);
+
+    let stylus_result = outcome(stylus_call.call().await);
+    let reference_result = outcome(reference_call.call().await);
+    assert_eq!(
+        stylus_result, reference_result,
+        "setMarketFee({market_index}, {fee_bps})"
+    );
+    if stylus_result.is_err() {
+        return Ok(());
+    }
+
+    let stylus_logs = collect_logs(stylus_call.send().await?.await?);
+    let reference_logs = collect_logs(reference_call.send().await?.await?);
+    assert_eq!(stylus_logs, reference_logs, "setMarketFee events");
+
+    Ok(())
+}
+
+/// Collect the topics and data of every log in a receipt, ignoring the emitting address.
+fn collect_logs(receipt: Option<ethers::types::TransactionReceipt>) -> Vec<(Vec<H256>, Bytes)> {
+    receipt
+        .map(|receipt| {
+            receipt
+                .logs
+                .into_iter()
+                .map(|log| (log.topics, log.data))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reduce a call result to its return value or revert data.
+fn outcome<T>(result: Result<T, ContractError<Client>>) -> Outcome<T> {
+    result.map_err(|err| err.as_revert().cloned())
+}
+
+/// Small deterministic generator for randomized inputs.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Random value biased towards edge cases.
+    fn u256(&mut self) -> U256 {
+        match self.next() % 8 {
+            0 => U256::zero(),
+            1 => U256::one(),
+            2 => U256::MAX,
+            3 => U256::MAX - U256::from(self.next() % 1_000),
+            4 => U256::from(self.next()),
+            5 => U256::from(self.next()) * U256::exp10(18),
+            _ => U256([self.next(), self.next(), self.next(), self.next()]),
+        }
+    }
+
+    /// Random token address, occasionally the zero address.
+    fn address(&mut self, case: usize) -> Address {
+        if self.next() % 16 == 0 {
+            return Address::zero();
+        }
+
+        let mut bytes = [0u8; 20];
+        bytes[..8].copy_from_slice(&self.next().to_be_bytes());
+        bytes[8..16].copy_from_slice(&self.next().to_be_bytes());
+        bytes[16..].copy_from_slice(&(case as u32).to_be_bytes());
+        Address::from(bytes)
+    }
+}
+
+fn read_secret_from_file(fpath: &str) -> eyre::Result<String> {
+    let f = std::fs::File::open(fpath)?;
+    let mut buf_reader = BufReader::new(f);
+    let mut secret = String::new();
+    buf_reader.read_line(&mut secret)?;
+    Ok(secret.trim().to_string())
+}
@@ -0,0 +1,64 @@
+//!
+//! Tests for the EIP-712 hashing of signed payloads.
+//!
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{eip712_domain, sol, SolStruct};
+use proptest::prelude::*;
+use stylus_dorg_task::eip712::{domain_separator, typed_data_hash, voucher_hash};
+
+sol! {
+    struct Voucher {
+        uint256 marketIndex;
+        address trader;
+        uint256 exchangeRate;
+        uint16 feeBps;
+        uint256 maxAmountIn;
+        uint256 nonce;
+        uint64 deadline;
+    }
+}
+
+fn any_u256() -> impl Strategy<Value = U256> {
+    any::<[u64; 4]>().prop_map(U256::from_limbs)
+}
+
+proptest! {
+    #[test]
+    fn voucher_digest_matches_alloy(
+        market_index in any_u256(),
+        trader in any::<[u8; 20]>().prop_map(Address::from),
+        exchange_rate in any_u256(),
+        fee_bps in any::<u16>(),
+        max_amount_in in any_u256(),
+        nonce in any_u256(),
+        deadline in any::<u64>(),
+        chain_id in any::<u64>(),
+    ) {
+        let verifying_contract = Address::repeat_byte(7);
+        let domain = eip712_domain! {
+            name: "Stylus dOrg Fixed-Cost Token Sales",
+            version: "1",
+            chain_id: chain_id,
+            verifying_contract: verifying_contract,
+        };
+        let reference = Voucher {
+            marketIndex: market_index,
+            trader,
+            exchangeRate: exchange_rate,
+            feeBps: fee_bps,
+            maxAmountIn: max_amount_in,
+            nonce,
+            deadline,
+        };
+
+        let voucher = (market_index, trader, exchange_rate, fee_bps, max_amount_in, nonce, deadline);
+        prop_assert_eq!(voucher_hash(&voucher), reference.eip712_hash_struct());
+        prop_assert_eq!(
+            typed_data_hash(domain_separator(chain_id, verifying_contract), voucher_hash(&voucher)),
+            reference.eip712_signing_hash(&domain)
+        );
+    }
+}
@@ -0,0 +1,67 @@
+//!
+//! Event signature checks for the events indexers rely on.
+//!
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolEvent;
+use stylus_dorg_task::events::MarketCreated;
+
+#[test]
+fn market_created_carries_the_market_index_and_creator() {
+    assert_eq!(
+        MarketCreated::SIGNATURE,
+        "MarketCreated(address,address,uint256,uint256,address)"
+    );
+
+    let event = MarketCreated {
+        base_token: Address::repeat_byte(1),
+        quote_token: Address::repeat_byte(2),
+        exchange_rate: U256::from(3),
+        market_index: U256::from(1),
+        creator: Address::repeat_byte(4),
+    };
+
+    // The market index is the third topic, the creator follows the exchange rate.
+    let topics = event.encode_topics();
+    assert_eq!(topics.len(), 4);
+    assert_eq!(U256::from_be_bytes(topics[3].0 .0), U256::from(1));
+    let data = event.encode_data();
+    assert_eq!(Address::from_slice(&data[44..64]), Address::repeat_byte(4));
+}
+
+#[test]
+fn configuration_events_carry_previous_and_new_values() {
+    let source = include_str!("../src/events.rs");
+    let (_, configuration) = source.split_once("// Configuration events").unwrap();
+    let events: Vec<&str> = configuration
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("event "))
+        .collect();
+    assert!(!events.is_empty());
+
+    for event in events {
+        let (name, rest) = event.split_once('(').unwrap();
+        let (params, _) = rest.split_once(')').unwrap();
+        let values: Vec<&str> = params
+            .split(',')
+            .filter_map(|param| param.split_whitespace().last())
+            .collect();
+
+        // Adding to or removing from a list implies the previous membership.
+        if name.ends_with("Added") || name.ends_with("Removed") {
+            continue;
+        }
+
+        // Every value set is paired with the value it replaced, in the same order.
+        let previous: Vec<&str> = values
+            .iter()
+            .filter_map(|value| value.strip_prefix("previous_"))
+            .collect();
+        let new: Vec<&str> = values
+            .iter()
+            .filter_map(|value| value.strip_prefix("new_"))
+            .collect();
+        assert!(!previous.is_empty(), "{name} has no previous value");
+        assert_eq!(previous, new, "{name}");
+    }
+}
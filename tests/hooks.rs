@@ -0,0 +1,167 @@
+//!
+//! Market hook checks against a devnode.
+//!
+//! Lists two markets of a pair, registers `tests/reference/RejectingHook.sol` on the better one
+//! and checks `swapBest` reverts with `AfterSwapHookRejected` rather than skipping a market
+//! whose fill was already recorded. Deploy the hook, two ERC-20 tokens held by the key's account
+//! (eg. `tests/reference/ReentrantToken.sol`) and a fresh market contract initialized by that
+//! account, then run:
+//!
+//! ```bash
+//! RPC_URL=http://localhost:8547 PRIVATE_KEY_PATH=... STYLUS_CONTRACT_ADDRESS=0x... \
+//! BASE_TOKEN_ADDRESS=0x... QUOTE_TOKEN_ADDRESS=0x... HOOK_ADDRESS=0x... \
+//! cargo test --test hooks -- --ignored
+//! ```
+//!
+
+use alloy_sol_types::SolError;
+use dotenv::dotenv;
+use ethers::{
+    middleware::SignerMiddleware,
+    prelude::abigen,
+    providers::{Http, Middleware, Provider},
+    signers::{LocalWallet, Signer},
+    types::{Address, U256},
+};
+use eyre::eyre;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+use std::sync::Arc;
+use stylus_dorg_task::AfterSwapHookRejected;
+
+/// Your private key file path.
+const PRIVATE_KEY_PATH: &str = "PRIVATE_KEY_PATH";
+
+/// Stylus RPC endpoint url.
+const RPC_URL: &str = "RPC_URL";
+
+/// Deployed program addresses.
+const STYLUS_CONTRACT_ADDRESS: &str = "STYLUS_CONTRACT_ADDRESS";
+const BASE_TOKEN_ADDRESS: &str = "BASE_TOKEN_ADDRESS";
+const QUOTE_TOKEN_ADDRESS: &str = "QUOTE_TOKEN_ADDRESS";
+const HOOK_ADDRESS: &str = "HOOK_ADDRESS";
+
+abigen!(
+    FixedRate,
+    r#"[
+        function createMarket(address base_token, address quote_token, uint256 exchange_rate) external returns (uint256)
+        function depositLiquidity(uint256 market_index, uint256 base_amount, uint256 quote_amount) external
+        function setHookApproval(address hook, bool approved) external
+        function setMarketHook(uint256 market_index, address hook) external
+        function swapBest(address base_token, address quote_token, uint8 direction, uint256 amount_in, uint256 min_amount_out) external returns (uint256)
+        function fetchCurrentMarketIndex() external view returns (uint256)
+        function fetchMarketReserves(uint256 market_index) external view returns (uint256, uint256)
+    ]"#
+);
+
+abigen!(
+    Erc20,
+    r#"[
+        function approve(address,uint256) external returns (bool)
+    ]"#
+);
+
+#[tokio::test]
+#[ignore = "requires a devnode with the contract, two tokens and the hook deployed"]
+async fn swap_best_reverts_when_the_after_swap_hook_rejects() -> eyre::Result<()> {
+    // Load environment variables
+    dotenv().ok();
+
+    let private_key_path = std::env::var(PRIVATE_KEY_PATH)
+        .map_err(|_| eyre!("No {} env var set", PRIVATE_KEY_PATH))?;
+    let private_key = read_secret_from_file(&private_key_path)?;
+    let rpc_url = std::env::var(RPC_URL).map_err(|_| eyre!("No {} env var set", RPC_URL))?;
+    let stylus_address: Address = std::env::var(STYLUS_CONTRACT_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", STYLUS_CONTRACT_ADDRESS))?
+        .parse()?;
+    let base_address: Address = std::env::var(BASE_TOKEN_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", BASE_TOKEN_ADDRESS))?
+        .parse()?;
+    let quote_address: Address = std::env::var(QUOTE_TOKEN_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", QUOTE_TOKEN_ADDRESS))?
+        .parse()?;
+    let hook_address: Address = std::env::var(HOOK_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", HOOK_ADDRESS))?
+        .parse()?;
+
+    // Set up rpc client.
+    let wallet = LocalWallet::from_str(&private_key)?;
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let client = Arc::new(SignerMiddleware::new(
+        provider,
+        wallet.with_chain_id(chain_id),
+    ));
+
+    let stylus = FixedRate::new(stylus_address, client.clone());
+    for token in [base_address, quote_address] {
+        Erc20::new(token, client.clone())
+            .approve(stylus_address, U256::MAX)
+            .send()
+            .await?
+            .await?;
+    }
+
+    // List a market at rate 2 and a better one at rate 3, both funded with quote token.
+    let liquidity = U256::exp10(21);
+    let mut markets = Vec::new();
+    for rate in [2u64, 3] {
+        stylus
+            .create_market(base_address, quote_address, U256::from(rate))
+            .send()
+            .await?
+            .await?;
+        let market_index = stylus.fetch_current_market_index().call().await? - 1;
+        stylus
+            .deposit_liquidity(market_index, U256::zero(), liquidity)
+            .send()
+            .await?
+            .await?;
+        markets.push(market_index);
+    }
+
+    // Register the rejecting hook on the better market.
+    let hooked = markets[1];
+    stylus
+        .set_hook_approval(hook_address, true)
+        .send()
+        .await?
+        .await?;
+    stylus
+        .set_market_hook(hooked, hook_address)
+        .send()
+        .await?
+        .await?;
+
+    // The route fills the hooked market first, so the whole swap reverts.
+    let amount_in = U256::exp10(18);
+    let result = stylus
+        .swap_best(base_address, quote_address, 0, amount_in, U256::zero())
+        .call()
+        .await;
+    let revert = result
+        .err()
+        .and_then(|err| err.as_revert().cloned())
+        .ok_or_else(|| eyre!("swapBest did not revert"))?;
+    let expected = AfterSwapHookRejected {
+        hook: alloy_primitives::Address::from(hook_address.0),
+    }
+    .abi_encode();
+    assert_eq!(revert.to_vec(), expected, "swapBest revert data");
+
+    // Neither market recorded a fill.
+    for market_index in markets {
+        let reserves = stylus.fetch_market_reserves(market_index).call().await?;
+        assert_eq!(reserves, (U256::zero(), liquidity), "market {market_index}");
+    }
+
+    Ok(())
+}
+
+fn read_secret_from_file(fpath: &str) -> eyre::Result<String> {
+    let f = std::fs::File::open(fpath)?;
+    let mut buf_reader = BufReader::new(f);
+    let mut secret = String::new();
+    buf_reader.read_line(&mut secret)?;
+    Ok(secret.trim().to_string())
+}
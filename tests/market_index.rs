@@ -0,0 +1,58 @@
+//!
+//! Tests for the uint256 market and basket ids of the external ABI.
+//!
+//! Markets and baskets are stored under uint64 keys, so every external id is narrowed with
+//! `narrow_market_index` before it touches storage.
+//!
+
+use proptest::prelude::*;
+use stylus_dorg_task::narrow_market_index;
+use stylus_sdk::alloy_primitives::{U256, U64};
+
+#[test]
+fn narrows_indexes_that_fit() {
+    assert_eq!(narrow_market_index(U256::ZERO), Some(U64::ZERO));
+    assert_eq!(narrow_market_index(U256::from(1)), Some(U64::from(1)));
+    assert_eq!(
+        narrow_market_index(U256::from(u64::MAX)),
+        Some(U64::from(u64::MAX))
+    );
+}
+
+#[test]
+fn rejects_indexes_that_do_not_fit() {
+    assert_eq!(
+        narrow_market_index(U256::from(u64::MAX) + U256::from(1)),
+        None
+    );
+    assert_eq!(narrow_market_index(U256::from(1) << 128), None);
+    assert_eq!(narrow_market_index(U256::MAX), None);
+}
+
+#[test]
+fn abi_has_no_uint64_market_ids() {
+    let snapshot = include_str!("snapshots/IContract.sol");
+    for line in snapshot.lines() {
+        for id in ["market_index", "basket_id"] {
+            assert!(!line.contains(&format!("uint64 {id}")), "{line}");
+            assert!(!line.contains(&format!("uint64 indexed {id}")), "{line}");
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn narrowing_round_trips(market_index in any::<u64>()) {
+        let narrowed = narrow_market_index(U256::from(market_index)).unwrap();
+        prop_assert_eq!(U256::from(narrowed), U256::from(market_index));
+    }
+
+    #[test]
+    fn narrowing_rejects_high_limbs(
+        low in any::<u64>(),
+        high in any::<[u64; 3]>().prop_filter("non-zero high limbs", |high| high != &[0; 3]),
+    ) {
+        let market_index = U256::from_limbs([low, high[0], high[1], high[2]]);
+        prop_assert_eq!(narrow_market_index(market_index), None);
+    }
+}
@@ -0,0 +1,273 @@
+//!
+//! Tests for `math::mul_div` and the swap rounding policy.
+//!
+
+use proptest::prelude::*;
+use stylus_dorg_task::math::{
+    amount_in, amount_out, exponential_decay, inverse_rate, linear_decay, mul_div, protocol_fee,
+    Rounding, WAD,
+};
+use stylus_sdk::alloy_primitives::{U256, U512};
+
+fn any_u256() -> impl Strategy<Value = U256> {
+    any::<[u64; 4]>().prop_map(U256::from_limbs)
+}
+
+fn any_rounding() -> impl Strategy<Value = Rounding> {
+    prop_oneof![Just(Rounding::Down), Just(Rounding::Up)]
+}
+
+/// Reference `a * b / denominator` computed in 512 bits.
+fn reference(a: U256, b: U256, denominator: U256, rounding: Rounding) -> Option<U256> {
+    let product = U512::from(a) * U512::from(b);
+    let denominator = U512::from(denominator);
+    let mut quotient = product / denominator;
+    if rounding == Rounding::Up && product % denominator != U512::ZERO {
+        quotient += U512::from(1);
+    }
+
+    (quotient <= U512::from(U256::MAX)).then(|| U256::from_limbs_slice(&quotient.as_limbs()[..4]))
+}
+
+#[test]
+fn mul_div_rejects_zero_denominator() {
+    assert_eq!(
+        mul_div(U256::from(1), U256::from(1), U256::ZERO, Rounding::Down),
+        None
+    );
+    assert_eq!(
+        mul_div(U256::from(1), U256::from(1), U256::ZERO, Rounding::Up),
+        None
+    );
+}
+
+#[test]
+fn mul_div_keeps_full_precision() {
+    // MAX * MAX / MAX overflows a 256-bit product but fits after division.
+    assert_eq!(
+        mul_div(U256::MAX, U256::MAX, U256::MAX, Rounding::Down),
+        Some(U256::MAX)
+    );
+    assert_eq!(
+        mul_div(U256::MAX, U256::from(2), U256::from(4), Rounding::Down),
+        Some(U256::MAX >> 1)
+    );
+    assert_eq!(
+        mul_div(U256::MAX, U256::from(2), U256::from(4), Rounding::Up),
+        Some((U256::MAX >> 1) + U256::from(1))
+    );
+}
+
+#[test]
+fn mul_div_rejects_results_above_256_bits() {
+    assert_eq!(
+        mul_div(U256::MAX, U256::from(2), U256::from(1), Rounding::Down),
+        None
+    );
+    assert_eq!(
+        mul_div(
+            U256::MAX,
+            U256::MAX,
+            U256::MAX - U256::from(1),
+            Rounding::Down
+        ),
+        None
+    );
+}
+
+#[test]
+fn mul_div_rounding_up_can_overflow() {
+    // (MAX - 1)^2 / (MAX - 2) is MAX plus a remainder.
+    let a = U256::MAX - U256::from(1);
+    let denominator = U256::MAX - U256::from(2);
+    assert_eq!(mul_div(a, a, denominator, Rounding::Down), Some(U256::MAX));
+    assert_eq!(mul_div(a, a, denominator, Rounding::Up), None);
+}
+
+#[test]
+fn amount_out_rounds_down() {
+    // 10 quote at a rate of 3 buys 3.33 base.
+    assert_eq!(
+        amount_out(U256::from(10), U256::from(1), U256::from(3), 0),
+        Some(U256::from(3))
+    );
+    // 10 base at a rate of 3 buys exactly 30 quote.
+    assert_eq!(
+        amount_out(U256::from(10), U256::from(3), U256::from(1), 0),
+        Some(U256::from(30))
+    );
+}
+
+#[test]
+fn amount_out_rounds_fee_up() {
+    // A 0.3% fee on 30 is 0.09, charged as 1.
+    assert_eq!(
+        amount_out(U256::from(10), U256::from(3), U256::from(1), 30),
+        Some(U256::from(29))
+    );
+    // A 0.3% fee on 30_000 is exactly 90.
+    assert_eq!(
+        amount_out(U256::from(10_000), U256::from(3), U256::from(1), 30),
+        Some(U256::from(29_910))
+    );
+}
+
+#[test]
+fn amount_in_rounds_up() {
+    // 3 base at a rate of 3 needs exactly 9 quote.
+    assert_eq!(
+        amount_in(U256::from(3), U256::from(1), U256::from(3), 0),
+        Some(U256::from(9))
+    );
+    // 10 quote at a rate of 3 needs 3.33 base, charged as 4.
+    assert_eq!(
+        amount_in(U256::from(10), U256::from(3), U256::from(1), 0),
+        Some(U256::from(4))
+    );
+    // 29 quote net of a 0.3% fee needs 29.09 gross quote, charged as 10 base.
+    assert_eq!(
+        amount_in(U256::from(29), U256::from(3), U256::from(1), 30),
+        Some(U256::from(10))
+    );
+}
+
+#[test]
+fn amount_in_covers_amount_out() {
+    let amount = U256::from(1_000_003);
+    let needed = amount_in(amount, U256::from(7), U256::from(1), 30).unwrap();
+    assert!(amount_out(needed, U256::from(7), U256::from(1), 30).unwrap() >= amount);
+}
+
+#[test]
+fn fees_above_100_percent_are_rejected() {
+    assert_eq!(
+        amount_out(U256::from(1), U256::from(1), U256::from(1), 10_001),
+        None
+    );
+    assert_eq!(
+        amount_in(U256::from(1), U256::from(1), U256::from(1), 10_000),
+        None
+    );
+}
+
+#[test]
+fn inverse_rate_is_scaled_and_rounds_down() {
+    assert_eq!(inverse_rate(U256::from(1)), Some(WAD));
+    assert_eq!(inverse_rate(U256::from(4)), Some(WAD / U256::from(4)));
+    // 1e18 / 3 = 333...333.33
+    assert_eq!(inverse_rate(U256::from(3)), Some(WAD / U256::from(3)));
+    assert_eq!(inverse_rate(U256::ZERO), None);
+}
+
+#[test]
+fn protocol_fee_rounds_down() {
+    // 3 * 333 * 10 / 10_000 = 0.999
+    assert_eq!(
+        protocol_fee(U256::from(333), U256::from(3), U256::from(1), 10),
+        Some(U256::ZERO)
+    );
+    assert_eq!(
+        protocol_fee(U256::from(10_000), U256::from(3), U256::from(1), 10),
+        Some(U256::from(30))
+    );
+}
+
+#[test]
+fn linear_decay_runs_from_start_to_floor() {
+    let (start, floor) = (U256::from(4_000), U256::from(3_000));
+    assert_eq!(linear_decay(start, floor, 0, 100), start);
+    assert_eq!(linear_decay(start, floor, 25, 100), U256::from(3_750));
+    // 4_000 - 1_000 * 1 / 3 = 3_666.67, the decay rounds down.
+    assert_eq!(linear_decay(start, floor, 1, 3), U256::from(3_667));
+    assert_eq!(linear_decay(start, floor, 100, 100), floor);
+    assert_eq!(linear_decay(start, floor, 500, 100), floor);
+}
+
+#[test]
+fn exponential_decay_halves_the_gap() {
+    let (start, floor) = (U256::from(2_048_000), U256::from(1_000));
+    assert_eq!(exponential_decay(start, floor, 0, 800), start);
+    // The gap of 2_047_000 halves after an eighth, less 1/8 of the 7_996 residual.
+    assert_eq!(
+        exponential_decay(start, floor, 100, 800),
+        U256::from(1_000 + 1_023_500 - 999)
+    );
+    assert_eq!(exponential_decay(start, floor, 800, 800), floor);
+}
+
+proptest! {
+    #[test]
+    fn decays_are_monotonic_and_bounded(
+        floor in any::<u128>(),
+        gap in any::<u128>(),
+        duration in 1u64..=1_000_000,
+        a in any::<u64>(),
+        b in any::<u64>(),
+    ) {
+        let (start, floor) = (U256::from(floor) + U256::from(gap), U256::from(floor));
+        let (a, b) = (a % (duration + 1), b % (duration + 1));
+        let (earlier, later) = (a.min(b), a.max(b));
+        for decay in [linear_decay, exponential_decay] {
+            let high = decay(start, floor, earlier, duration);
+            let low = decay(start, floor, later, duration);
+            prop_assert!(floor <= low && low <= high && high <= start);
+        }
+    }
+
+    #[test]
+    fn protocol_fee_fits_in_the_total_fee(
+        amount_in in any::<u128>(),
+        rate in 1u64..,
+        fee_bps in 0u16..=1_000,
+        protocol_fee_bps in 0u16..=100,
+    ) {
+        let (amount_in, rate) = (U256::from(amount_in), U256::from(rate));
+        let gross = mul_div(amount_in, rate, U256::from(1), Rounding::Down).unwrap();
+        let net = amount_out(amount_in, rate, U256::from(1), fee_bps + protocol_fee_bps).unwrap();
+        let share = protocol_fee(amount_in, rate, U256::from(1), protocol_fee_bps).unwrap();
+        prop_assert!(share <= gross - net);
+    }
+
+    #[test]
+    fn mul_div_matches_reference(
+        a in any_u256(),
+        b in any_u256(),
+        denominator in any_u256(),
+        rounding in any_rounding(),
+    ) {
+        prop_assume!(!denominator.is_zero());
+        prop_assert_eq!(mul_div(a, b, denominator, rounding), reference(a, b, denominator, rounding));
+    }
+
+    #[test]
+    fn mul_div_rounds_up_by_at_most_one(
+        a in any_u256(),
+        b in any_u256(),
+        denominator in any_u256(),
+    ) {
+        if let (Some(down), Some(up)) = (
+            mul_div(a, b, denominator, Rounding::Down),
+            mul_div(a, b, denominator, Rounding::Up),
+        ) {
+            prop_assert!(up == down || up == down + U256::from(1));
+        }
+    }
+
+    #[test]
+    fn mul_div_by_one_is_identity(a in any_u256(), rounding in any_rounding()) {
+        prop_assert_eq!(mul_div(a, U256::from(1), U256::from(1), rounding), Some(a));
+    }
+
+    #[test]
+    fn mul_div_of_a_fraction_never_exceeds_the_amount(
+        amount in any_u256(),
+        numerator in any::<u16>(),
+        extra in any::<u16>(),
+    ) {
+        // numerator <= denominator, as for fee bps.
+        let denominator = U256::from(numerator) + U256::from(extra) + U256::from(1);
+        let result = mul_div(amount, U256::from(numerator), denominator, Rounding::Down);
+        prop_assert!(result.is_some());
+        prop_assert!(result.unwrap() <= amount);
+    }
+}
@@ -0,0 +1,67 @@
+//!
+//! Tests for the merkle proofs of allowlists and claims.
+//!
+
+mod common;
+
+use proptest::prelude::*;
+use stylus_dorg_task::merkle::{address_leaf, amount_leaf, hash_pair, verify};
+use stylus_sdk::alloy_primitives::{Address, B256, U256};
+
+/// Build the proof of each leaf of a tree, and its root.
+fn proofs(leaves: &[B256]) -> (B256, Vec<Vec<B256>>) {
+    let mut proofs = vec![Vec::new(); leaves.len()];
+    let mut positions: Vec<usize> = (0..leaves.len()).collect();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        let mut next = Vec::new();
+        for pair in level.chunks(2) {
+            next.push(match pair {
+                [a, b] => hash_pair(*a, *b),
+                [a] => *a,
+                _ => unreachable!(),
+            });
+        }
+        for (proof, position) in proofs.iter_mut().zip(positions.iter_mut()) {
+            if let Some(sibling) = level.get(*position ^ 1) {
+                proof.push(*sibling);
+            }
+            *position /= 2;
+        }
+        level = next;
+    }
+    (level[0], proofs)
+}
+
+#[test]
+fn pairs_hash_in_sorted_order() {
+    let (a, b) = (B256::repeat_byte(1), B256::repeat_byte(2));
+    assert_eq!(hash_pair(a, b), hash_pair(b, a));
+}
+
+#[test]
+fn leaves_commit_to_their_values() {
+    let account = Address::repeat_byte(1);
+    assert_ne!(address_leaf(account), address_leaf(Address::repeat_byte(2)));
+    assert_ne!(
+        amount_leaf(account, U256::from(1)),
+        amount_leaf(account, U256::from(2))
+    );
+}
+
+proptest! {
+    #[test]
+    fn proofs_verify_only_their_leaf(
+        accounts in prop::collection::vec(any::<[u8; 20]>().prop_map(Address::from), 1..20),
+    ) {
+        let leaves: Vec<B256> = accounts.iter().copied().map(address_leaf).collect();
+        let (root, proofs) = proofs(&leaves);
+        for (leaf, proof) in leaves.iter().zip(&proofs) {
+            prop_assert!(verify(proof, root, *leaf));
+        }
+
+        let outsider = address_leaf(Address::ZERO);
+        prop_assume!(!leaves.contains(&outsider));
+        prop_assert!(!verify(&proofs[0], root, outsider));
+    }
+}
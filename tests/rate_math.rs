@@ -0,0 +1,104 @@
+//!
+//! Property tests for swap rate math invariants.
+//!
+
+use proptest::prelude::*;
+use stylus_dorg_task::{
+    math::{amount_in, amount_out, mul_div, Rounding},
+    BPS, MAX_MARKET_FEE_BPS,
+};
+use stylus_sdk::alloy_primitives::U256;
+
+fn any_u256() -> impl Strategy<Value = U256> {
+    any::<[u64; 4]>().prop_map(U256::from_limbs)
+}
+
+/// Amounts and rates small enough that a round trip does not overflow.
+fn any_u128() -> impl Strategy<Value = U256> {
+    any::<u128>().prop_map(U256::from)
+}
+
+fn any_rate() -> impl Strategy<Value = U256> {
+    (1..=u128::MAX).prop_map(U256::from)
+}
+
+fn any_fee_bps() -> impl Strategy<Value = u16> {
+    0..=MAX_MARKET_FEE_BPS
+}
+
+proptest! {
+    #[test]
+    fn base_quote_base_round_trip_never_mints(
+        base_amount in any_u128(),
+        rate in any_rate(),
+        fee_bps in any_fee_bps(),
+    ) {
+        let quote_amount = amount_out(base_amount, rate, U256::from(1), fee_bps).unwrap();
+        let base_back = amount_out(quote_amount, U256::from(1), rate, fee_bps).unwrap();
+        prop_assert!(base_back <= base_amount);
+    }
+
+    #[test]
+    fn quote_base_quote_round_trip_never_mints(
+        quote_amount in any_u128(),
+        rate in any_rate(),
+        fee_bps in any_fee_bps(),
+    ) {
+        let base_amount = amount_out(quote_amount, U256::from(1), rate, fee_bps).unwrap();
+        let quote_back = amount_out(base_amount, rate, U256::from(1), fee_bps).unwrap();
+        prop_assert!(quote_back <= quote_amount);
+    }
+
+    #[test]
+    fn fee_never_exceeds_configured_bps(
+        amount in any_u256(),
+        numerator in any_u256(),
+        denominator in any_u256(),
+        fee_bps in any_fee_bps(),
+    ) {
+        let gross = mul_div(amount, numerator, denominator, Rounding::Down);
+        let net = amount_out(amount, numerator, denominator, fee_bps);
+        prop_assert_eq!(gross.is_some(), net.is_some());
+
+        if let (Some(gross), Some(net)) = (gross, net) {
+            // The fee is at most the configured share of the gross amount plus one unit of rounding.
+            let fee = gross - net;
+            let exact_fee =
+                mul_div(gross, U256::from(fee_bps), U256::from(BPS), Rounding::Down).unwrap();
+            prop_assert!(fee <= exact_fee + U256::from(1));
+            prop_assert!(net <= gross);
+        }
+    }
+
+    #[test]
+    fn exact_out_input_covers_its_output(
+        amount in any_u128(),
+        rate in any_rate(),
+        fee_bps in any_fee_bps(),
+        base_for_quote in any::<bool>(),
+    ) {
+        let (numerator, denominator) = if base_for_quote {
+            (rate, U256::from(1))
+        } else {
+            (U256::from(1), rate)
+        };
+
+        if let Some(needed) = amount_in(amount, numerator, denominator, fee_bps) {
+            let received = amount_out(needed, numerator, denominator, fee_bps).unwrap();
+            prop_assert!(received >= amount);
+        }
+    }
+
+    #[test]
+    fn rate_math_never_panics(
+        a in any_u256(),
+        b in any_u256(),
+        denominator in any_u256(),
+        fee_bps in any::<u16>(),
+    ) {
+        let _ = mul_div(a, b, denominator, Rounding::Down);
+        let _ = mul_div(a, b, denominator, Rounding::Up);
+        let _ = amount_out(a, b, denominator, fee_bps);
+        let _ = amount_in(a, b, denominator, fee_bps);
+    }
+}
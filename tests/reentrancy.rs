@@ -0,0 +1,220 @@
+//!
+//! Reentrancy guard checks.
+//!
+//! Tokens with transfer hooks rely on the SDK rejecting reentrant calls, which the
+//! `reentrant` feature of `stylus-sdk` would turn off.
+//!
+//! The devnode test lists a market of two `tests/reference/ReentrantToken.sol` deployments and
+//! has the base token call back into the contract from swaps, deposits and withdrawals. Deploy
+//! both tokens from the key's account and a fresh, initialized market contract, then run:
+//!
+//! ```bash
+//! RPC_URL=http://localhost:8547 PRIVATE_KEY_PATH=... STYLUS_CONTRACT_ADDRESS=0x... \
+//! BASE_TOKEN_ADDRESS=0x... QUOTE_TOKEN_ADDRESS=0x... \
+//! cargo test --test reentrancy -- --ignored
+//! ```
+//!
+
+use dotenv::dotenv;
+use ethers::{
+    middleware::SignerMiddleware,
+    prelude::abigen,
+    providers::{Http, Middleware, Provider},
+    signers::{LocalWallet, Signer},
+    types::{Address, Bytes, U256},
+};
+use eyre::eyre;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Your private key file path.
+const PRIVATE_KEY_PATH: &str = "PRIVATE_KEY_PATH";
+
+/// Stylus RPC endpoint url.
+const RPC_URL: &str = "RPC_URL";
+
+/// Deployed program addresses.
+const STYLUS_CONTRACT_ADDRESS: &str = "STYLUS_CONTRACT_ADDRESS";
+const BASE_TOKEN_ADDRESS: &str = "BASE_TOKEN_ADDRESS";
+const QUOTE_TOKEN_ADDRESS: &str = "QUOTE_TOKEN_ADDRESS";
+
+abigen!(
+    FixedRate,
+    r#"[
+        function createMarket(address base_token, address quote_token, uint256 exchange_rate) external returns (uint256)
+        function depositLiquidity(uint256 market_index, uint256 base_amount, uint256 quote_amount) external
+        function withdrawLiquidity(uint256 market_index, uint256 base_amount, uint256 quote_amount) external
+        function swapBaseTokenForQuoteToken(address base_token, address quote_token, uint256 base_amount) external
+        function fetchCurrentMarketIndex() external view returns (uint256)
+    ]"#
+);
+
+abigen!(
+    ReentrantToken,
+    r#"[
+        function approve(address,uint256) external returns (bool)
+        function arm(address target, bytes calldata data) external
+        function reentered() external view returns (bool)
+        function reentrySucceeded() external view returns (bool)
+        function reentryResult() external view returns (bytes)
+    ]"#
+);
+
+#[test]
+fn sdk_reentrancy_guard_stays_on() {
+    let manifest = include_str!("../Cargo.toml");
+    let sdk = manifest
+        .lines()
+        .find(|line| line.starts_with("stylus-sdk"))
+        .unwrap();
+
+    assert!(!sdk.contains("reentrant"), "{sdk}");
+    assert!(!manifest.contains("stylus-sdk/reentrant"));
+}
+
+#[tokio::test]
+#[ignore = "requires a devnode with the contract and both reentrant tokens deployed"]
+async fn token_callbacks_can_not_reenter() -> eyre::Result<()> {
+    // Load environment variables
+    dotenv().ok();
+
+    let private_key_path = std::env::var(PRIVATE_KEY_PATH)
+        .map_err(|_| eyre!("No {} env var set", PRIVATE_KEY_PATH))?;
+    let private_key = read_secret_from_file(&private_key_path)?;
+    let rpc_url = std::env::var(RPC_URL).map_err(|_| eyre!("No {} env var set", RPC_URL))?;
+    let stylus_address: Address = std::env::var(STYLUS_CONTRACT_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", STYLUS_CONTRACT_ADDRESS))?
+        .parse()?;
+    let base_address: Address = std::env::var(BASE_TOKEN_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", BASE_TOKEN_ADDRESS))?
+        .parse()?;
+    let quote_address: Address = std::env::var(QUOTE_TOKEN_ADDRESS)
+        .map_err(|_| eyre!("No {} env var set", QUOTE_TOKEN_ADDRESS))?
+        .parse()?;
+
+    // Set up rpc client.
+    let wallet = LocalWallet::from_str(&private_key)?;
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let client = Arc::new(SignerMiddleware::new(
+        provider,
+        wallet.with_chain_id(chain_id),
+    ));
+
+    let stylus = FixedRate::new(stylus_address, client.clone());
+    let base_token = ReentrantToken::new(base_address, client.clone());
+    let quote_token = ReentrantToken::new(quote_address, client);
+
+    // List a one-to-one market and fund it while the tokens are disarmed.
+    for token in [&base_token, &quote_token] {
+        token
+            .arm(Address::zero(), Bytes::new())
+            .send()
+            .await?
+            .await?;
+        token
+            .approve(stylus_address, U256::MAX)
+            .send()
+            .await?
+            .await?;
+    }
+    stylus
+        .create_market(base_address, quote_address, U256::one())
+        .send()
+        .await?
+        .await?;
+    let market_index = stylus.fetch_current_market_index().call().await? - 1;
+    let liquidity = U256::exp10(21);
+    stylus
+        .deposit_liquidity(market_index, liquidity, liquidity)
+        .send()
+        .await?
+        .await?;
+
+    // Calls the base token makes from its transfer hook.
+    let amount = U256::exp10(18);
+    let reentries = [
+        (
+            "swap",
+            stylus
+                .swap_base_token_for_quote_token(base_address, quote_address, amount)
+                .calldata(),
+        ),
+        (
+            "deposit",
+            stylus
+                .deposit_liquidity(market_index, amount, amount)
+                .calldata(),
+        ),
+        (
+            "withdraw",
+            stylus
+                .withdraw_liquidity(market_index, amount, amount)
+                .calldata(),
+        ),
+    ];
+
+    for (reentry, data) in reentries {
+        let data = data.ok_or_else(|| eyre!("no {reentry} calldata"))?;
+
+        // Each operation moves base tokens, pulling them for swaps and deposits and pushing
+        // them for withdrawals.
+        let operations = [
+            (
+                "swap",
+                stylus.swap_base_token_for_quote_token(base_address, quote_address, amount),
+            ),
+            (
+                "deposit",
+                stylus.deposit_liquidity(market_index, amount, amount),
+            ),
+            (
+                "withdraw",
+                stylus.withdraw_liquidity(market_index, amount, amount),
+            ),
+        ];
+
+        for (operation, call) in operations {
+            println!("{operation} reentering {reentry}");
+            base_token
+                .arm(stylus_address, data.clone())
+                .send()
+                .await?
+                .await?;
+
+            // The outer call goes through, the token records the rejected callback.
+            let receipt = call
+                .send()
+                .await?
+                .await?
+                .ok_or_else(|| eyre!("no {operation} receipt"))?;
+            assert_eq!(receipt.status, Some(1.into()), "{operation} reverted");
+            assert!(
+                base_token.reentered().call().await?,
+                "{operation} made no token call"
+            );
+            assert!(
+                !base_token.reentry_succeeded().call().await?,
+                "{operation} let {reentry} reenter"
+            );
+
+            // The SDK guard reverts without data, unlike the contract's own errors.
+            assert_eq!(
+                base_token.reentry_result().call().await?,
+                Bytes::new(),
+                "{operation} rejected {reentry} past the guard"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn read_secret_from_file(fpath: &str) -> eyre::Result<String> {
+    let f = std::fs::File::open(fpath)?;
+    let mut buf_reader = BufReader::new(f);
+    let mut secret = String::new();
+    buf_reader.read_line(&mut secret)?;
+    Ok(secret.trim().to_string())
+}
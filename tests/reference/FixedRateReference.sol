@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: MIT-OR-APACHE-2.0
+pragma solidity ^0.8.23;
+
+/// @title Fixed-rate reference
+/// @notice Solidity reference of the market creation, fee and quote logic of the Stylus contract.
+/// @dev `tests/differential.rs` deploys both and cross-checks outputs, reverts and events.
+/// Keep names, error types and event signatures equal to `src/lib.rs`.
+contract FixedRateReference {
+    uint16 public constant BPS = 10_000;
+    uint16 public constant MAX_MARKET_FEE_BPS = 1_000;
+
+    struct Market {
+        address baseToken;
+        address quoteToken;
+        uint256 exchangeRate;
+        uint16 feeBps;
+        address creator;
+    }
+
+    uint64 internal marketIndex = 1;
+    mapping(uint64 => Market) internal markets;
+    mapping(address => mapping(address => uint64)) internal indexes;
+
+    event MarketCreated(address indexed base_token, address indexed quote_token, uint256 exchange_rate, uint256 indexed market_index, address creator);
+    event MarketFeeSet(uint256 indexed market_index, uint16 previous_fee_bps, uint16 new_fee_bps);
+    event EffectiveRateChanged(uint256 indexed market_index, uint256 rate, uint8 reason);
+
+    error BaseTokenCanNotBeZeroAddress();
+    error QuoteTokenCanNotBeZeroAddress();
+    error ExchangeRateCanNotBeZero();
+    error MultiplicationOverflow();
+    error OutOfBoundIndex();
+    error NotMarketCreator();
+    error InvalidSwapDirection();
+    error FeeTooHigh(uint16 fee_bps, uint16 max_fee_bps);
+
+    function createMarket(address base_token, address quote_token, uint256 exchange_rate)
+        external
+        returns (uint256)
+    {
+        if (exchange_rate == 0) revert ExchangeRateCanNotBeZero();
+        if (base_token == address(0)) revert BaseTokenCanNotBeZeroAddress();
+        if (quote_token == address(0)) revert QuoteTokenCanNotBeZeroAddress();
+
+        uint64 index = marketIndex;
+        markets[index] = Market(base_token, quote_token, exchange_rate, 0, msg.sender);
+        // A pair with several markets keeps the oldest as its primary market.
+        if (indexes[base_token][quote_token] == 0) indexes[base_token][quote_token] = index;
+        marketIndex = index + 1;
+
+        emit MarketCreated(base_token, quote_token, exchange_rate, index, msg.sender);
+        // A new market publishes its rate with the `Manual` reason.
+        emit EffectiveRateChanged(index, exchange_rate, 0);
+
+        return index;
+    }
+
+    function setMarketFee(uint256 market_index, uint16 fee_bps) external {
+        Market storage market = markets[marketKey(market_index)];
+        if (market.creator == address(0) || market.creator != msg.sender) revert NotMarketCreator();
+        if (fee_bps > MAX_MARKET_FEE_BPS) revert FeeTooHigh(fee_bps, MAX_MARKET_FEE_BPS);
+
+        uint16 previousFeeBps = market.feeBps;
+        market.feeBps = fee_bps;
+
+        emit MarketFeeSet(market_index, previousFeeBps, fee_bps);
+    }
+
+    function fetchCurrentMarketIndex() external view returns (uint256) {
+        return marketIndex;
+    }
+
+    function quoteExactIn(uint256 market_index, uint8 direction, uint256 amount_in)
+        external
+        view
+        returns (uint256)
+    {
+        (uint256 numerator, uint256 denominator, uint16 feeBps) = directionRate(market_index, direction);
+        uint256 grossAmountOut = mulDiv(amount_in, numerator, denominator, false);
+        return grossAmountOut - mulDiv(grossAmountOut, feeBps, BPS, true);
+    }
+
+    function quoteExactOut(uint256 market_index, uint8 direction, uint256 amount_out)
+        external
+        view
+        returns (uint256)
+    {
+        (uint256 numerator, uint256 denominator, uint16 feeBps) = directionRate(market_index, direction);
+        uint256 grossAmountOut = mulDiv(amount_out, BPS, BPS - feeBps, true);
+        return mulDiv(grossAmountOut, denominator, numerator, true);
+    }
+
+    /// @dev Rate of a swap direction as (numerator, denominator), and the market fee.
+    function directionRate(uint256 market_index, uint8 direction)
+        internal
+        view
+        returns (uint256, uint256, uint16)
+    {
+        Market storage market = markets[marketKey(market_index)];
+        if (market.creator == address(0)) revert OutOfBoundIndex();
+
+        if (direction == 0) return (market.exchangeRate, 1, market.feeBps);
+        if (direction == 1) return (1, market.exchangeRate, market.feeBps);
+        revert InvalidSwapDirection();
+    }
+
+    /// @dev Narrow an external market index to its storage key, matching `market_key`.
+    function marketKey(uint256 market_index) internal pure returns (uint64) {
+        if (market_index > type(uint64).max) revert OutOfBoundIndex();
+        return uint64(market_index);
+    }
+
+    /// @dev `x * y / denominator` with a 512-bit intermediate product, see OpenZeppelin `Math.mulDiv`.
+    function mulDiv(uint256 x, uint256 y, uint256 denominator, bool roundUp)
+        internal
+        pure
+        returns (uint256 result)
+    {
+        unchecked {
+            uint256 prod0 = x * y;
+            uint256 prod1;
+            assembly {
+                let mm := mulmod(x, y, not(0))
+                prod1 := sub(sub(mm, prod0), lt(mm, prod0))
+            }
+
+            if (prod1 == 0) {
+                result = prod0 / denominator;
+            } else {
+                if (denominator <= prod1) revert MultiplicationOverflow();
+
+                uint256 remainder;
+                assembly {
+                    remainder := mulmod(x, y, denominator)
+                    prod1 := sub(prod1, gt(remainder, prod0))
+                    prod0 := sub(prod0, remainder)
+                }
+
+                uint256 d = denominator;
+                uint256 twos = d & (0 - d);
+                assembly {
+                    d := div(d, twos)
+                    prod0 := div(prod0, twos)
+                    twos := add(div(sub(0, twos), twos), 1)
+                }
+                prod0 |= prod1 * twos;
+
+                uint256 inverse = (3 * d) ^ 2;
+                inverse *= 2 - d * inverse;
+                inverse *= 2 - d * inverse;
+                inverse *= 2 - d * inverse;
+                inverse *= 2 - d * inverse;
+                inverse *= 2 - d * inverse;
+                inverse *= 2 - d * inverse;
+                result = prod0 * inverse;
+            }
+
+            if (roundUp && mulmod(x, y, denominator) > 0) {
+                if (result == type(uint256).max) revert MultiplicationOverflow();
+                result += 1;
+            }
+        }
+    }
+}
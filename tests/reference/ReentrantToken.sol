@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: MIT-OR-APACHE-2.0
+pragma solidity ^0.8.23;
+
+/// @title Reentrant token
+/// @notice ERC-20 whose transfers call back into a target once armed.
+/// @dev `tests/reentrancy.rs` arms it with swap, deposit and withdraw calldata and checks the
+/// Stylus contract rejects the reentrant call. The callback's outcome is recorded, not bubbled.
+contract ReentrantToken {
+    string public constant name = "Reentrant";
+    string public constant symbol = "REENTER";
+    uint8 public constant decimals = 18;
+
+    uint256 public totalSupply;
+    mapping(address => uint256) public balanceOf;
+    mapping(address => mapping(address => uint256)) public allowance;
+
+    address internal target;
+    bytes internal reentryData;
+
+    bool public reentered;
+    bool public reentrySucceeded;
+    bytes public reentryResult;
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+
+    constructor(uint256 supply) {
+        totalSupply = supply;
+        balanceOf[msg.sender] = supply;
+        emit Transfer(address(0), msg.sender, supply);
+    }
+
+    /// @notice Call `target` with `data` on the next transfer.
+    function arm(address target_, bytes calldata data) external {
+        target = target_;
+        reentryData = data;
+        reentered = false;
+        reentrySucceeded = false;
+        delete reentryResult;
+    }
+
+    function approve(address spender, uint256 value) external returns (bool) {
+        allowance[msg.sender][spender] = value;
+        emit Approval(msg.sender, spender, value);
+        return true;
+    }
+
+    function transfer(address to, uint256 value) external returns (bool) {
+        move(msg.sender, to, value);
+        return true;
+    }
+
+    function transferFrom(address from, address to, uint256 value) external returns (bool) {
+        uint256 allowed = allowance[from][msg.sender];
+        if (allowed != type(uint256).max) allowance[from][msg.sender] = allowed - value;
+        move(from, to, value);
+        return true;
+    }
+
+    /// @dev Move the balance, then make the armed call once.
+    function move(address from, address to, uint256 value) internal {
+        balanceOf[from] -= value;
+        balanceOf[to] += value;
+        emit Transfer(from, to, value);
+
+        address callee = target;
+        if (callee == address(0)) return;
+        target = address(0);
+
+        (bool success, bytes memory result) = callee.call(reentryData);
+        reentered = true;
+        reentrySucceeded = success;
+        reentryResult = result;
+    }
+}
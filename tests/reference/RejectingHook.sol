@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: MIT-OR-APACHE-2.0
+pragma solidity ^0.8.23;
+
+/// @title Rejecting hook
+/// @notice Market hook that accepts every swap in `beforeSwap` and reverts in `afterSwap`.
+/// @dev `tests/hooks.rs` registers it on the best market of a pair and checks `swapBest`
+/// reverts instead of skipping a market whose fill was already recorded.
+contract RejectingHook {
+    error Rejected();
+
+    function beforeSwap(uint256, address, uint8, uint256, uint16 fee_bps)
+        external
+        pure
+        returns (uint16)
+    {
+        return fee_bps;
+    }
+
+    function afterSwap(uint256, address, uint8, uint256, uint256) external pure {
+        revert Rejected();
+    }
+
+    function beforeLiquidity(uint256, address, bool, uint256, uint256) external pure {}
+}
@@ -0,0 +1,135 @@
+//!
+//! Checks that revert data decodes back to the error it was encoded from.
+//!
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{Panic, Revert as RevertReason, SolError};
+use stylus_dorg_task::{
+    revert::Revert, ContractError, ExternalCallFailed, FeeTooHigh, MarketExists, RateChanged,
+};
+use stylus_sdk::keccak_const::Keccak256;
+
+/// Error signatures and argument types in the ABI snapshot.
+fn snapshot_errors() -> Vec<(String, Vec<String>)> {
+    let snapshot = include_str!("snapshots/IContract.sol");
+    snapshot
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("error "))
+        .map(|error| {
+            let (name, rest) = error.split_once('(').unwrap();
+            let (params, _) = rest.split_once(')').unwrap();
+            let types: Vec<String> = params
+                .split(',')
+                .filter_map(|param| param.split_whitespace().next())
+                .map(String::from)
+                .collect();
+            (format!("{name}({})", types.join(",")), types)
+        })
+        .collect()
+}
+
+/// A sample of contract errors, with and without arguments.
+fn sample_errors() -> [ContractError; 3] {
+    [
+        ContractError::MarketExists(MarketExists {}),
+        ContractError::RateChanged(RateChanged {
+            expected: U256::from(3),
+            actual: U256::from(4),
+        }),
+        ContractError::FeeTooHigh(FeeTooHigh {
+            fee_bps: 2_000,
+            max_fee_bps: 1_000,
+        }),
+    ]
+}
+
+#[test]
+fn contract_errors_round_trip() {
+    for (error, expected) in sample_errors().into_iter().zip(sample_errors()) {
+        let data = Vec::<u8>::from(error);
+        assert_eq!(Revert::decode(&data), Revert::Contract(expected));
+    }
+}
+
+#[test]
+fn every_snapshot_error_decodes() {
+    for (signature, types) in snapshot_errors() {
+        let digest = Keccak256::new().update(signature.as_bytes()).finalize();
+        let mut data = digest[..4].to_vec();
+
+        // Zero static arguments, dynamic ones point at a shared empty tail.
+        let is_dynamic = |ty: &String| ty == "bytes" || ty == "string" || ty.ends_with("[]");
+        let tail = U256::from(32 * types.len());
+        for ty in &types {
+            let word = if is_dynamic(ty) { tail } else { U256::ZERO };
+            data.extend(word.to_be_bytes::<32>());
+        }
+        if types.iter().any(is_dynamic) {
+            data.extend([0; 32]);
+        }
+
+        assert!(
+            matches!(Revert::decode(&data), Revert::Contract(_)),
+            "{signature} does not decode to a contract error"
+        );
+    }
+}
+
+#[test]
+fn reasons_and_panics_decode() {
+    let reason = RevertReason::from("ERC20: insufficient allowance").abi_encode();
+    assert_eq!(
+        Revert::decode(&reason),
+        Revert::Reason("ERC20: insufficient allowance".into())
+    );
+
+    let panic = Panic {
+        code: U256::from(0x11),
+    }
+    .abi_encode();
+    assert_eq!(Revert::decode(&panic), Revert::Panic(U256::from(0x11)));
+}
+
+#[test]
+fn external_call_failures_keep_the_token_revert() {
+    let token = Address::repeat_byte(0x11);
+    let reason = RevertReason::from("ERC20: transfer amount exceeds balance").abi_encode();
+    let data = Vec::<u8>::from(ContractError::ExternalCallFailed(ExternalCallFailed {
+        target: token,
+        revert_data: reason.into(),
+    }));
+
+    assert_eq!(
+        Revert::decode(&data).external_call(),
+        Some((
+            token,
+            Revert::Reason("ERC20: transfer amount exceeds balance".into())
+        ))
+    );
+    assert_eq!(
+        Revert::Contract(ContractError::MarketExists(MarketExists {})).external_call(),
+        None
+    );
+}
+
+#[test]
+fn unknown_data_is_kept() {
+    let data = vec![0xde, 0xad, 0xbe, 0xef];
+    let revert = Revert::decode(&data);
+
+    assert_eq!(revert, Revert::Unknown(data));
+    assert_eq!(revert.to_string(), "0xdeadbeef");
+}
+
+#[test]
+fn display_is_human_readable() {
+    let revert = Revert::Contract(ContractError::RateChanged(RateChanged {
+        expected: U256::from(3),
+        actual: U256::from(4),
+    }));
+
+    assert_eq!(
+        revert.to_string(),
+        "RateChanged(RateChanged { expected: 3, actual: 4 })"
+    );
+}
@@ -0,0 +1,91 @@
+//!
+//! Checks `selectors::ALL` against the signatures in the ABI snapshot.
+//!
+
+use stylus_dorg_task::selectors;
+use stylus_sdk::keccak_const::Keccak256;
+
+/// Function signatures in the ABI snapshot, in order.
+fn snapshot_signatures() -> Vec<String> {
+    let snapshot = include_str!("snapshots/IContract.sol");
+    snapshot
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("function "))
+        .map(|function| {
+            let (name, rest) = function.split_once('(').unwrap();
+            let types: Vec<String> = split_params(rest)
+                .into_iter()
+                .filter_map(param_type)
+                .collect();
+            format!("{name}({})", types.join(","))
+        })
+        .collect()
+}
+
+/// Type of a parameter, None for an empty parameter list.
+///
+/// Tuple types may be printed with spaces after their commas, which signatures drop.
+fn param_type(param: &str) -> Option<String> {
+    let param = param.trim();
+    let end = param.rfind(')').map_or(0, |i| i + 1);
+    let (tuple, rest) = param.split_at(end);
+    let suffix = rest
+        .split_whitespace()
+        .next()
+        .filter(|suffix| tuple.is_empty() || suffix.starts_with('['));
+    let ty = format!("{}{}", tuple.replace(' ', ""), suffix.unwrap_or_default());
+    (!ty.is_empty()).then_some(ty)
+}
+
+/// Split the parameters before the closing parenthesis, keeping tuple types whole.
+fn split_params(rest: &str) -> Vec<&str> {
+    let mut params = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                params.push(&rest[start..i]);
+                break;
+            }
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                params.push(&rest[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    params
+}
+
+#[test]
+fn selectors_match_abi_snapshot() {
+    let expected: Vec<[u8; 4]> = snapshot_signatures()
+        .iter()
+        .map(|signature| {
+            let digest = Keccak256::new().update(signature.as_bytes()).finalize();
+            digest[..4].try_into().unwrap()
+        })
+        .collect();
+
+    assert_eq!(selectors::ALL.to_vec(), expected);
+}
+
+#[test]
+fn selectors_are_unique() {
+    let mut all = selectors::ALL.to_vec();
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), selectors::ALL.len());
+}
+
+#[test]
+fn known_selectors() {
+    // Selectors shared with well-known interfaces.
+    assert_eq!(selectors::NAME, [0x06, 0xfd, 0xde, 0x03]);
+    assert_eq!(selectors::DOMAIN_SEPARATOR, [0x36, 0x44, 0xe5, 0x15]);
+    assert_eq!(selectors::TRANSFER_OWNERSHIP, [0xf2, 0xfd, 0xe3, 0x8b]);
+    assert_eq!(selectors::ON_TRANSFER_RECEIVED, stylus_dorg_task::ERC1363_RECEIVED);
+}
@@ -0,0 +1,722 @@
+// SPDX-License-Identifier: MIT-OR-APACHE-2.0
+pragma solidity ^0.8.23;
+
+interface IContract {
+    function initialize() external;
+
+    function initialize(address owner, address fee_recipient, uint16 protocol_fee_bps, uint8 creation_mode) external;
+
+    function createMarket(address base_token, address quote_token, uint256 exchange_rate) external payable returns (uint256);
+
+    function createMarketWithFeeTier(address base_token, address quote_token, uint256 exchange_rate, uint64 tier_id) external payable returns (uint256);
+
+    function createMarketWithSalt(address base_token, address quote_token, uint256 exchange_rate, bytes32 salt) external payable returns (uint256);
+
+    function createAndFund(address base_token, address quote_token, uint256 exchange_rate, uint256 base_amount, uint256 quote_amount) external payable returns (uint256);
+
+    function previewCreateMarket(address base_token, address quote_token, uint256 exchange_rate, uint256 base_amount, uint256 quote_amount) external view returns (uint256, uint256, uint8, uint8);
+
+    function depositLiquidity(uint256 market_index, uint256 base_amount, uint256 quote_amount) external;
+
+    function withdrawLiquidity(uint256 market_index, uint256 base_amount, uint256 quote_amount) external;
+
+    function withdrawLiquidity(uint256 market_index, uint256 base_amount, uint256 quote_amount, address recipient) external;
+
+    function transferReserves(uint256 from_market, uint256 to_market, address token, uint256 amount) external;
+
+    function setWithdrawalDelay(uint256 market_index, uint64 delay, bool auto_pause) external;
+
+    function fetchWithdrawalDelay(uint256 market_index) external view returns (uint64, bool);
+
+    function requestWithdrawal(uint256 market_index, uint256 base_amount, uint256 quote_amount) external;
+
+    function executeWithdrawal(uint256 market_index) external;
+
+    function executeWithdrawal(uint256 market_index, address recipient) external;
+
+    function cancelWithdrawal(uint256 market_index) external;
+
+    function fetchPendingWithdrawal(uint256 market_index) external view returns (uint256, uint256, uint64);
+
+    function setMinLockedLiquidity(uint256 amount) external;
+
+    function fetchMinLockedLiquidity() external view returns (uint256);
+
+    function lockLiquidity(uint256 market_index, uint256 amount) external;
+
+    function fetchLockedLiquidity(uint256 market_index) external view returns (uint256);
+
+    function swapBaseTokenForQuoteToken(address base_token, address quote_token, uint256 base_amount) external;
+
+    function swapBaseTokenForQuoteToken(address base_token, address quote_token, uint256 base_amount, uint256 expected_rate) external;
+
+    function swapQuoteTokenForBaseToken(address base_token, address quote_token, uint256 quote_amount) external;
+
+    function swapQuoteTokenForBaseToken(address base_token, address quote_token, uint256 quote_amount, uint256 expected_rate) external;
+
+    function swapBaseTokenForQuoteTokenSubmittedAt(address base_token, address quote_token, uint256 base_amount, uint64 submitted_at) external;
+
+    function swapQuoteTokenForBaseTokenSubmittedAt(address base_token, address quote_token, uint256 quote_amount, uint64 submitted_at) external;
+
+    function setMaxSwapAge(uint64 max_age) external;
+
+    function fetchMaxSwapAge() external view returns (uint64);
+
+    function swapBest(address base_token, address quote_token, uint8 direction, uint256 amount_in, uint256 min_amount_out) external returns (uint256);
+
+    function swapSplit(uint8 direction, (uint256,uint256)[] memory legs, uint256 min_total_out) external returns (uint256);
+
+    function swapWithVoucher((uint256, address, uint256, uint16, uint256, uint256, uint64) voucher, uint8 direction, uint256 amount_in, uint256 min_amount_out, bytes calldata signature) external returns (uint256);
+
+    function fetchVoucherUsed(address creator, uint256 nonce) external view returns (bool);
+
+    function swapViaUnderlying(uint256 market_index, uint8 direction, uint256 amount_in, uint256 min_amount_out) external returns (uint256);
+
+    function swapAndCall(uint256 market_index, uint8 direction, uint256 amount_in, uint256 min_amount_out, address callback_target, bytes calldata data) external returns (uint256);
+
+    function onTransferReceived(address _operator, address from, uint256 value, bytes calldata data) external returns (bytes4);
+
+    function commitSwap(bytes32 commitment) external payable;
+
+    function revealAndExecute(uint256 market_index, uint8 direction, uint256 amount, bytes32 salt) external;
+
+    function cancelSwapCommitment(bytes32 commitment) external;
+
+    function computeSwapCommitment(address committer, uint256 market_index, uint8 direction, uint256 amount, bytes32 salt) external pure returns (bytes32);
+
+    function fetchSwapCommitment(bytes32 commitment) external view returns (address, uint256, uint64);
+
+    function placeConditionalOrder(uint256 market_index, uint8 direction, uint8 condition, uint256 trigger_rate, uint256 amount_in, uint256 min_amount_out, uint64 expires_at, uint256 min_fill) external returns (uint256);
+
+    function cancelConditionalOrder(uint256 order_id) external;
+
+    function fillConditionalOrder(uint256 order_id) external returns (uint256);
+
+    function fillConditionalOrderPartial(uint256 order_id, uint256 amount_in) external returns (uint256);
+
+    function settleOrders(uint256[] memory order_ids) external returns (uint256);
+
+    function fetchConditionalOrder(uint256 order_id) external view returns (address, uint256, uint8, uint8, uint256, uint256, uint256, uint64, uint256);
+
+    function fetchConditionalOrderTriggered(uint256 order_id) external view returns (bool);
+
+    function fetchConditionalOrderCount() external view returns (uint256);
+
+    function fetchOpenOrders(uint256 market_index, uint64 offset, uint64 limit) external view returns (uint256[] memory);
+
+    function cancelExpiredOrders(uint256[] memory order_ids) external returns (uint256);
+
+    function setKeeperTipBounds(uint16 min_bps, uint16 max_bps) external;
+
+    function fetchKeeperTipBounds() external view returns (uint16, uint16);
+
+    function setMarketKeeperTip(uint256 market_index, uint8 mode, uint256 amount) external;
+
+    function fetchMarketKeeperTip(uint256 market_index) external view returns (uint8, uint256);
+
+    function fetchConditionalOrderTip(uint256 order_id) external view returns (uint256);
+
+    function quoteExactIn(uint256 market_index, uint8 direction, uint256 amount_in) external view returns (uint256);
+
+    function quoteBatch((uint256,uint8,uint256)[] memory requests) external view returns (uint256[] memory, bool[] memory);
+
+    function quoteExactOut(uint256 market_index, uint8 direction, uint256 amount_out) external view returns (uint256);
+
+    function checkSwapPreconditions(address user, uint256 market_index, uint8 direction, uint256 amount) external view returns (uint16);
+
+    function quoteBothDirections(uint256 market_index, uint256 amount) external view returns (uint256, uint256, uint256);
+
+    function fetchInitializationStatus() external view returns (bool);
+
+    function initializedVersion() external view returns (uint64);
+
+    function name() external view returns (string memory);
+
+    function version() external view returns (string memory);
+
+    function implementationInfo() external view returns (string memory, string memory, string memory, uint64, uint64, uint64);
+
+    function fetchConfig() external view returns (address, address, uint64, bool, bytes32, uint16, uint256, uint16, address, address, bool, uint8);
+
+    function DOMAIN_SEPARATOR() external view returns (bytes32);
+
+    function nonces(address owner) external view returns (uint256);
+
+    function fetchCurrentMarketIndex() external view returns (uint256);
+
+    function fetchExchangeRate(address base_token, address quote_token) external view returns (uint256);
+
+    function fetchMarketId(address base_token, address quote_token) external view returns (uint256);
+
+    function fetchMarketByTokens(address base_token, address quote_token) external view returns (address, address, uint256, uint8);
+
+    function fetchMarketById(uint256 market_index) external view returns (address, address, uint256, uint8, bytes32, bytes32);
+
+    function fetchMarketsEnriched(uint64 offset, uint64 limit) external view returns (uint256,address,address,uint8,uint8,uint256,uint256,uint256,uint8,uint8,uint16,uint16,uint256,uint256,address)[] memory;
+
+    function fetchMarketStatus(uint256 market_index) external view returns (uint8);
+
+    function fetchMarketHealth(uint256 market_index) external view returns (bool, bool, bool, bool, bool);
+
+    function fetchHealth() external view returns (uint64, uint64, uint64, uint64);
+
+    function pauseMarket(uint256 market_index) external;
+
+    function unpauseMarket(uint256 market_index) external;
+
+    function flagMarket(uint256 market_index, bytes32 reason_hash) external;
+
+    function resolveFlag(uint256 market_index, bool reinstate) external;
+
+    function fetchMarketFlag(uint256 market_index) external view returns (bytes32);
+
+    function setMarketExpiry(uint256 market_index, uint64 expires_at) external;
+
+    function setMarketFee(uint256 market_index, uint16 fee_bps) external;
+
+    function fetchMarketFee(uint256 market_index) external view returns (uint16);
+
+    function setFeeTier(uint64 tier_id, uint16 fee_bps) external;
+
+    function fetchFeeTier(uint64 tier_id) external view returns (bool, uint16);
+
+    function setMarketFeeTier(uint256 market_index, uint64 tier_id) external;
+
+    function fetchMarketFeeTier(uint256 market_index) external view returns (uint64);
+
+    function setMarketMakerFee(uint256 market_index, uint16 fee_bps) external;
+
+    function setMarketDepositTolerance(uint256 market_index, bool enabled, uint16 tolerance_bps) external;
+
+    function fetchMarketDepositTolerance(uint256 market_index) external view returns (bool, uint16);
+
+    function fetchMarketFeeSchedule(uint256 market_index) external view returns (uint16, uint16, uint16);
+
+    function setHookApproval(address hook, bool approved) external;
+
+    function fetchIsApprovedHook(address hook) external view returns (bool);
+
+    function setHookGasLimit(uint64 gas_limit) external;
+
+    function fetchHookGasLimit() external view returns (uint64);
+
+    function setMarketHook(uint256 market_index, address hook) external;
+
+    function fetchMarketHook(uint256 market_index) external view returns (address);
+
+    function setMarketComplianceRegistry(uint256 market_index, address registry) external;
+
+    function fetchMarketComplianceRegistry(uint256 market_index) external view returns (address);
+
+    function setMarketEoaOnly(uint256 market_index, bool enabled) external;
+
+    function fetchMarketEoaOnly(uint256 market_index) external view returns (bool);
+
+    function setMarketDirectionMode(uint256 market_index, uint8 mode) external;
+
+    function fetchMarketDirectionMode(uint256 market_index) external view returns (uint8);
+
+    function setMarketContractAllowed(uint256 market_index, address account, bool allowed) external;
+
+    function fetchMarketContractAllowed(uint256 market_index, address account) external view returns (bool);
+
+    function setMarketRecipientRestricted(uint256 market_index, bool enabled) external;
+
+    function fetchMarketRecipientRestricted(uint256 market_index) external view returns (bool);
+
+    function setMarketCustodianAllowed(uint256 market_index, address custodian, bool allowed) external;
+
+    function fetchMarketCustodianAllowed(uint256 market_index, address custodian) external view returns (bool);
+
+    function setMarketRateBounds(uint256 market_index, uint256 min_rate, uint256 max_rate) external;
+
+    function fetchMarketRateBounds(uint256 market_index) external view returns (uint256, uint256);
+
+    function setMarketRateLimit(uint256 market_index, uint16 max_change_bps, uint16 max_window_change_bps, uint64 window) external;
+
+    function fetchMarketRateLimit(uint256 market_index) external view returns (uint16, uint16, uint64);
+
+    function grantRateOperator(uint256 market_index, address operator) external;
+
+    function revokeRateOperator(uint256 market_index) external;
+
+    function fetchRateOperator(uint256 market_index) external view returns (address);
+
+    function transferMarketCreator(uint256 market_index, address new_creator) external;
+
+    function acceptMarketCreator(uint256 market_index) external;
+
+    function fetchPendingMarketCreator(uint256 market_index) external view returns (address);
+
+    function setPresale(uint256 market_index, uint64 ends_at, uint256 presale_rate, uint256 public_rate, bytes32 merkle_root) external;
+
+    function setPresaleAllowlist(uint256 market_index, address[] memory accounts, bool allowed) external;
+
+    function provePresaleAccess(uint256 market_index, bytes32[] memory proof) external;
+
+    function fetchPresaleAllowed(uint256 market_index, address account) external view returns (bool);
+
+    function fetchPhase(uint256 market_index) external view returns (uint8, uint64, uint256, uint256, bytes32);
+
+    function setSale(uint256 market_index, uint256 soft_cap, uint256 hard_cap, uint64 ends_at) external;
+
+    function finalizeSale(uint256 market_index) external;
+
+    function claimRefund(uint256 market_index) external;
+
+    function fetchSale(uint256 market_index) external view returns (uint8, uint256, uint256, uint64, uint256);
+
+    function fetchContribution(uint256 market_index, address buyer) external view returns (uint256, uint256);
+
+    function setAirdropRoot(uint256 market_index, bytes32 root, uint256 amount, uint64 claim_deadline) external;
+
+    function claimAirdrop(uint256 market_index, uint256 amount, bytes32[] memory proof) external;
+
+    function sweepAirdrop(uint256 market_index) external;
+
+    function fetchAirdrop(uint256 market_index) external view returns (bytes32, uint256, uint256, uint64, bool);
+
+    function fetchAirdropClaimed(uint256 market_index, address account) external view returns (bool);
+
+    function scheduleRateUpdate(uint256 market_index, uint256 new_rate, uint64 effective_at) external;
+
+    function fetchPendingRate(uint256 market_index) external view returns (uint256, uint64);
+
+    function setRateDecay(uint256 market_index, uint8 mode, uint256 start_rate, uint256 floor_rate, uint64 start_time, uint64 duration) external;
+
+    function fetchRateDecay(uint256 market_index) external view returns (uint8, uint256, uint256, uint64, uint64);
+
+    function fetchCurrentRate(uint256 market_index) external view returns (uint256);
+
+    function fetchMarketDecimals(uint256 market_index) external view returns (uint8, uint8);
+
+    function refreshTokenMetadata(uint256 market_index) external;
+
+    function fetchMarketDecimalsCachedAt(uint256 market_index) external view returns (uint64);
+
+    function setMarketCommitReveal(uint256 market_index, bool enabled) external;
+
+    function fetchMarketCommitReveal(uint256 market_index) external view returns (bool);
+
+    function setMarketVaults(uint256 market_index, bool base_vault, bool quote_vault) external;
+
+    function fetchMarketVaults(uint256 market_index) external view returns (bool, bool);
+
+    function setMarketRebasing(uint256 market_index, bool enabled) external;
+
+    function fetchMarketRebasing(uint256 market_index) external view returns (bool, uint256, uint256);
+
+    function syncMarketReserves(uint256 market_index) external;
+
+    function fetchPairHistory(address base_token, address quote_token) external view returns (uint256[] memory);
+
+    function fetchBestMarket(address base_token, address quote_token, uint8 direction) external view returns (uint256);
+
+    function fetchMarketReserves(uint256 market_index) external view returns (uint256, uint256);
+
+    function fetchTvl(address token) external view returns (uint256);
+
+    function fetchTvlAll(uint64 offset, uint64 limit) external view returns (address[] memory, uint256[] memory);
+
+    function fetchAccounting(uint256 market_index) external view returns ((address, uint256, uint256, uint256, uint256, uint256, uint256, uint256, uint256), (address, uint256, uint256, uint256, uint256, uint256, uint256, uint256, uint256));
+
+    function verifySolvency(uint256 market_index) external returns (bool);
+
+    function setSolvencyConfig(uint16 tolerance_bps, uint256 bounty) external;
+
+    function fetchSolvencyConfig() external view returns (uint16, uint256);
+
+    function fetchUserStats(address user, uint256 market_index) external view returns (uint64, uint256, uint256, uint256, uint256);
+
+    function setPurchaseCap(uint256 market_index, uint256 cap) external;
+
+    function fetchPurchaseCap(uint256 market_index) external view returns (uint256);
+
+    function fetchPurchased(uint256 market_index, address user) external view returns (uint256);
+
+    function fetchRemainingAllocation(uint256 market_index, address user) external view returns (uint256);
+
+    function closeMarket(uint256 market_index) external;
+
+    function createBasket(address base_token, address[] memory quote_tokens, uint256[] memory rates, uint256 sold_cap) external returns (uint256);
+
+    function depositBasket(uint256 basket_id, uint256 base_amount) external;
+
+    function withdrawBasket(uint256 basket_id, address token, uint256 amount) external;
+
+    function buyFromBasket(uint256 basket_id, address quote_token, uint256 quote_amount, uint256 min_base_amount) external returns (uint256);
+
+    function fetchBasket(uint256 basket_id) external view returns (address, address, uint256, uint256, uint256, address[] memory);
+
+    function fetchBasketQuote(uint256 basket_id, address quote_token) external view returns (uint256, uint256);
+
+    function fetchMarketByMarketId(uint256 market_id) external view returns (address, address, uint256, uint8);
+
+    function computeMarketId(address base_token, address quote_token, bytes32 salt) external pure returns (uint256);
+
+    function computeSaltedMarketId(address creator, bytes32 salt) external pure returns (uint256);
+
+    function fetchMarketBySalt(address creator, bytes32 salt) external view returns (uint256);
+
+    function fetchDeterministicMarketIds() external view returns (bool, bytes32);
+
+    function setDeterministicMarketIds(bool enabled, bytes32 salt) external;
+
+    function fetchCreationMode() external view returns (uint8);
+
+    function setCreationMode(uint8 mode) external;
+
+    function fetchIsAllowedCreator(address creator) external view returns (bool);
+
+    function grantCreator(address creator) external;
+
+    function revokeCreator(address creator) external;
+
+    function fetchListingFee() external view returns (address, uint256);
+
+    function setListingFee(address token, uint256 amount) external;
+
+    function fetchListingFeeWaived(address account) external view returns (bool);
+
+    function setListingFeeWaiver(address account, bool waived) external;
+
+    function setProtocolFee(uint16 fee_bps) external;
+
+    function fetchProtocolFee() external view returns (uint16);
+
+    function MAX_PROTOCOL_FEE_BPS() external pure returns (uint16);
+
+    function setTreasuryConfig(address buyback_token, bool burn, address recipient) external;
+
+    function fetchTreasuryConfig() external view returns (address, bool, address);
+
+    function fetchTreasuryBalance(address token) external view returns (uint256);
+
+    function setFeeCollector(address collector) external;
+
+    function fetchFeeCollector() external view returns (address);
+
+    function setFeePushThreshold(address token, uint256 threshold) external;
+
+    function fetchFeePushThreshold(address token) external view returns (uint256);
+
+    function collectFees(address token) external returns (uint256);
+
+    function collectFees(address token, address recipient) external returns (uint256);
+
+    function withdrawTreasury(address token, address recipient, uint256 amount) external;
+
+    function buybackTreasury(uint256 market_index, uint256 amount_in, uint256 min_amount_out) external returns (uint256);
+
+    function setGuardian(address guardian, bool enabled) external;
+
+    function fetchIsGuardian(address account) external view returns (bool);
+
+    function setInsuranceShare(uint16 share_bps) external;
+
+    function fetchInsuranceShare() external view returns (uint16);
+
+    function fetchInsuranceBalance(address token) external view returns (uint256);
+
+    function setCreatorFeeShare(uint16 share_bps) external;
+
+    function fetchCreatorFeeShare() external view returns (uint16);
+
+    function fetchCreatorFees(address creator, address token) external view returns (uint256);
+
+    function claimCreatorFees(address token) external returns (uint256);
+
+    function claimCreatorFees(address token, address recipient) external returns (uint256);
+
+    function coverShortfall(uint256 market_index, address token, uint256 amount) external;
+
+    function transferOwnership(address new_owner) external;
+
+    function acceptOwnership() external;
+
+    function sunset() external;
+
+    function fetchSunset() external view returns (bool);
+
+    function addAdmin(address admin) external;
+
+    function removeAdmin(address admin) external;
+
+    function setAdminThreshold(uint64 threshold) external;
+
+    function proposeAdminAction(bytes calldata data) external returns (uint256);
+
+    function confirm(uint256 action_id) external;
+
+    function execute(uint256 action_id) external returns (bytes memory);
+
+    function setL1Governor(address governor) external;
+
+    function fetchL1Governor() external view returns (address);
+
+    function executeL1Message(bytes calldata data) external returns (bytes memory);
+
+    function fetchOwner() external view returns (address);
+
+    function fetchPendingOwner() external view returns (address);
+
+    function fetchAdmins() external view returns (address[] memory);
+
+    function fetchAdminThreshold() external view returns (uint64);
+
+    function fetchAdminAction(uint256 action_id) external view returns (address, bytes memory, bool, uint64);
+
+    function fetchSelectors() external pure returns (bytes4[] memory);
+
+    error AlreadyInitialized();
+
+    error MarketExists();
+
+    error BaseTokenCanNotBeZeroAddress();
+
+    error QuoteTokenCanNotBeZeroAddress();
+
+    error ExchangeRateCanNotBeZero();
+
+    error AmountCanNotBeZero();
+
+    error IncorrectBaseAmount();
+
+    error IncorrectQuoteAmount();
+
+    error DivisionUnderflow();
+
+    error MultiplicationOverflow();
+
+    error OutOfBoundIndex();
+
+    error NotOwner();
+
+    error NotPendingOwner();
+
+    error OwnerCanNotBeZeroAddress();
+
+    error NotAdmin();
+
+    error AdminCanNotBeZeroAddress();
+
+    error AdminExists();
+
+    error AdminNotFound();
+
+    error InvalidAdminThreshold();
+
+    error AdminActionNotFound();
+
+    error AdminActionAlreadyConfirmed();
+
+    error AdminActionAlreadyExecuted();
+
+    error InsufficientAdminConfirmations();
+
+    error InvalidAdminActionData();
+
+    error MarketNotFound();
+
+    error MarketNotActive(uint8);
+
+    error MarketNotPaused(uint8);
+
+    error NotMarketCreator();
+
+    error MarketAlreadyClosed();
+
+    error InsufficientLiquidity();
+
+    error BaseTokenTransferFailed();
+
+    error QuoteTokenTransferFailed();
+
+    error RateChanged(uint256, uint256);
+
+    error CommitRevealRequired();
+
+    error SwapCommitmentExists();
+
+    error SwapCommitmentNotFound();
+
+    error RevealTooEarly();
+
+    error InvalidSwapDirection();
+
+    error DepositRefundFailed();
+
+    error FeeTooHigh(uint16, uint16);
+
+    error FeatureDisabled();
+
+    error InsufficientTreasuryBalance();
+
+    error RecipientCanNotBeZeroAddress();
+
+    error BuybackTokenNotInMarket();
+
+    error InsufficientAmountOut(uint256, uint256);
+
+    error TokenTransferFailed(address);
+
+    error NotGuardian();
+
+    error TokenNotInMarket(address);
+
+    error InsufficientInsuranceBalance();
+
+    error MarketNotFlagged(uint8);
+
+    error CreatorNotAllowed();
+
+    error InvalidCreationMode();
+
+    error IncorrectListingFee(uint256, uint256);
+
+    error RateOutOfBounds(uint256, uint256, uint256);
+
+    error InvalidRateBounds();
+
+    error EffectiveTimeNotInFuture(uint64);
+
+    error InvalidDecayMode();
+
+    error InvalidRateDecay();
+
+    error SplitLegPairMismatch(uint256);
+
+    error InvalidBasket();
+
+    error QuoteTokenNotAccepted(address);
+
+    error BasketSoldCapReached(uint256, uint256);
+
+    error NotBasketCreator();
+
+    error VaultCallFailed(address);
+
+    error MarketHasReserves();
+
+    error InvalidSwapData();
+
+    error SwapCallbackFailed(address);
+
+    error HookNotApproved(address);
+
+    error HookRejected(address);
+
+    error NotFeeCollector();
+
+    error NotCompliant(address);
+
+    error ContractCallersNotAllowed(address);
+
+    error ExternalCallFailed(address, bytes);
+
+    error InvariantViolated();
+
+    error NotRateOperator();
+
+    error NotL1Governor();
+
+    error L1MessageNotAllowed(bytes4);
+
+    error WithdrawalDelayed(uint64);
+
+    error NoPendingWithdrawal();
+
+    error WithdrawalNotReady(uint64);
+
+    error LiquidityLocked(uint256);
+
+    error InvalidVoucher();
+
+    error VoucherExpired(uint64);
+
+    error VoucherUsed(uint256);
+
+    error VoucherAmountExceeded(uint256);
+
+    error PurchaseCapExceeded(uint256);
+
+    error InvalidMerkleProof();
+
+    error PresaleAccessDenied(address);
+
+    error SaleNotOpen(uint8);
+
+    error SaleStillOpen(uint64);
+
+    error SaleNotFailed();
+
+    error InvalidSaleCaps();
+
+    error HardCapExceeded(uint256);
+
+    error SaleNotSucceeded(uint8);
+
+    error AirdropInProgress();
+
+    error AirdropClaimsClosed(uint64);
+
+    error AirdropClaimsOpen(uint64);
+
+    error AirdropAlreadyClaimed(address);
+
+    error InvalidTriggerCondition();
+
+    error TriggerAlreadyReached(uint256);
+
+    error TriggerNotReached(uint256);
+
+    error OrderNotOpen(uint256);
+
+    error NotOrderMaker();
+
+    error OrderMarketMismatch(uint256);
+
+    error InvalidKeeperTipMode();
+
+    error InvalidKeeperTipBounds();
+
+    error RecipientNotAllowed(address);
+
+    error InvalidDepositTolerance();
+
+    error DepositRatioMismatch(uint256, uint256);
+
+    error NotPendingCreator();
+
+    error CreatorCanNotBeZeroAddress();
+
+    error FeeTierNotFound(uint64);
+
+    error MarketFeeTiered(uint64);
+
+    error SwapTooOld(uint64);
+
+    error RateChangeTooLarge(uint256, uint256, uint16);
+
+    error ContractSunset();
+
+    error InvalidSolvencyTolerance();
+
+    error SameMarket();
+
+    error InvalidDirectionMode();
+
+    error DirectionDisabled(uint8);
+
+    error CommitmentNotExpired(uint64);
+
+    error AfterSwapHookRejected(address);
+
+    error RebasingMarketNotSupported();
+
+    error InvalidOrderExpiry();
+
+    error OrderExpired(uint256);
+
+    error InvalidFillAmount(uint256, uint256);
+}
@@ -0,0 +1,68 @@
+//!
+//! Storage layout checks.
+//!
+//! Upgrades behind a proxy rely on the layout below staying put.
+//!
+
+use stylus_dorg_task::{
+    AdminAction, Basket, Contract, Market, RateDecay, RebasingShares, SwapCommitment, UserStats,
+    CONTRACT_STORAGE_SLOTS, MARKET_STORAGE_SLOTS,
+};
+use stylus_sdk::storage::StorageType;
+
+#[test]
+fn contract_keeps_its_reserved_slots() {
+    assert_eq!(
+        <Contract as StorageType>::REQUIRED_SLOTS,
+        CONTRACT_STORAGE_SLOTS
+    );
+    assert_eq!(CONTRACT_STORAGE_SLOTS, 256);
+}
+
+#[test]
+fn market_packs_small_fields() {
+    // base_token + expires_at + fee_bps + status + commit_reveal, quote_token + decimals + flags,
+    // creator + pending_rate_at, exchange_rate, market_id, base_reserve, quote_reserve,
+    // min_rate, max_rate, pending_rate.
+    assert_eq!(
+        <Market as StorageType>::REQUIRED_SLOTS,
+        MARKET_STORAGE_SLOTS
+    );
+    assert_eq!(MARKET_STORAGE_SLOTS, 10);
+}
+
+#[test]
+fn swap_commitment_packs_committed_block() {
+    // committer + committed_block, deposit.
+    assert_eq!(<SwapCommitment as StorageType>::REQUIRED_SLOTS, 2);
+}
+
+#[test]
+fn admin_action_layout() {
+    // proposer, data, executed, confirmed.
+    assert_eq!(<AdminAction as StorageType>::REQUIRED_SLOTS, 4);
+}
+
+#[test]
+fn user_stats_layout() {
+    // swap_count, base_in, quote_in, base_out, quote_out.
+    assert_eq!(<UserStats as StorageType>::REQUIRED_SLOTS, 5);
+}
+
+#[test]
+fn rate_decay_packs_its_schedule() {
+    // mode + start_time + duration, start_rate, floor_rate.
+    assert_eq!(<RateDecay as StorageType>::REQUIRED_SLOTS, 3);
+}
+
+#[test]
+fn rebasing_shares_layout() {
+    // base_shares, quote_shares.
+    assert_eq!(<RebasingShares as StorageType>::REQUIRED_SLOTS, 2);
+}
+
+#[test]
+fn basket_layout() {
+    // base_token, creator, base_reserve, sold, sold_cap, quote_tokens, rates, quote_reserves.
+    assert_eq!(<Basket as StorageType>::REQUIRED_SLOTS, 8);
+}